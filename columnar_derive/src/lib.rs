@@ -4,27 +4,61 @@ use proc_macro::TokenStream;
 use quote::quote;
 use syn::{parse_macro_input, DeriveInput};
 
-#[proc_macro_derive(Columnar)]
+#[proc_macro_derive(Columnar, attributes(columnar))]
 pub fn derive(input: TokenStream) -> TokenStream {
 
     let ast = parse_macro_input!(input as DeriveInput);
     let name = &ast.ident;
 
+    let mut transparent = false;
+    // Predicates replacing the derived `Field: Columnar` bounds, as in `#[columnar(bound = "T: Columnar")]`.
+    let mut bound: Option<Vec<syn::WherePredicate>> = None;
+    for attr in ast.attrs.iter().filter(|attr| attr.path().is_ident("columnar")) {
+        let parsed = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("transparent") { transparent = true; Ok(()) }
+            else if meta.path.is_ident("bound") {
+                let predicates = meta.value()?.parse::<syn::LitStr>()?;
+                let predicates = predicates.parse_with(syn::punctuated::Punctuated::<syn::WherePredicate, syn::Token![,]>::parse_terminated)?;
+                bound = Some(predicates.into_iter().collect());
+                Ok(())
+            }
+            else { Err(meta.error("unrecognized columnar attribute")) }
+        });
+        if let Err(error) = parsed { return error.to_compile_error().into(); }
+    }
+    // The integer type of an explicit `#[repr(..)]`, with which fieldless enums record their discriminants.
+    let mut repr: Option<syn::Ident> = None;
+    for attr in ast.attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        let _ = attr.parse_nested_meta(|meta| {
+            let integers = ["u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64"];
+            if let Some(ident) = meta.path.get_ident().filter(|ident| integers.iter().any(|int| ident == int)) {
+                repr = Some(ident.clone());
+            }
+            Ok(())
+        });
+    }
+    if transparent {
+        return match ast.data {
+            syn::Data::Struct(data_struct) => derive_transparent(name, &ast.generics, data_struct, ast.vis),
+            _ => syn::Error::new_spanned(name, "`#[columnar(transparent)]` is only supported for structs").to_compile_error().into(),
+        };
+    }
+
     match ast.data {
         syn::Data::Struct(data_struct) => {
             match data_struct.fields {
                 syn::Fields::Unit => derive_unit_struct(name, &ast.generics, ast.vis),
-                _ => derive_struct(name, &ast.generics, data_struct, ast.vis),
+                _ => derive_struct(name, &ast.generics, data_struct, ast.vis, bound.as_deref()),
             }
         }
         syn::Data::Enum(data_enum) => {
-            derive_enum(name, &ast.generics, data_enum, ast.vis)
+            derive_enum(name, &ast.generics, data_enum, ast.vis, bound.as_deref(), repr.as_ref())
         }
         syn::Data::Union(_) => unimplemented!("Unions are unsupported by Columnar"),
     }
 }
 
-fn derive_struct(name: &syn::Ident, generics: &syn::Generics, data_struct: syn::DataStruct, vis: syn::Visibility) -> proc_macro::TokenStream {
+fn derive_struct(name: &syn::Ident, generics: &syn::Generics, data_struct: syn::DataStruct, vis: syn::Visibility, bound: Option<&[syn::WherePredicate]>) -> proc_macro::TokenStream {
 
     let c_name = format!("{}Container", name);
     let c_ident = syn::Ident::new(&c_name, name.span());
@@ -50,22 +84,65 @@ fn derive_struct(name: &syn::Ident, generics: &syn::Generics, data_struct: syn::
         _ => unimplemented!(),
     };
 
+    // The order of the fields' columns when serialized and compared, from `#[columnar(order = N)]` if present,
+    // and the fields' tags for `Tagged`, from `#[columnar(tag = N)]` if present.
+    let (layout, tags) = match field_attributes(&data_struct.fields) {
+        Ok(attributes) => attributes,
+        Err(error) => return error.to_compile_error().into(),
+    };
+    let layout_names = &layout.iter().map(|index| &names[*index]).collect::<Vec<_>>();
+
+    // Fields of type `Vec<#name>` are recursive, and are recorded as lists of node indices into the container itself.
+    let recursive: &Vec<bool> = &types.iter().map(|ty| is_recursive(ty, name)).collect();
+    let any_recursive = recursive.iter().any(|r| *r);
+    for (ty, rec) in types.iter().zip(recursive.iter()) {
+        if !rec && mentions(ty, name) {
+            return syn::Error::new_spanned(ty, format!("recursive fields are only supported as `Vec<{}>`", name)).to_compile_error().into();
+        }
+    }
+    if any_recursive && tags.is_some() {
+        return syn::Error::new_spanned(name, "`#[columnar(tag = N)]` is not supported for recursive structs").to_compile_error().into();
+    }
+    // The types whose containers record each field.
+    let storage: &Vec<_> = &types.iter().zip(recursive.iter()).map(|(ty, rec)| {
+        if *rec { quote! { Vec<u64> } } else { quote! { #ty } }
+    }).collect();
+
     // Generic type parameters for the containers for the struct fields.
     let container_types = &names.iter().enumerate().map(|(index, name)| {
         let new_name = format!("C{}", index);
         syn::Ident::new(&new_name, name.span())
     }).collect::<Vec<_>>();
 
+    // The columns of recursive structs record nodes, of which rows are those whose indices are in `roots`.
+    // The children of a row are nodes but not rows, so that pushing a tree adds one row.
+    // The names, types, and generic type parameters of all columns, including `roots`.
+    let roots = &syn::Ident::new("roots", name.span());
+    let roots_type = &syn::Ident::new("CR", name.span());
+    let all_names = &names.iter().chain(any_recursive.then_some(roots)).collect::<Vec<_>>();
+    let all_types = &container_types.iter().chain(any_recursive.then_some(roots_type)).collect::<Vec<_>>();
+    let all_storage = &storage.iter().cloned().chain(any_recursive.then(|| quote! { u64 })).collect::<Vec<_>>();
+    let all_layout_names = &layout_names.iter().copied().chain(any_recursive.then_some(roots)).collect::<Vec<_>>();
+    // The container of owned rows, whose roots are a `Vec<u64>` if recursive.
+    let owned_roots = if any_recursive { quote! { Vec<u64> } } else { quote! { } };
+
     // The container struct is a tuple of containers, named to correspond with fields.
     let container_struct = {
+        let roots_field = if any_recursive {
+            quote! {
+                /// Container for the node of each row.
+                pub roots: CR,
+            }
+        } else { quote! { } };
         quote! {
             /// Derived columnar container for a struct.
-            #[derive(Copy, Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
-            #vis struct #c_ident < #(#container_types),* >{
+            #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+            #vis struct #c_ident < #(#all_types),* >{
                 #(
                     /// Container for #names.
                     pub #names : #container_types,
                 )*
+                #roots_field
             }
         }
     };
@@ -79,15 +156,40 @@ fn derive_struct(name: &syn::Ident, generics: &syn::Generics, data_struct: syn::
     
         let ty_gen = quote! { < #(#reference_types),* > };
 
+        // References print as the struct they reference would.
+        // Recursive fields print their rows, which are references of this type, so need no `Debug` bound.
+        let name_str = name.to_string();
+        let fields = &names.iter().zip(recursive.iter()).map(|(name, rec)| {
+            if *rec { quote! { &(0 .. ::columnar::Len::len(&self.#name)).map(|index| ::columnar::Index::get(&self.#name, index)).collect::<Vec<_>>() } }
+            else    { quote! { &self.#name } }
+        }).collect::<Vec<_>>();
+        let debug_body = if named {
+            let name_strs = names.iter().map(|n| n.to_string());
+            quote! { f.debug_struct(#name_str) #( .field(#name_strs, #fields) )* .finish() }
+        }
+        else {
+            quote! { f.debug_tuple(#name_str) #( .field(#fields) )* .finish() }
+        };
+        let debug_bounds = reference_types.iter().zip(recursive.iter()).map(|(r, rec)| {
+            if *rec { quote! { #r: ::columnar::Index<Ref = #r_ident #ty_gen> + ::columnar::Len } }
+            else    { quote! { #r: ::std::fmt::Debug } }
+        });
+
         quote! {
             /// Derived columnar reference for a struct.
-            #[derive(Copy, Clone, Debug)]
+            #[derive(Copy, Clone, PartialEq, Eq, serde::Serialize)]
             #vis struct #r_ident #ty_gen {
                 #(
                     /// Field for #names.
                     pub #names : #reference_types,
                 )*
             }
+
+            impl #ty_gen ::std::fmt::Debug for #r_ident #ty_gen where #(#debug_bounds),* {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    #debug_body
+                }
+            }
         }
     };
 
@@ -103,32 +205,135 @@ fn derive_struct(name: &syn::Ident, generics: &syn::Generics, data_struct: syn::
         let struct_generics = generics.params.iter();
         let impl_gen = quote! { < #(#struct_generics,)* #(#reference_types),* > };
 
-        let where_clause = quote! { where #(#reference_types: PartialEq<#types>),* };
+        let bounds = reference_types.iter().zip(types.iter()).zip(recursive.iter()).map(|((r, ty), rec)| {
+            if *rec { quote! { #r: ::columnar::Index<Ref = #r_ident < #(#reference_types),* >> + ::columnar::Len } }
+            else    { quote! { #r: PartialEq<#ty> } }
+        });
+        let where_clause = quote! { where #(#bounds),* };
 
         // Either use curly braces or parentheses to destructure the item.
         let destructure_self =
         if named { quote! { let #name { #(#names),* } = other; } }
         else     { quote! { let #name ( #(#names),* ) = other; } };
 
+        let compare = names.iter().zip(recursive.iter()).map(|(name, rec)| {
+            if *rec { quote! { (::columnar::Len::len(&self.#name) == <[_]>::len(#name) && <[_]>::iter(#name).enumerate().all(|(index, item)| ::columnar::Index::get(&self.#name, index) == *item)) } }
+            else    { quote! { self.#name == *#name } }
+        });
+
         quote! {
             impl #impl_gen PartialEq<#name #ty_gen> for #r_ident < #(#reference_types),* >  #where_clause {
                 fn eq(&self, other: &#name #ty_gen) -> bool {
                     #destructure_self
-                    #(self.#names == *#names) &&*
+                    #(#compare) &&*
+                }
+            }
+        }
+
+    };
+
+    // Recursive fields push their nodes before the node that contains them, and then push the node indices.
+    // Each child is pushed as a row, whose root is then removed so that it is only a node.
+    let push_rows = &names.iter().zip(recursive.iter()).filter(|(_, rec)| **rec).map(|(name, _)| {
+        quote! { let #name: Vec<u64> = ::std::iter::IntoIterator::into_iter(#name).map(|item| { self.push(item); self.roots.pop().unwrap() }).collect(); }
+    }).collect::<Vec<_>>();
+    let first_name = &names[0];
+    let first_type = &container_types[0];
+    let push_fields = &names.iter().zip(recursive.iter()).map(|(name, rec)| {
+        if *rec { quote! { self.#name.push(&#name[..]); } }
+        else    { quote! { self.#name.push(#name); } }
+    }).collect::<Vec<_>>();
+    let (push_node, push_root) = if any_recursive {
+        (quote! { let node = ::columnar::Len::len(&self.#first_name) as u64; }, quote! { self.roots.push(node); })
+    } else { (quote! { }, quote! { }) };
+    let push_len = if any_recursive { quote! { #first_type: ::columnar::Len, } } else { quote! { } };
+    let push_bounds = |bound: &dyn Fn(&syn::Type) -> proc_macro2::TokenStream| {
+        container_types.iter().zip(types.iter()).zip(recursive.iter()).map(|((c, ty), rec)| {
+            if *rec { quote! { #c: for<'b> ::columnar::Push<&'b [u64]> } }
+            else    { let bound = bound(ty); quote! { #c: #bound } }
+        }).collect::<Vec<_>>()
+    };
+
+    let into_owned = {
+
+        let reference_types = &names.iter().enumerate().map(|(index, name)| {
+            let new_name = format!("R{}", index);
+            syn::Ident::new(&new_name, name.span())
+        }).collect::<Vec<_>>();
+
+        let (_impl_gen, ty_gen, where_clause) = generics.split_for_impl();
+
+        let struct_generics = generics.params.iter();
+        let impl_gen = quote! { < #(#struct_generics,)* #(#reference_types),* > };
+
+        let bounds = &reference_types.iter().zip(types.iter()).zip(recursive.iter()).map(|((r, ty), rec)| {
+            if *rec { quote! { #r: ::columnar::Index<Ref = #r_ident < #(#reference_types),* >> + ::columnar::Len } }
+            else    { quote! { #r: ::columnar::IntoOwned<Owned = #ty> } }
+        }).collect::<Vec<_>>();
+        let struct_where = where_clause.map(|clause| clause.predicates.iter().collect::<Vec<_>>()).unwrap_or_default();
+
+        let owned = names.iter().zip(recursive.iter()).map(|(name, rec)| {
+            if *rec { quote! { (0 .. ::columnar::Len::len(&self.#name)).map(|index| ::columnar::IntoOwned::into_owned(::columnar::Index::get(&self.#name, index))).collect() } }
+            else    { quote! { ::columnar::IntoOwned::into_owned(self.#name) } }
+        });
+
+        let build =
+        if named { quote! { #name { #(#names: #owned),* } } }
+        else     { quote! { #name ( #(#owned),* ) } };
+
+        quote! {
+            impl #impl_gen ::columnar::IntoOwned for #r_ident < #(#reference_types),* > where #(#bounds,)* #(#struct_where,)* {
+                type Owned = #name #ty_gen;
+                fn into_owned(self) -> Self::Owned {
+                    #build
+                }
+            }
+            impl #impl_gen From<#r_ident < #(#reference_types),* >> for #name #ty_gen where #(#bounds,)* #(#struct_where,)* {
+                #[inline(always)]
+                fn from(other: #r_ident < #(#reference_types),* >) -> Self {
+                    ::columnar::IntoOwned::into_owned(other)
                 }
             }
         }
+    };
+
+    // Fields are reached by name, or for tuple structs by position; recursive fields are not reached.
+    let inspect = {
+
+        let reference_types = &names.iter().enumerate().map(|(index, name)| {
+            let new_name = format!("R{}", index);
+            syn::Ident::new(&new_name, name.span())
+        }).collect::<Vec<_>>();
+
+        let bounds = reference_types.iter().zip(recursive.iter()).filter(|(_, rec)| !**rec).map(|(r, _)| {
+            quote! { #r: ::columnar::path::Inspect<'columnar> + Copy + 'columnar }
+        });
+        let arms = names.iter().enumerate().zip(recursive.iter()).filter(|(_, rec)| !**rec).map(|((index, name), _)| {
+            let name_str = if named { name.to_string() } else { index.to_string() };
+            quote! { #name_str => Some(Box::new(self.#name)), }
+        });
 
+        quote! {
+            impl <'columnar, #(#reference_types),* > ::columnar::path::Inspect<'columnar> for #r_ident < #(#reference_types),* > where #(#bounds,)* {
+                fn field(&self, name: &str) -> Option<Box<dyn ::columnar::path::Inspect<'columnar> + 'columnar>> {
+                    match name {
+                        #(#arms)*
+                        _ => None,
+                    }
+                }
+            }
+        }
     };
 
     let push_own = { 
         let (_impl_gen, ty_gen, _where_clause) = generics.split_for_impl();
-        let push = names.iter().map(|name| { quote! { self.#name.push(#name); } });
+        let push = push_fields;
         
         let struct_generics = generics.params.iter();
         let impl_gen = quote! { < #(#struct_generics,)* #(#container_types),* > };
 
-        let where_clause2 = quote! { where #(#container_types: ::columnar::Push<#types>),* };
+        let bounds = push_bounds(&|ty| quote! { ::columnar::Push<#ty> });
+        let where_clause2 = quote! { where #push_len #(#bounds),* };
 
         // Either use curly braces or parentheses to destructure the item.
         let destructure_self = 
@@ -136,10 +341,13 @@ fn derive_struct(name: &syn::Ident, generics: &syn::Generics, data_struct: syn::
         else     { quote! { let #name ( #(#names),* ) = item; } };
 
         quote! {
-            impl #impl_gen ::columnar::Push<#name #ty_gen> for #c_ident < #(#container_types),* >  #where_clause2 {
+            impl #impl_gen ::columnar::Push<#name #ty_gen> for #c_ident < #(#container_types,)* #owned_roots >  #where_clause2 {
                 fn push(&mut self, item: #name #ty_gen) {
                     #destructure_self
+                    #(#push_rows)*
+                    #push_node
                     #(#push)*
+                    #push_root
                 }
             }
         }
@@ -147,22 +355,26 @@ fn derive_struct(name: &syn::Ident, generics: &syn::Generics, data_struct: syn::
 
     let push_ref = { 
         let (_impl_gen, ty_gen, _where_clause) = generics.split_for_impl();
-        let push = names.iter().map(|name| { quote! { self.#name.push(#name); } });
+        let push = push_fields;
         
         let struct_generics = generics.params.iter();
         let impl_gen = quote! { < 'columnar, #(#struct_generics,)* #(#container_types),* > };
 
-        let where_clause2 = quote! { where #(#container_types: ::columnar::Push<&'columnar #types>),* };
+        let bounds = push_bounds(&|ty| quote! { ::columnar::Push<&'columnar #ty> });
+        let where_clause2 = quote! { where #push_len #(#bounds),* };
 
         let destructure_self = 
         if named { quote! { let #name { #(#names),* } = item; } }
         else     { quote! { let #name ( #(#names),* ) = item; } };
 
         quote! {
-            impl #impl_gen ::columnar::Push<&'columnar #name #ty_gen> for #c_ident < #(#container_types),* >  #where_clause2 {
+            impl #impl_gen ::columnar::Push<&'columnar #name #ty_gen> for #c_ident < #(#container_types,)* #owned_roots >  #where_clause2 {
                 fn push(&mut self, item: &'columnar #name #ty_gen) {
                     #destructure_self
+                    #(#push_rows)*
+                    #push_node
                     #(#push)*
+                    #push_root
                 }
             }
         }
@@ -176,54 +388,118 @@ fn derive_struct(name: &syn::Ident, generics: &syn::Generics, data_struct: syn::
             syn::Ident::new(&new_name, name.span())
         }).collect::<Vec<_>>();
 
-        let push = names.iter().map(|name| { quote! { self.#name.push(#name); } });
+        let push = push_fields;
+        let push_rows = names.iter().zip(recursive.iter()).filter(|(_, rec)| **rec).map(|(name, _)| {
+            quote! { let #name: Vec<u64> = (0 .. ::columnar::Len::len(&#name)).map(|index| { self.push(::columnar::Index::get(&#name, index)); self.roots.pop().unwrap() }).collect(); }
+        });
         
         let impl_gen = quote! { < #(#container_types,)* #(#reference_types),* > };
 
-        let where_clause = quote! { where #(#container_types: ::columnar::Push<#reference_types>),* };
-
         let index_type = quote! { #r_ident < #(#reference_types,)* > };
+        let bounds = container_types.iter().zip(reference_types.iter()).zip(recursive.iter()).map(|((c, r), rec)| {
+            if *rec { quote! { #c: for<'b> ::columnar::Push<&'b [u64]>, #r: ::columnar::Index<Ref = #index_type> + ::columnar::Len } }
+            else    { quote! { #c: ::columnar::Push<#r> } }
+        });
+        let where_clause = quote! { where #push_len #(#bounds),* };
+
         let destructure_self = quote! { let #r_ident { #(#names),* } = item; };
 
         quote! {
-            impl #impl_gen ::columnar::Push<#index_type> for #c_ident < #(#container_types),* > #where_clause {
+            impl #impl_gen ::columnar::Push<#index_type> for #c_ident < #(#container_types,)* #owned_roots > #where_clause {
                 fn push(&mut self, item: #index_type) {
                     #destructure_self
+                    #(#push_rows)*
+                    #push_node
                     #(#push)*
+                    #push_root
                 }
             }
         }
     };
 
+    // Recursive structs index their nodes through `IndexNode`, and their rows through `roots`.
     let index_own = {
-        let impl_gen = quote! { < #(#container_types),* > };
-        let ty_gen = quote! { < #(#container_types),* > };
-        let where_clause = quote! { where #(#container_types: ::columnar::Index),* };
-
-        let index_type = quote! { #r_ident < #(<#container_types as ::columnar::Index>::Ref,)* > };
+        let impl_gen = quote! { < #(#all_types),* > };
+        let ty_gen = quote! { < #(#all_types),* > };
+        let copy = if any_recursive { quote! { Self: Copy, } } else { quote! { } };
+        let where_clause = quote! { where #copy #(#container_types: ::columnar::Index),* };
+
+        let ref_types = container_types.iter().zip(recursive.iter()).map(|(c, rec)| {
+            if *rec { quote! { ::columnar::Indirect<<#c as ::columnar::Index>::Ref, ::columnar::Nodes<Self>> } }
+            else    { quote! { <#c as ::columnar::Index>::Ref } }
+        });
+        let index_type = quote! { #r_ident < #(#ref_types,)* > };
+        let gets = names.iter().zip(recursive.iter()).map(|(name, rec)| {
+            if *rec { quote! { ::columnar::Indirect::new(::columnar::Index::get(&self.#name, index), ::columnar::Nodes(*self)) } }
+            else    { quote! { self.#name.get(index) } }
+        });
 
-        quote! {
-            impl #impl_gen ::columnar::Index for #c_ident #ty_gen #where_clause {
-                type Ref = #index_type;
-                fn get(&self, index: usize) -> Self::Ref {
-                    #r_ident { #(#names: self.#names.get(index),)* }
+        if any_recursive {
+            quote! {
+                impl #impl_gen ::columnar::IndexNode for #c_ident #ty_gen #where_clause {
+                    type Ref = #index_type;
+                    fn get_node(&self, index: usize) -> Self::Ref {
+                        #r_ident { #(#names: #gets,)* }
+                    }
+                }
+                impl #impl_gen ::columnar::Index for #c_ident #ty_gen #where_clause, CR: ::columnar::IndexAs<u64> {
+                    type Ref = #index_type;
+                    fn get(&self, index: usize) -> Self::Ref {
+                        ::columnar::IndexNode::get_node(self, ::columnar::IndexAs::index_as(&self.roots, index) as usize)
+                    }
+                }
+            }
+        }
+        else {
+            quote! {
+                impl #impl_gen ::columnar::Index for #c_ident #ty_gen #where_clause {
+                    type Ref = #index_type;
+                    fn get(&self, index: usize) -> Self::Ref {
+                        #r_ident { #(#names: #gets,)* }
+                    }
                 }
             }
         }
     };
 
     let index_ref = {
-        let impl_gen = quote! { < 'columnar, #(#container_types),* > };
-        let ty_gen = quote! { < #(#container_types),* > };
+        let impl_gen = quote! { < 'columnar, #(#all_types),* > };
+        let ty_gen = quote! { < #(#all_types),* > };
         let where_clause = quote! { where #(&'columnar #container_types: ::columnar::Index),* };
 
-        let index_type = quote! { #r_ident < #(<&'columnar #container_types as ::columnar::Index>::Ref,)* > };
+        let ref_types = container_types.iter().zip(recursive.iter()).map(|(c, rec)| {
+            if *rec { quote! { ::columnar::Indirect<<&'columnar #c as ::columnar::Index>::Ref, ::columnar::Nodes<&'columnar #c_ident #ty_gen>> } }
+            else    { quote! { <&'columnar #c as ::columnar::Index>::Ref } }
+        });
+        let index_type = quote! { #r_ident < #(#ref_types,)* > };
+        let gets = names.iter().zip(recursive.iter()).map(|(name, rec)| {
+            if *rec { quote! { ::columnar::Indirect::new(::columnar::Index::get(&&self.#name, index), ::columnar::Nodes(*self)) } }
+            else    { quote! { ::columnar::Index::get(&&self.#name, index) } }
+        });
 
-        quote! {
-            impl #impl_gen ::columnar::Index for &'columnar #c_ident #ty_gen #where_clause {
-                type Ref = #index_type;
-                fn get(&self, index: usize) -> Self::Ref {
-                    #r_ident { #(#names: (&self.#names).get(index),)* }
+        if any_recursive {
+            quote! {
+                impl #impl_gen ::columnar::IndexNode for &'columnar #c_ident #ty_gen #where_clause {
+                    type Ref = #index_type;
+                    fn get_node(&self, index: usize) -> Self::Ref {
+                        #r_ident { #(#names: #gets,)* }
+                    }
+                }
+                impl #impl_gen ::columnar::Index for &'columnar #c_ident #ty_gen #where_clause, CR: ::columnar::IndexAs<u64> {
+                    type Ref = #index_type;
+                    fn get(&self, index: usize) -> Self::Ref {
+                        ::columnar::IndexNode::get_node(self, ::columnar::IndexAs::index_as(&self.roots, index) as usize)
+                    }
+                }
+            }
+        }
+        else {
+            quote! {
+                impl #impl_gen ::columnar::Index for &'columnar #c_ident #ty_gen #where_clause {
+                    type Ref = #index_type;
+                    fn get(&self, index: usize) -> Self::Ref {
+                        #r_ident { #(#names: #gets,)* }
+                    }
                 }
             }
         }
@@ -231,29 +507,101 @@ fn derive_struct(name: &syn::Ident, generics: &syn::Generics, data_struct: syn::
 
     let clear = { 
 
-        let impl_gen = quote! { < #(#container_types),* > };
-        let ty_gen = quote! { < #(#container_types),* > };
-        let where_clause = quote! { where #(#container_types: ::columnar::Clear),* };
+        let impl_gen = quote! { < #(#all_types),* > };
+        let ty_gen = quote! { < #(#all_types),* > };
+        let where_clause = quote! { where #(#all_types: ::columnar::Clear),* };
 
         quote! {
             impl #impl_gen ::columnar::Clear for #c_ident #ty_gen #where_clause {
-                fn clear(&mut self) { #(self.#names.clear());* }
+                fn clear(&mut self) { #(self.#all_names.clear());* }
             }
         }
     };
 
+    // Permuting the rows of recursive structs permutes their roots, and leaves their nodes in place.
+    let permute = {
+
+        let impl_gen = quote! { < #(#all_types),* > };
+        let ty_gen = quote! { < #(#all_types),* > };
+        let permuted = if any_recursive { vec![roots] } else { names.iter().collect() };
+        let permuted_types = if any_recursive { vec![roots_type] } else { container_types.iter().collect() };
+        let where_clause = quote! { where #(#permuted_types: ::columnar::Permute),* };
+
+        quote! {
+            impl #impl_gen ::columnar::Permute for #c_ident #ty_gen #where_clause {
+                fn permute(&mut self, perm: &[usize]) { #(self.#permuted.permute(perm);)* }
+            }
+        }
+    };
+
+    // Rows of recursive structs are pushed, which copies their nodes with indices into `self`.
+    let extend_from_range = {
+
+        let impl_gen = quote! { < #(#all_types),* > };
+        let ty_gen = quote! { < #(#all_types),* > };
+
+        if any_recursive {
+            quote! {
+                impl #impl_gen ::columnar::ExtendFromRange for #c_ident #ty_gen where for<'columnar> &'columnar Self: ::columnar::Index, for<'columnar> Self: ::columnar::Push<<&'columnar Self as ::columnar::Index>::Ref> {
+                    fn extend_from_range(&mut self, other: &Self, range: ::std::ops::Range<usize>) {
+                        for index in range { ::columnar::Push::push(self, ::columnar::Index::get(&other, index)); }
+                    }
+                }
+            }
+        }
+        else {
+            let where_clause = quote! { where #(#container_types: ::columnar::ExtendFromRange),* };
+            quote! {
+                impl #impl_gen ::columnar::ExtendFromRange for #c_ident #ty_gen #where_clause {
+                    fn extend_from_range(&mut self, other: &Self, range: ::std::ops::Range<usize>) {
+                        #(self.#names.extend_from_range(&other.#names, range.clone());)*
+                    }
+                }
+            }
+        }
+    };
+
+    let compare_columns = {
+
+        let impl_gen = quote! { < #(#all_types),* > };
+        let ty_gen = quote! { < #(#all_types),* > };
+        let roots_bound = if any_recursive { quote! { CR: ::columnar::IndexAs<u64>, } } else { quote! { } };
+        let where_clause = quote! { where #roots_bound #(#container_types: ::columnar::Index, <#container_types as ::columnar::Index>::Ref: Ord),* };
+        // The rows of recursive structs are compared by their nodes.
+        let nodes = if any_recursive {
+            quote! { let (i, j) = (::columnar::IndexAs::index_as(&self.roots, i) as usize, ::columnar::IndexAs::index_as(&self.roots, j) as usize); }
+        } else { quote! { } };
+
+        let count = names.len();
+        let numbers = 0 .. names.len();
+
+        quote! {
+            impl #impl_gen ::columnar::sort::CompareColumns for #c_ident #ty_gen #where_clause {
+                fn columns(&self) -> usize { #count }
+                fn compare_column(&self, column: usize, i: usize, j: usize) -> ::std::cmp::Ordering {
+                    use ::columnar::Index;
+                    #nodes
+                    match column {
+                        #( #numbers => self.#layout_names.get(i).cmp(&self.#layout_names.get(j)), )*
+                        _ => panic!("column {} out of bounds for {} columns", column, #count),
+                    }
+                }
+            }
+        }
+    };
+
+    // The rows of recursive structs are their roots.
     let length = { 
 
-        let impl_gen = quote! { < #(#container_types),* > };
-        let ty_gen = quote! { < #(#container_types),* > };
-        let where_clause = quote! { where #(#container_types: ::columnar::Len),* };
+        let impl_gen = quote! { < #(#all_types),* > };
+        let ty_gen = quote! { < #(#all_types),* > };
+        let rows = if any_recursive { roots } else { first_name };
+        let where_clause = if any_recursive { quote! { where CR: ::columnar::Len } } else { quote! { where #(#container_types: ::columnar::Len),* } };
 
-        let first_name = &names[0];
-        
         quote! {
             impl #impl_gen ::columnar::Len for #c_ident #ty_gen #where_clause {
                 fn len(&self) -> usize {
-                    self.#first_name.len()
+                    self.#rows.len()
                 }
             }
         }
@@ -261,78 +609,108 @@ fn derive_struct(name: &syn::Ident, generics: &syn::Generics, data_struct: syn::
 
     let as_bytes = { 
 
-        let impl_gen = quote! { <'a, #(#container_types),* > };
-        let ty_gen = quote! { < #(#container_types),* > };
-        let where_clause = quote! { where #(#container_types: ::columnar::AsBytes<'a>),* };
+        let impl_gen = quote! { <'a, #(#all_types),* > };
+        let ty_gen = quote! { < #(#all_types),* > };
+        let where_clause = quote! { where #(#all_types: ::columnar::AsBytes<'a>),* };
         
         quote! {
             impl #impl_gen ::columnar::AsBytes<'a> for #c_ident #ty_gen #where_clause {
                 // type Borrowed<'columnar> = #c_ident < #(<#container_types as ::columnar::AsBytes>::Borrowed<'columnar>,)*>;
                 fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
                     let iter = None.into_iter();
-                    #( let iter = iter.chain(self.#names.as_bytes()); )*
+                    #( let iter = iter.chain(self.#all_layout_names.as_bytes()); )*
                     iter
                 }
             }
         }
     };
 
+    // Fields are visited in the order of their columns, by name, or for tuple structs by position.
+    let layout_visit = {
+
+        let impl_gen = quote! { <'a, #(#all_types),* > };
+        let ty_gen = quote! { < #(#all_types),* > };
+        let where_clause = quote! { where #(#all_types: ::columnar::layout::Layout<'a>),* };
+        let roles = layout.iter().map(|index| if named { names[*index].to_string() } else { index.to_string() }).chain(any_recursive.then(|| roots.to_string()));
+
+        quote! {
+            impl #impl_gen ::columnar::layout::Layout<'a> for #c_ident #ty_gen #where_clause {
+                fn visit(&self, visitor: &mut impl ::columnar::layout::Visitor<'a>) {
+                    #( ::columnar::layout::component(visitor, #roles, &self.#all_layout_names); )*
+                }
+            }
+        }
+    };
+
     let from_bytes = { 
 
-        let impl_gen = quote! { < 'columnar, #(#container_types),* > };
-        let ty_gen = quote! { < #(#container_types),* > };
-        let where_clause = quote! { where #(#container_types: ::columnar::FromBytes<'columnar>),* };
+        let impl_gen = quote! { < 'columnar, #(#all_types),* > };
+        let ty_gen = quote! { < #(#all_types),* > };
+        let where_clause = quote! { where #(#all_types: ::columnar::FromBytes<'columnar>),* };
         
         quote! {
             impl #impl_gen ::columnar::FromBytes<'columnar> for #c_ident #ty_gen #where_clause {
                 fn from_bytes(bytes: &mut impl Iterator<Item=&'columnar [u8]>) -> Self {
-                    #(let #names = ::columnar::FromBytes::from_bytes(bytes);)*
-                    Self { #(#names,)* }
+                    #(let #all_layout_names = ::columnar::FromBytes::from_bytes(bytes);)*
+                    Self { #(#all_names,)* }
                 }
             }
         }
     };
-
     let columnar_impl = {
 
         let (impl_gen, ty_gen, where_clause) = generics.split_for_impl();
 
-        let where_clause2 = if let Some(struct_where) = where_clause {
-            let params = struct_where.predicates.iter(); 
-            quote! {  where #(#types : ::columnar::Columnar,)* #(#params),* }
-        }
-        else {
-            quote! { where #(#types : ::columnar::Columnar,)* }
+        let params = where_clause.map(|clause| clause.predicates.iter().collect::<Vec<_>>()).unwrap_or_default();
+        let where_clause2 = match bound {
+            Some(bound) => quote! { where #(#bound,)* #(#params),* },
+            None => quote! { where #(#storage : ::columnar::Columnar,)* #(#params),* },
         };
     
         // Either use curly braces or parentheses to destructure the item.
         let destructure_self = 
         if named { quote! { let #name { #(#names),* } = self; } }
         else     { quote! { let #name ( #(#names),* ) = self; } };
+
+        // Recursive fields are read out of the indicated rows of the container.
+        let owned = names.iter().zip(recursive.iter()).map(|(name, rec)| {
+            if *rec { quote! { (0 .. ::columnar::Len::len(&other.#name)).map(|index| Self::into_owned(::columnar::Index::get(&other.#name, index))).collect() } }
+            else    { quote! { ::columnar::Columnar::into_owned(other.#name) } }
+        }).collect::<Vec<_>>();
+        let copy_from = names.iter().zip(recursive.iter()).zip(owned.iter()).map(|((name, rec), owned)| {
+            if *rec { quote! { *#name = #owned; } }
+            else    { quote! { ::columnar::Columnar::copy_from(#name, other.#name); } }
+        });
         
         // Either use curly braces or parentheses to destructure the item.
         let into_self =
-        if named { quote! { #name { #(#names: ::columnar::Columnar::into_owned(other.#names)),* } } }
-        else     { quote! { #name ( #(::columnar::Columnar::into_owned(other.#names)),* ) } };
+        if named { quote! { #name { #(#names: #owned),* } } }
+        else     { quote! { #name ( #(#owned),* ) } };
+
+        let borrowed = quote! { #c_ident < #(<<#all_storage as ::columnar::Columnar>::Container as ::columnar::Container<#all_storage>>::Borrowed<'a> ),* > };
+        let ref_types = storage.iter().zip(recursive.iter()).map(|(ty, rec)| {
+            if *rec { quote! { ::columnar::Indirect<<#ty as ::columnar::Columnar>::Ref<'a>, ::columnar::Nodes<#borrowed>> } }
+            else    { quote! { <#ty as ::columnar::Columnar>::Ref<'a> } }
+        });
 
         quote! {
             impl #impl_gen ::columnar::Columnar for #name #ty_gen #where_clause2 {
-                type Ref<'a> = #r_ident < #(<#types as ::columnar::Columnar>::Ref<'a>,)* > where #(#types: 'a,)*;
+                type Ref<'a> = #r_ident < #(#ref_types,)* > where #(#types: 'a,)*;
                 fn copy_from<'a>(&mut self, other: Self::Ref<'a>) {
                     #destructure_self
-                    #( ::columnar::Columnar::copy_from(#names, other.#names); )*
+                    #( #copy_from )*
                 }
                 fn into_owned<'a>(other: Self::Ref<'a>) -> Self {
                     #into_self
                 }
-                type Container = #c_ident < #(<#types as ::columnar::Columnar>::Container ),* >;
+                type Container = #c_ident < #(<#all_storage as ::columnar::Columnar>::Container ),* >;
             }
 
-            impl #impl_gen ::columnar::Container<#name #ty_gen> for #c_ident < #(<#types as ::columnar::Columnar>::Container ),* > #where_clause2 {
-                type Borrowed<'a> = #c_ident < #(<<#types as ::columnar::Columnar>::Container as ::columnar::Container<#types>>::Borrowed<'a> ),* > where #(#types: 'a,)*;
+            impl #impl_gen ::columnar::Container<#name #ty_gen> for #c_ident < #(<#all_storage as ::columnar::Columnar>::Container ),* > #where_clause2 {
+                type Borrowed<'a> = #borrowed where #(#types: 'a,)*;
                 fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
                     #c_ident {
-                        #( #names: <<#types as ::columnar::Columnar>::Container as ::columnar::Container<#types>>::borrow(&self.#names), )*
+                        #( #all_names: <<#all_storage as ::columnar::Columnar>::Container as ::columnar::Container<#all_storage>>::borrow(&self.#all_names), )*
                     }
                 }
             }
@@ -340,12 +718,43 @@ fn derive_struct(name: &syn::Ident, generics: &syn::Generics, data_struct: syn::
     };
 
 
+    let tagged = match &tags {
+        None => quote! { },
+        Some(tags) => {
+            let (impl_gen, ty_gen, where_clause) = generics.split_for_impl();
+            let params = where_clause.map(|clause| clause.predicates.iter().collect::<Vec<_>>()).unwrap_or_default();
+            let bounds = match bound {
+                Some(bound) => quote! { #(#bound,)* },
+                None => quote! { #(#types : ::columnar::Columnar,)* },
+            };
+            quote! {
+                impl #impl_gen ::columnar::tagged::Tagged for #name #ty_gen where #bounds #(#types: Default,)* #(#params),* {
+                    fn encode_tagged(container: &<Self as ::columnar::Columnar>::Container, store: &mut Vec<u64>) {
+                        use ::columnar::AsBytes;
+                        let fields = vec![
+                            #( (#tags, <<#types as ::columnar::Columnar>::Container as ::columnar::Container<#types>>::borrow(&container.#names).as_bytes().collect::<Vec<_>>()), )*
+                        ];
+                        ::columnar::tagged::encode(store, ::columnar::Len::len(container), fields);
+                    }
+                    fn decode_tagged(store: &[u64]) -> <Self as ::columnar::Columnar>::Container {
+                        let decoder = ::columnar::tagged::Decoder::new(store);
+                        #c_ident {
+                            #( #names: decoder.field::<#types>(#tags).unwrap_or_else(|| ::columnar::tagged::defaults::<#types>(decoder.rows())), )*
+                        }
+                    }
+                }
+            }
+        }
+    };
+
     quote! {
 
         #container_struct
         #reference_struct
 
         #partial_eq
+        #into_owned
+        #inspect
 
         #push_own
         #push_ref
@@ -355,11 +764,16 @@ fn derive_struct(name: &syn::Ident, generics: &syn::Generics, data_struct: syn::
         #index_ref
         #length
         #clear
+        #permute
+        #extend_from_range
+        #compare_columns
 
         #as_bytes
         #from_bytes
+        #layout_visit
 
         #columnar_impl
+        #tagged
 
     }.into()
 }
@@ -373,7 +787,7 @@ fn derive_unit_struct(name: &syn::Ident, _generics: &syn::Generics, vis: syn::Vi
     quote! {
 
         /// Derived columnar container for a unit struct.
-        #[derive(Copy, Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
         #vis struct #c_ident<CW = u64> {
             /// Count of the number of contained records.
             pub count: CW,
@@ -411,6 +825,19 @@ fn derive_unit_struct(name: &syn::Ident, _generics: &syn::Generics, vis: syn::Vi
             }
         }
 
+        impl ::columnar::Permute for #c_ident {
+            fn permute(&mut self, perm: &[usize]) {
+                assert_eq!(perm.len() as u64, self.count);
+            }
+        }
+
+        impl ::columnar::ExtendFromRange for #c_ident {
+            fn extend_from_range(&mut self, other: &Self, range: ::std::ops::Range<usize>) {
+                assert!(range.start <= range.end && range.end as u64 <= other.count);
+                self.count += range.len() as u64;
+            }
+        }
+
         impl<CW: Copy+::columnar::common::index::CopyAs<u64>> ::columnar::Len for #c_ident<CW> {
             fn len(&self) -> usize {
                 use columnar::common::index::CopyAs;
@@ -421,16 +848,29 @@ fn derive_unit_struct(name: &syn::Ident, _generics: &syn::Generics, vis: syn::Vi
         impl<'a> ::columnar::AsBytes<'a> for #c_ident <&'a u64> {
             // type Borrowed<'columnar> = #c_ident;
             fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
-                std::iter::once((8, bytemuck::cast_slice(std::slice::from_ref(self.count))))
+                std::iter::once((8, ::columnar::bytemuck::cast_slice(std::slice::from_ref(self.count))))
+            }
+        }
+
+        impl<'a> ::columnar::layout::Layout<'a> for #c_ident <&'a u64> {
+            fn visit(&self, visitor: &mut impl ::columnar::layout::Visitor<'a>) {
+                visitor.enter("count");
+                visitor.buffer(8, ::columnar::bytemuck::cast_slice(std::slice::from_ref(self.count)));
+                visitor.leave("count");
             }
         }
 
         impl<'columnar> ::columnar::FromBytes<'columnar> for #c_ident <&'columnar u64> {
             fn from_bytes(bytes: &mut impl Iterator<Item=&'columnar [u8]>) -> Self {
-                Self { count: &bytemuck::try_cast_slice(bytes.next().unwrap()).unwrap()[0] }
+                Self { count: &::columnar::bytemuck::try_cast_slice(bytes.next().unwrap()).unwrap()[0] }
             }
         }
 
+        impl ::columnar::IntoOwned for #name {
+            type Owned = #name;
+            fn into_owned(self) -> Self::Owned { self }
+        }
+
         impl ::columnar::Columnar for #name {
             type Ref<'a> = #name;
             fn copy_from<'a>(&mut self, other: Self::Ref<'a>) { *self = other; }
@@ -448,13 +888,120 @@ fn derive_unit_struct(name: &syn::Ident, _generics: &syn::Generics, vis: syn::Vi
     }.into()
 }
 
+/// The derived container for a `#[columnar(transparent)]` struct wraps the container of its only field,
+/// so that its layout is identical to the field's. Its index `Ref` type is the field's `Ref` type.
+/// The container cannot be the field's own, which already implements `Container` for the field's type,
+/// as a second implementation would make borrowing it ambiguous. References are pushed as any type that
+/// converts into the field's type, which distinguishes them from `&#name` without naming the `Ref` type.
+fn derive_transparent(name: &syn::Ident, generics: &syn::Generics, data_struct: syn::DataStruct, vis: syn::Visibility) -> proc_macro::TokenStream {
+
+    let c_name = format!("{}Container", name);
+    let c_ident = syn::Ident::new(&c_name, name.span());
+
+    if !generics.params.is_empty() {
+        return syn::Error::new_spanned(&generics.params, "`#[columnar(transparent)]` is not supported for generic structs").to_compile_error().into();
+    }
+    let fields: Vec<_> = data_struct.fields.iter().collect();
+    if fields.len() != 1 {
+        return syn::Error::new_spanned(&data_struct.fields, "`#[columnar(transparent)]` requires exactly one field").to_compile_error().into();
+    }
+    let ty = &fields[0].ty;
+    if mentions(ty, name) {
+        return syn::Error::new_spanned(ty, "`#[columnar(transparent)]` is not supported for recursive structs").to_compile_error().into();
+    }
+    // How to read the field, and how to construct `#name` from it.
+    let (field, build) = match &fields[0].ident {
+        Some(ident) => (quote! { #ident }, quote! { #name { #ident: inner } }),
+        None => (quote! { 0 }, quote! { #name(inner) }),
+    };
+
+    quote! {
+
+        /// Derived columnar container for a transparent struct, wrapping the container of its field.
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+        #vis struct #c_ident<C = <#ty as ::columnar::Columnar>::Container>(pub C);
+
+        impl<'columnar, C: ::columnar::Push<&'columnar #ty>> ::columnar::Push<&'columnar #name> for #c_ident<C> {
+            #[inline(always)]
+            fn push(&mut self, item: &'columnar #name) { ::columnar::Push::push(&mut self.0, &item.#field) }
+        }
+        impl<R: ::columnar::IntoOwned<Owned = #ty>, C: ::columnar::Push<R>> ::columnar::Push<R> for #c_ident<C> {
+            #[inline(always)]
+            fn push(&mut self, item: R) { ::columnar::Push::push(&mut self.0, item) }
+        }
+        impl<R: ::columnar::IntoOwned<Owned = #ty>> From<R> for #name {
+            #[inline(always)]
+            fn from(other: R) -> #name {
+                let inner = other.into_owned();
+                #build
+            }
+        }
+
+        impl<C: ::columnar::Index> ::columnar::Index for #c_ident<C> {
+            type Ref = C::Ref;
+            #[inline(always)]
+            fn get(&self, index: usize) -> Self::Ref { self.0.get(index) }
+        }
+        impl<'columnar, C> ::columnar::Index for &'columnar #c_ident<C> where &'columnar C: ::columnar::Index {
+            type Ref = <&'columnar C as ::columnar::Index>::Ref;
+            #[inline(always)]
+            fn get(&self, index: usize) -> Self::Ref { (&self.0).get(index) }
+        }
+
+        impl<C: ::columnar::Len> ::columnar::Len for #c_ident<C> {
+            #[inline(always)]
+            fn len(&self) -> usize { self.0.len() }
+        }
+        impl<C: ::columnar::Clear> ::columnar::Clear for #c_ident<C> {
+            fn clear(&mut self) { self.0.clear() }
+        }
+        impl<C: ::columnar::HeapSize> ::columnar::HeapSize for #c_ident<C> {
+            fn heap_size(&self) -> (usize, usize) { self.0.heap_size() }
+        }
+        impl<C: ::columnar::Permute> ::columnar::Permute for #c_ident<C> {
+            fn permute(&mut self, perm: &[usize]) { self.0.permute(perm) }
+        }
+        impl<C: ::columnar::ExtendFromRange> ::columnar::ExtendFromRange for #c_ident<C> {
+            fn extend_from_range(&mut self, other: &Self, range: ::std::ops::Range<usize>) {
+                self.0.extend_from_range(&other.0, range)
+            }
+        }
+        impl<'columnar, C: ::columnar::AsBytes<'columnar>> ::columnar::AsBytes<'columnar> for #c_ident<C> {
+            fn as_bytes(&self) -> impl Iterator<Item=(u64, &'columnar [u8])> { self.0.as_bytes() }
+        }
+        impl<'columnar, C: ::columnar::layout::Layout<'columnar>> ::columnar::layout::Layout<'columnar> for #c_ident<C> {
+            fn visit(&self, visitor: &mut impl ::columnar::layout::Visitor<'columnar>) { self.0.visit(visitor) }
+        }
+        impl<'columnar, C: ::columnar::FromBytes<'columnar>> ::columnar::FromBytes<'columnar> for #c_ident<C> {
+            fn from_bytes(bytes: &mut impl Iterator<Item=&'columnar [u8]>) -> Self { #c_ident(C::from_bytes(bytes)) }
+        }
+
+        impl ::columnar::Columnar for #name {
+            type Ref<'a> = <#ty as ::columnar::Columnar>::Ref<'a>;
+            fn into_owned<'a>(other: Self::Ref<'a>) -> Self {
+                let inner = <#ty as ::columnar::Columnar>::into_owned(other);
+                #build
+            }
+            type Container = #c_ident;
+        }
+
+        impl ::columnar::Container<#name> for #c_ident {
+            type Borrowed<'a> = #c_ident<<<#ty as ::columnar::Columnar>::Container as ::columnar::Container<#ty>>::Borrowed<'a>>;
+            fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
+                #c_ident(<<#ty as ::columnar::Columnar>::Container as ::columnar::Container<#ty>>::borrow(&self.0))
+            }
+        }
+
+    }.into()
+}
+
 /// The derived container for an `enum` type will be a struct with containers for each field of each variant, plus an offset container and a discriminant container.
 /// Its index `Ref` type will be an enum with parallel variants, each containing the index `Ref` types of the corresponding variant containers.
 #[allow(unused)]
-fn derive_enum(name: &syn::Ident, generics: &syn:: Generics, data_enum: syn::DataEnum, vis: syn::Visibility) -> proc_macro::TokenStream {
+fn derive_enum(name: &syn::Ident, generics: &syn:: Generics, data_enum: syn::DataEnum, vis: syn::Visibility, bound: Option<&[syn::WherePredicate]>, repr: Option<&syn::Ident>) -> proc_macro::TokenStream {
 
     if data_enum.variants.iter().all(|variant| variant.fields.is_empty()) {
-        return derive_tags(name, generics, data_enum, vis);
+        return derive_tags(name, generics, data_enum, vis, repr);
     }
 
     let c_name = format!("{}Container", name);
@@ -489,7 +1036,7 @@ fn derive_enum(name: &syn::Ident, generics: &syn:: Generics, data_enum: syn::Dat
     let container_struct = {
         quote! {
             /// Derived columnar container for an enum.
-            #[derive(Copy, Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+            #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
             #[allow(non_snake_case)]
             #vis struct #c_ident < #(#container_types,)* CVar = Vec<u8>, COff = Vec<u64>, >{
                 #(
@@ -513,15 +1060,106 @@ fn derive_enum(name: &syn::Ident, generics: &syn:: Generics, data_enum: syn::Dat
     
         let ty_gen = quote! { < #(#reference_types),* > };
 
+        // References print as the variant they reference would, with one entry per field.
+        // Variants with multiple fields reference a tuple, whose elements are printed separately.
+        let field_types = &variants.iter().zip(reference_types.iter()).map(|((_, types), r)| {
+            if types.len() > 1 {
+                (0 .. types.len()).map(|index| syn::Ident::new(&format!("{}_{}", r, index), r.span())).collect()
+            }
+            else {
+                vec![r.clone()]
+            }
+        }).collect::<Vec<Vec<_>>>();
+        let debug_params = field_types.iter().flatten();
+        let debug_args = field_types.iter().zip(variants.iter()).map(|(fields, (_, types))| {
+            if types.len() > 1 { quote! { ( #(#fields),* ) } } else { quote! { #(#fields)* } }
+        });
+        let debug_bounds = field_types.iter().flatten();
+        let arms = variants.iter().map(|(variant, types)| {
+            let variant_str = variant.to_string();
+            if types.is_empty() {
+                quote! { #r_ident::#variant(_) => f.write_str(#variant_str), }
+            }
+            else if types.len() == 1 {
+                quote! { #r_ident::#variant(x) => f.debug_tuple(#variant_str).field(x).finish(), }
+            }
+            else {
+                let temp_names = &(0 .. types.len()).map(|index| syn::Ident::new(&format!("t{}", index), variant.span())).collect::<Vec<_>>();
+                quote! { #r_ident::#variant(( #(#temp_names),* )) => f.debug_tuple(#variant_str) #( .field(#temp_names) )* .finish(), }
+            }
+        });
+
         quote! {
             /// Reference for an enum.
-            #[derive(Copy, Clone, Debug)]
+            #[derive(Copy, Clone, PartialEq, Eq, serde::Serialize)]
             #vis enum #r_ident #ty_gen {
                 #(
                     /// Enum variant for #names.
                     #names(#reference_types),
                 )*
             }
+
+            impl < #(#debug_params),* > ::std::fmt::Debug for #r_ident < #(#debug_args),* > where #(#debug_bounds: ::std::fmt::Debug),* {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        }
+    };
+
+    let into_owned = {
+
+        let reference_types = &names.iter().enumerate().map(|(index, name)| {
+            let new_name = format!("R{}", index);
+            syn::Ident::new(&new_name, name.span())
+        }).collect::<Vec<_>>();
+
+        let (_impl_gen, ty_gen, where_clause) = generics.split_for_impl();
+
+        let struct_generics = generics.params.iter();
+        let impl_gen = quote! { < #(#struct_generics,)* #(#reference_types),* > };
+
+        let bounds = &reference_types.iter().zip(variants.iter()).map(|(r, (_, types))| {
+            quote! { #r: ::columnar::IntoOwned<Owned = (#(#types),*)> }
+        }).collect::<Vec<_>>();
+        let struct_where = where_clause.map(|clause| clause.predicates.iter().collect::<Vec<_>>()).unwrap_or_default();
+
+        let arms = variants.iter().enumerate().map(|(index, (variant, types))| {
+            if data_enum.variants[index].fields == syn::Fields::Unit {
+                quote! { #r_ident::#variant(_) => #name::#variant, }
+            }
+            else {
+                let temp_names = &types.iter().enumerate().map(|(index, _)| {
+                    let new_name = format!("t{}", index);
+                    syn::Ident::new(&new_name, variant.span())
+                }).collect::<Vec<_>>();
+
+                quote! {
+                    #r_ident::#variant(item) => {
+                        let ( #( #temp_names ),* ) = ::columnar::IntoOwned::into_owned(item);
+                        #name::#variant( #( #temp_names ),* )
+                    },
+                }
+            }
+        });
+
+        quote! {
+            impl #impl_gen ::columnar::IntoOwned for #r_ident < #(#reference_types),* > where #(#bounds,)* #(#struct_where,)* {
+                type Owned = #name #ty_gen;
+                fn into_owned(self) -> Self::Owned {
+                    match self {
+                        #( #arms )*
+                    }
+                }
+            }
+            impl #impl_gen From<#r_ident < #(#reference_types),* >> for #name #ty_gen where #(#bounds,)* #(#struct_where,)* {
+                #[inline(always)]
+                fn from(other: #r_ident < #(#reference_types),* >) -> Self {
+                    ::columnar::IntoOwned::into_owned(other)
+                }
+            }
         }
     };
 
@@ -728,6 +1366,67 @@ fn derive_enum(name: &syn::Ident, generics: &syn:: Generics, data_enum: syn::Dat
         }
     };
 
+    let permute = {
+
+        let impl_gen = quote! { < #(#container_types),* > };
+        let ty_gen = quote! { < #(#container_types),* > };
+        let where_clause = quote! { where #(#container_types: ::columnar::Permute),* };
+
+        let numbers = (0 .. variants.len());
+        let count = variants.len();
+
+        quote! {
+            impl #impl_gen ::columnar::Permute for #c_ident #ty_gen #where_clause {
+                fn permute(&mut self, perm: &[usize]) {
+                    assert_eq!(perm.len(), self.variant.len());
+                    // Rows of each variant are permuted among themselves, in their order of appearance.
+                    let mut perms = vec![Vec::new(); #count];
+                    let mut variant = Vec::with_capacity(perm.len());
+                    let mut offset = Vec::with_capacity(perm.len());
+                    for index in perm {
+                        let var = self.variant[*index];
+                        offset.push(perms[var as usize].len() as u64);
+                        perms[var as usize].push(self.offset[*index] as usize);
+                        variant.push(var);
+                    }
+                    self.variant = variant;
+                    self.offset = offset;
+                    #( self.#names.permute(&perms[#numbers]); )*
+                }
+            }
+        }
+    };
+
+    let extend_from_range = {
+
+        let impl_gen = quote! { < #(#container_types),* > };
+        let ty_gen = quote! { < #(#container_types),* > };
+        let where_clause = quote! { where #(#container_types: ::columnar::ExtendFromRange + ::columnar::Len),* };
+
+        let numbers = (0 .. variants.len());
+        let count = variants.len();
+
+        quote! {
+            impl #impl_gen ::columnar::ExtendFromRange for #c_ident #ty_gen #where_clause {
+                fn extend_from_range(&mut self, other: &Self, range: ::std::ops::Range<usize>) {
+                    // Rows of each variant within `range` are contiguous in that variant's container.
+                    let bases = [#( ::columnar::Len::len(&self.#names) as u64 ),*];
+                    let mut lowers = [None; #count];
+                    let mut uppers = [0u64; #count];
+                    for index in range {
+                        let var = other.variant[index];
+                        let offset = other.offset[index];
+                        let lower = *lowers[var as usize].get_or_insert(offset);
+                        uppers[var as usize] = offset + 1;
+                        self.variant.push(var);
+                        self.offset.push(bases[var as usize] + offset - lower);
+                    }
+                    #( if let Some(lower) = lowers[#numbers] { self.#names.extend_from_range(&other.#names, lower as usize .. uppers[#numbers] as usize); } )*
+                }
+            }
+        }
+    };
+
     let length = { 
 
         let impl_gen = quote! { < #(#container_types,)* CVar, COff> };
@@ -761,6 +1460,25 @@ fn derive_enum(name: &syn::Ident, generics: &syn:: Generics, data_enum: syn::Dat
         }
     };
 
+    // Variants are visited by name, followed by the columns of discriminants and offsets.
+    let layout_visit = {
+
+        let impl_gen = quote! { < 'a, #(#container_types,)* CVar, COff> };
+        let ty_gen = quote! { < #(#container_types,)* CVar, COff > };
+        let where_clause = quote! { where #(#container_types: ::columnar::layout::Layout<'a>,)* CVar: ::columnar::layout::Layout<'a>, COff: ::columnar::layout::Layout<'a> };
+        let roles = names.iter().map(|name| name.to_string());
+
+        quote! {
+            impl #impl_gen ::columnar::layout::Layout<'a> for #c_ident #ty_gen #where_clause {
+                fn visit(&self, visitor: &mut impl ::columnar::layout::Visitor<'a>) {
+                    #( ::columnar::layout::component(visitor, #roles, &self.#names); )*
+                    ::columnar::layout::component(visitor, "variant", &self.variant);
+                    ::columnar::layout::component(visitor, "offset", &self.offset);
+                }
+            }
+        }
+    };
+
     let from_bytes = { 
 
         let impl_gen = quote! { < 'columnar, #(#container_types,)* CVar, COff> };
@@ -786,17 +1504,18 @@ fn derive_enum(name: &syn::Ident, generics: &syn:: Generics, data_enum: syn::Dat
 
         let types = &variants.iter().flat_map(|(_, types)| types).collect::<Vec<_>>();
 
-        let where_clause2 = if let Some(enum_where) = where_clause {
-            let params = enum_where.predicates.iter(); 
-            quote! {  where #(#types : ::columnar::Columnar,)* #(#params),* }
-        }
-        else {
-            quote! { where #(#types : ::columnar::Columnar,)* }
+        let params = where_clause.map(|clause| clause.predicates.iter().collect::<Vec<_>>()).unwrap_or_default();
+        let where_clause2 = match bound {
+            Some(bound) => quote! { where #(#bound,)* #(#params),* },
+            None => quote! { where #(#types : ::columnar::Columnar,)* #(#params),* },
         };
 
 
         let variant_types = &variants.iter().map(|(_, types)| quote! { (#(#types),*) }).collect::<Vec<_>>();
 
+        // Variants are tagged by their position, for `OpenEnum`.
+        let tags = (0 .. variants.len()).map(|index| index as u8);
+
         let container_types = &variants.iter().map(|(_, types)| quote! { <(#(#types),*) as ::columnar::Columnar>::Container }).collect::<Vec<_>>();
 
         let reference_args = variants.iter().map(|(_, types)| quote! { <(#(#types),*) as ::columnar::Columnar>::Ref<'a> });
@@ -870,12 +1589,51 @@ fn derive_enum(name: &syn::Ident, generics: &syn:: Generics, data_enum: syn::Dat
                 type Borrowed<'a> = #c_ident < #( < #container_types as ::columnar::Container<#variant_types> >::Borrowed<'a>, )* &'a [u8], &'a [u64] > where #(#variant_types: 'a,)*;
                 fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
                     #c_ident {
-                        #(#names: self.#names.borrow(),)*
+                        #(#names: < #container_types as ::columnar::Container<#variant_types> >::borrow(&self.#names),)*
                         variant: self.variant.borrow(),
                         offset: self.offset.borrow(),
                     }
                 }
             }
+
+            impl #impl_gen ::columnar::open::OpenEnum for #name #ty_gen #where_clause2 {
+                fn variant_slices() -> Vec<usize> {
+                    use ::columnar::AsBytes;
+                    let container = <Self as ::columnar::Columnar>::Container::default();
+                    let borrowed = ::columnar::Container::<Self>::borrow(&container);
+                    vec![ #( borrowed.#names.as_bytes().count(), )* ]
+                }
+                fn tag(row: &Self::Ref<'_>) -> u8 {
+                    match row {
+                        #( #r_ident::#names(_) => #tags, )*
+                    }
+                }
+            }
+        }
+    };
+
+    // The contents of the variant are reached by the variant's name.
+    let inspect = {
+
+        let reference_types = &names.iter().enumerate().map(|(index, name)| {
+            let new_name = format!("R{}", index);
+            syn::Ident::new(&new_name, name.span())
+        }).collect::<Vec<_>>();
+
+        let arms = names.iter().map(|variant| {
+            let variant_str = variant.to_string();
+            quote! { (#r_ident::#variant(item), #variant_str) => Some(Box::new(*item)), }
+        });
+
+        quote! {
+            impl <'columnar, #(#reference_types),* > ::columnar::path::Inspect<'columnar> for #r_ident < #(#reference_types),* > where #(#reference_types: ::columnar::path::Inspect<'columnar> + Copy + 'columnar,)* {
+                fn field(&self, name: &str) -> Option<Box<dyn ::columnar::path::Inspect<'columnar> + 'columnar>> {
+                    match (self, name) {
+                        #(#arms)*
+                        _ => None,
+                    }
+                }
+            }
         }
     };
 
@@ -883,6 +1641,8 @@ fn derive_enum(name: &syn::Ident, generics: &syn:: Generics, data_enum: syn::Dat
 
         #container_struct
         #reference_struct
+        #into_owned
+        #inspect
 
         #push_own
         #push_ref
@@ -892,9 +1652,12 @@ fn derive_enum(name: &syn::Ident, generics: &syn:: Generics, data_enum: syn::Dat
         #index_ref
         #length
         #clear
+        #permute
+        #extend_from_range
 
         #as_bytes
         #from_bytes
+        #layout_visit
 
         #columnar_impl
 
@@ -902,8 +1665,11 @@ fn derive_enum(name: &syn::Ident, generics: &syn:: Generics, data_enum: syn::Dat
 }
 
 /// A derivation for an enum type with no fields in any of its variants.
+///
+/// Variants are recorded by their position as a `u8`, or for enums with an integer `#[repr(..)]`
+/// by their discriminant as that integer type.
 #[allow(unused)]
-fn derive_tags(name: &syn::Ident, _generics: &syn:: Generics, data_enum: syn::DataEnum, vis: syn::Visibility) -> proc_macro::TokenStream {
+fn derive_tags(name: &syn::Ident, _generics: &syn:: Generics, data_enum: syn::DataEnum, vis: syn::Visibility, repr: Option<&syn::Ident>) -> proc_macro::TokenStream {
 
     let c_name = format!("{}Container", name);
     let c_ident = syn::Ident::new(&c_name, name.span());
@@ -915,15 +1681,20 @@ fn derive_tags(name: &syn::Ident, _generics: &syn:: Generics, data_enum: syn::Da
         .map(|variant| &variant.ident)
         .collect();
 
-    let indices: &Vec<u8> = &(0 .. names.len()).map(|x| x as u8).collect();
-
-    // Bit silly, but to help us fit in a byte and reign in bloat.
-    assert!(names.len() <= 256, "Too many variants for enum");
+    let (int, indices) = match repr {
+        Some(int) => (quote! { #int }, names.iter().map(|variant| quote! { (#name::#variant as #int) }).collect::<Vec<_>>()),
+        None => {
+            // Bit silly, but to help us fit in a byte and reign in bloat.
+            assert!(names.len() <= 256, "Too many variants for enum");
+            (quote! { u8 }, (0 .. names.len()).map(|x| { let x = x as u8; quote! { #x } }).collect())
+        }
+    };
+    let indices = &indices;
 
     quote! {
         /// Derived columnar container for all-unit enum.
-        #[derive(Copy, Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
-        #vis struct #c_ident <CVar = Vec<u8>> {
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+        #vis struct #c_ident <CVar = Vec<#int>> {
             /// Container for variant.
             pub variant: CVar,
         }
@@ -944,21 +1715,21 @@ fn derive_tags(name: &syn::Ident, _generics: &syn:: Generics, data_enum: syn::Da
             }
         }
 
-        impl<CVar: ::columnar::Len + ::columnar::IndexAs<u8>> ::columnar::Index for #c_ident <CVar> {
+        impl<CVar: ::columnar::Len + ::columnar::IndexAs<#int>> ::columnar::Index for #c_ident <CVar> {
             type Ref = #name;
             fn get(&self, index: usize) -> Self::Ref {
                 match self.variant.index_as(index) {
-                    #( #indices => #name::#names, )*
+                    #( x if x == #indices => #name::#names, )*
                     x => panic!("Unacceptable discriminant found: {:?}", x),
                 }
             }
         }
 
-        impl<'columnar, CVar: ::columnar::Len + ::columnar::IndexAs<u8>> ::columnar::Index for &'columnar #c_ident <CVar> {
+        impl<'columnar, CVar: ::columnar::Len + ::columnar::IndexAs<#int>> ::columnar::Index for &'columnar #c_ident <CVar> {
             type Ref = #name;
             fn get(&self, index: usize) -> Self::Ref {
                 match self.variant.index_as(index) {
-                    #( #indices => #name::#names, )*
+                    #( x if x == #indices => #name::#names, )*
                     x => panic!("Unacceptable discriminant found: {:?}", x),
                 }
             }
@@ -970,6 +1741,18 @@ fn derive_tags(name: &syn::Ident, _generics: &syn:: Generics, data_enum: syn::Da
             }
         }
 
+        impl<CVar: ::columnar::Permute> ::columnar::Permute for #c_ident <CVar> {
+            fn permute(&mut self, perm: &[usize]) {
+                self.variant.permute(perm);
+            }
+        }
+
+        impl<CVar: ::columnar::ExtendFromRange> ::columnar::ExtendFromRange for #c_ident <CVar> {
+            fn extend_from_range(&mut self, other: &Self, range: ::std::ops::Range<usize>) {
+                self.variant.extend_from_range(&other.variant, range);
+            }
+        }
+
         impl<CVar: ::columnar::Len> ::columnar::Len for #c_ident <CVar> {
             fn len(&self) -> usize {
                 self.variant.len()
@@ -983,12 +1766,23 @@ fn derive_tags(name: &syn::Ident, _generics: &syn:: Generics, data_enum: syn::Da
             }
         }
 
+        impl<'a, CVar: ::columnar::layout::Layout<'a>> ::columnar::layout::Layout<'a> for #c_ident <CVar> {
+            fn visit(&self, visitor: &mut impl ::columnar::layout::Visitor<'a>) {
+                ::columnar::layout::component(visitor, "variant", &self.variant);
+            }
+        }
+
         impl<'columnar, CVar: ::columnar::FromBytes<'columnar>> ::columnar::FromBytes<'columnar> for #c_ident <CVar> {
             fn from_bytes(bytes: &mut impl Iterator<Item=&'columnar [u8]>) -> Self {
                 Self { variant: ::columnar::FromBytes::from_bytes(bytes) }
             }
         }
 
+        impl ::columnar::IntoOwned for #name {
+            type Owned = #name;
+            fn into_owned(self) -> Self::Owned { self }
+        }
+
         impl ::columnar::Columnar for #name {
             type Ref<'a> = #name;
             fn copy_from<'a>(&mut self, other: Self::Ref<'a>) { *self = other; }
@@ -996,7 +1790,7 @@ fn derive_tags(name: &syn::Ident, _generics: &syn:: Generics, data_enum: syn::Da
             type Container = #c_ident;
         }
 
-        impl<CV: ::columnar::Container<u8>> ::columnar::Container<#name> for #c_ident <CV> {
+        impl<CV: ::columnar::Container<#int>> ::columnar::Container<#name> for #c_ident <CV> {
             type Borrowed<'a> = #c_ident < CV::Borrowed<'a> > where CV: 'a;
             fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
                 #c_ident {
@@ -1006,3 +1800,80 @@ fn derive_tags(name: &syn::Ident, _generics: &syn:: Generics, data_enum: syn::Da
         }
     }.into()
 }
+
+/// The indices of `fields` in the order their columns are laid out, and the fields' tags.
+///
+/// Fields are laid out in declaration order, unless they carry `#[columnar(order = N)]`, in which
+/// case they are laid out by increasing `N`. Fields may carry `#[columnar(tag = N)]`, to be
+/// encoded by `Tagged`. For each attribute, either all fields or none must specify it, and
+/// no two fields may specify the same value.
+fn field_attributes(fields: &syn::Fields) -> syn::Result<(Vec<usize>, Option<Vec<u64>>)> {
+    let mut orders = Vec::new();
+    let mut tags = Vec::new();
+    for field in fields.iter() {
+        let (mut order, mut tag) = (None, None);
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("columnar")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("order") {
+                    order = Some(meta.value()?.parse::<syn::LitInt>()?.base10_parse::<u64>()?);
+                    Ok(())
+                }
+                else if meta.path.is_ident("tag") {
+                    tag = Some(meta.value()?.parse::<syn::LitInt>()?.base10_parse::<u64>()?);
+                    Ok(())
+                }
+                else { Err(meta.error("unrecognized columnar field attribute")) }
+            })?;
+        }
+        orders.push(order);
+        tags.push(tag);
+    }
+    let fields = fields.iter().collect::<Vec<_>>();
+    // The values of the attribute if all fields have it, or `None` if no field has it.
+    let all_or_none = |values: &[Option<u64>], attribute: &str| -> syn::Result<Option<Vec<u64>>> {
+        if values.iter().all(|value| value.is_none()) { return Ok(None); }
+        if let Some(index) = values.iter().position(|value| value.is_none()) {
+            return Err(syn::Error::new_spanned(fields[index], format!("either all fields or none must have `#[columnar({} = N)]`", attribute)));
+        }
+        let values = values.iter().map(|value| value.unwrap()).collect::<Vec<_>>();
+        if let Some(index) = (0 .. values.len()).find(|index| values[.. *index].contains(&values[*index])) {
+            return Err(syn::Error::new_spanned(fields[index], format!("duplicate `#[columnar({} = N)]`", attribute)));
+        }
+        Ok(Some(values))
+    };
+    let mut layout = (0 .. fields.len()).collect::<Vec<_>>();
+    if let Some(orders) = all_or_none(&orders, "order")? {
+        layout.sort_by_key(|index| orders[*index]);
+    }
+    Ok((layout, all_or_none(&tags, "tag")?))
+}
+
+/// True if `ty` is `Vec<#name>` or `Vec<Self>`, a recursive field recorded by row indices.
+fn is_recursive(ty: &syn::Type, name: &syn::Ident) -> bool {
+    if let syn::Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            if segment.ident == "Vec" {
+                if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(syn::GenericArgument::Type(syn::Type::Path(inner))) = args.args.first() {
+                        if let Some(inner) = inner.path.segments.last() {
+                            return args.args.len() == 1 && (inner.ident == *name || inner.ident == "Self");
+                        }
+                    }
+                }
+            }
+        }
+    }
+    false
+}
+
+/// True if `ty` mentions `#name` or `Self` anywhere within it.
+fn mentions(ty: &syn::Type, name: &syn::Ident) -> bool {
+    fn search(tokens: proc_macro2::TokenStream, name: &syn::Ident) -> bool {
+        tokens.into_iter().any(|token| match token {
+            proc_macro2::TokenTree::Ident(ident) => ident == *name || ident == "Self",
+            proc_macro2::TokenTree::Group(group) => search(group.stream(), name),
+            _ => false,
+        })
+    }
+    search(quote! { #ty }, name)
+}
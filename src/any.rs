@@ -0,0 +1,148 @@
+//! Columns whose row type is known only at runtime.
+//!
+//! A `Box<dyn AnyColumn>` holds the container of some `Columnar` type, and supports the
+//! operations that do not mention that type: measuring, clearing, and encoding to and
+//! decoding from bytes. The row type is recorded, and the container can be recovered by
+//! downcasting with the row type, after which the full typed interface is available.
+
+use std::any::{Any, TypeId};
+
+use crate::{AsBytes, Clear, ColumnRead, Columnar, Container, FromBytes, HeapSize, Index, Len, Push};
+use crate::bytes::serialization::{decode, encode, try_decode_owned};
+
+/// A column of some `Columnar` type, with the type erased.
+pub trait AnyColumn : ColumnRead + Any {
+    /// The `TypeId` of the row type.
+    fn row_type(&self) -> TypeId;
+    /// The name of the row type, for diagnostics only.
+    fn row_type_name(&self) -> &'static str;
+    /// A new, empty column of the same row type.
+    fn empty(&self) -> Box<dyn AnyColumn>;
+
+    /// The number of `u64` words that `encode` will append.
+    fn length_in_words(&self) -> usize;
+    /// Appends the encoded column to `store`.
+    fn encode(&self, store: &mut Vec<u64>);
+    /// Appends the rows of a column encoded in `store`.
+    ///
+    /// The column must have been encoded from a column of the same row type.
+    fn decode(&mut self, store: &[u64]);
+    /// Appends the rows of a column encoded in `store`, if it is the encoding of a column of the same row type.
+    ///
+    /// Returns `false`, leaving the column unchanged, if the rows cannot be read, as checked by
+    /// `bytes::serialization::try_decode_owned`.
+    fn try_decode(&mut self, store: &[u64]) -> bool;
+
+    /// Presents the column as `Any`, to downcast by reference.
+    fn as_any(&self) -> &dyn Any;
+    /// Presents the column as `Any`, to downcast by mutable reference.
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    /// Presents the column as `Any`, to downcast by value.
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+/// The container of `T`, as an `AnyColumn`.
+pub struct Column<T: Columnar>(pub T::Container);
+
+impl<T: Columnar> Default for Column<T> {
+    fn default() -> Self { Self(Default::default()) }
+}
+impl<T: Columnar> Len for Column<T> {
+    #[inline(always)] fn len(&self) -> usize { self.0.len() }
+}
+impl<T: Columnar> Clear for Column<T> {
+    #[inline(always)] fn clear(&mut self) { self.0.clear() }
+}
+impl<T: Columnar> HeapSize for Column<T> where T::Container: HeapSize {
+    fn heap_size(&self) -> (usize, usize) { self.0.heap_size() }
+}
+
+impl<T: Columnar> AnyColumn for Column<T> where T::Container: HeapSize + 'static {
+    fn row_type(&self) -> TypeId { TypeId::of::<T>() }
+    fn row_type_name(&self) -> &'static str { std::any::type_name::<T>() }
+    fn empty(&self) -> Box<dyn AnyColumn> { Box::new(Self::default()) }
+
+    fn length_in_words(&self) -> usize { self.0.borrow().length_in_words() }
+    fn encode(&self, store: &mut Vec<u64>) { encode(store, self.0.borrow().as_bytes()) }
+    fn decode(&mut self, store: &[u64]) {
+        let borrowed = <T::Container as Container<T>>::Borrowed::from_bytes(&mut decode(store));
+        for index in 0 .. borrowed.len() {
+            self.0.push(borrowed.get(index));
+        }
+    }
+    fn try_decode(&mut self, store: &[u64]) -> bool {
+        let Some(rows) = try_decode_owned::<T>(store) else { return false };
+        let borrowed = rows.borrow();
+        for index in 0 .. borrowed.len() {
+            self.0.push(borrowed.get(index));
+        }
+        true
+    }
+
+    fn as_any(&self) -> &dyn Any { self }
+    fn as_any_mut(&mut self) -> &mut dyn Any { self }
+    fn into_any(self: Box<Self>) -> Box<dyn Any> { self }
+}
+
+impl dyn AnyColumn {
+    /// Erases the type of `container`, a container of `T`.
+    pub fn new<T: Columnar>(container: T::Container) -> Box<dyn AnyColumn> where T::Container: HeapSize + 'static {
+        Box::new(Column::<T>(container))
+    }
+    /// True if the rows of the column have type `T`.
+    pub fn is<T: Columnar>(&self) -> bool { self.row_type() == TypeId::of::<T>() }
+    /// The container, if the rows of the column have type `T`.
+    pub fn downcast_ref<T: Columnar>(&self) -> Option<&T::Container> where T::Container: 'static {
+        self.as_any().downcast_ref::<Column<T>>().map(|column| &column.0)
+    }
+    /// The container, if the rows of the column have type `T`.
+    pub fn downcast_mut<T: Columnar>(&mut self) -> Option<&mut T::Container> where T::Container: 'static {
+        self.as_any_mut().downcast_mut::<Column<T>>().map(|column| &mut column.0)
+    }
+    /// The container, if the rows of the column have type `T`, and otherwise the column.
+    pub fn downcast<T: Columnar>(self: Box<Self>) -> Result<T::Container, Box<dyn AnyColumn>> where T::Container: 'static {
+        if self.is::<T>() {
+            Ok(self.into_any().downcast::<Column<T>>().unwrap().0)
+        } else {
+            Err(self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::AnyColumn;
+    use crate::{Columnar, Container, Len};
+
+    #[test]
+    fn round_trip() {
+        let numbers = Columnar::into_columns(0 .. 100u64);
+        let names = Columnar::into_columns((0 .. 10).map(|i| format!("name{}", i)));
+        let columns = vec![<dyn AnyColumn>::new::<u64>(numbers), <dyn AnyColumn>::new::<String>(names)];
+
+        assert!(columns[0].is::<u64>() && !columns[0].is::<String>());
+        assert!(columns[1].downcast_ref::<u64>().is_none());
+        assert_eq!(columns[1].downcast_ref::<String>().unwrap().len(), 10);
+
+        let mut store = Vec::new();
+        for column in columns.iter() {
+            let before = store.len();
+            column.encode(&mut store);
+            assert_eq!(store.len() - before, column.length_in_words());
+        }
+        let mut offset = 0;
+        for column in columns {
+            let mut decoded = column.empty();
+            decoded.decode(&store[offset .. offset + column.length_in_words()]);
+            offset += column.length_in_words();
+            assert_eq!(decoded.len(), column.len());
+            assert_eq!(decoded.row_type(), column.row_type());
+        }
+
+        let column = <dyn AnyColumn>::new::<u64>(Columnar::into_columns(0 .. 10u64));
+        let column = column.downcast::<String>().unwrap_err();
+        let numbers = column.downcast::<u64>().ok().unwrap();
+        assert_eq!(Container::<u64>::borrow(&numbers), &(0 .. 10).collect::<Vec<_>>()[..]);
+    }
+}
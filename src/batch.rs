@@ -0,0 +1,227 @@
+//! Ordered sets of named columns with equal numbers of rows.
+//!
+//! A `Batch` is the columnar analogue of a table: each column is a type-erased
+//! `AnyColumn`, found by name or position, and every column has the same number of rows.
+//! A batch encodes to a single sequence of `u64` words, and decodes into a batch with the
+//! same schema, which supplies the column types that the encoding does not record.
+
+use crate::Columnar;
+use crate::any::AnyColumn;
+
+/// A sequence of named, type-erased columns, all with the same number of rows.
+#[derive(Default)]
+pub struct Batch {
+    names: Vec<String>,
+    columns: Vec<Box<dyn AnyColumn>>,
+}
+
+impl Batch {
+    /// A batch without columns.
+    pub fn new() -> Self { Self::default() }
+
+    /// Appends a column named `name`.
+    ///
+    /// Panics if the batch already has a column named `name`, or if the batch has columns
+    /// and their number of rows differs from that of `column`.
+    pub fn push_column(&mut self, name: impl Into<String>, column: Box<dyn AnyColumn>) {
+        if let Err(error) = self.try_push_column(name, column) { panic!("{}", error); }
+    }
+    /// Appends a column named `name`, or reports why it does not fit the batch.
+    ///
+    /// On error the batch is unchanged, and the column is dropped.
+    pub fn try_push_column(&mut self, name: impl Into<String>, column: Box<dyn AnyColumn>) -> Result<(), BatchError> {
+        let name = name.into();
+        if self.position(&name).is_some() { return Err(BatchError::DuplicateName { name }); }
+        if !self.columns.is_empty() && self.rows() != column.len() {
+            return Err(BatchError::RowMismatch { name, rows: column.len(), expected: self.rows() });
+        }
+        self.names.push(name);
+        self.columns.push(column);
+        Ok(())
+    }
+    /// Appends a column named `name` holding `container`, a container of `T`.
+    pub fn with_column<T: Columnar>(mut self, name: impl Into<String>, container: T::Container) -> Self where T::Container: crate::HeapSize + 'static {
+        self.push_column(name, <dyn AnyColumn>::new::<T>(container));
+        self
+    }
+
+    /// The number of rows in each column, or zero if there are no columns.
+    pub fn rows(&self) -> usize { self.columns.first().map(|column| column.len()).unwrap_or(0) }
+    /// The number of columns.
+    pub fn width(&self) -> usize { self.columns.len() }
+    /// The name and row type name of each column, in order.
+    pub fn schema(&self) -> impl Iterator<Item = (&str, &'static str)> {
+        self.names.iter().zip(self.columns.iter()).map(|(name, column)| (&name[..], column.row_type_name()))
+    }
+    /// The position of the column named `name`.
+    pub fn position(&self, name: &str) -> Option<usize> { self.names.iter().position(|n| n == name) }
+    /// The column named `name`.
+    pub fn column(&self, name: &str) -> Option<&dyn AnyColumn> {
+        self.position(name).map(|index| &*self.columns[index])
+    }
+    /// The column named `name`, if its rows have type `T`.
+    pub fn typed<T: Columnar>(&self, name: &str) -> Option<&T::Container> where T::Container: 'static {
+        self.column(name)?.downcast_ref::<T>()
+    }
+    /// The columns, in order.
+    pub fn columns(&self) -> impl Iterator<Item = &dyn AnyColumn> {
+        self.columns.iter().map(|column| &**column)
+    }
+    /// True if all columns have the same number of rows.
+    ///
+    /// This is maintained by `push_column`, but may be broken through `column_mut`.
+    pub fn is_aligned(&self) -> bool { self.columns.iter().all(|column| column.len() == self.rows()) }
+    /// The column named `name`, mutably.
+    pub fn column_mut(&mut self, name: &str) -> Option<&mut dyn AnyColumn> {
+        let index = self.position(name)?;
+        Some(&mut *self.columns[index])
+    }
+
+    /// A batch with the same schema and no rows.
+    pub fn empty(&self) -> Self {
+        Self {
+            names: self.names.clone(),
+            columns: self.columns.iter().map(|column| column.empty()).collect(),
+        }
+    }
+    /// Removes all rows, retaining the schema.
+    pub fn clear(&mut self) {
+        for column in self.columns.iter_mut() { column.clear(); }
+    }
+
+    /// The number of `u64` words that `encode` will append.
+    pub fn length_in_words(&self) -> usize {
+        1 + self.columns.len() + self.columns.iter().map(|column| column.length_in_words()).sum::<usize>()
+    }
+    /// Appends the encoded batch to `store`.
+    ///
+    /// The encoding is the number of columns, the length in words of each column, and then
+    /// the encoding of each column. Names and types are not recorded.
+    pub fn encode(&self, store: &mut Vec<u64>) {
+        store.push(self.columns.len() as u64);
+        store.extend(self.columns.iter().map(|column| column.length_in_words() as u64));
+        for column in self.columns.iter() {
+            column.encode(store);
+        }
+    }
+    /// Appends the rows of a batch encoded in `store`.
+    ///
+    /// The batch must have been encoded from a batch with the same schema as `self`.
+    pub fn decode(&mut self, store: &[u64]) {
+        assert_eq!(store[0] as usize, self.columns.len(), "encoded batch has a different number of columns");
+        let (lengths, mut store) = store[1..].split_at(self.columns.len());
+        for (column, length) in self.columns.iter_mut().zip(lengths) {
+            let (encoded, rest) = store.split_at(*length as usize);
+            column.decode(encoded);
+            store = rest;
+        }
+    }
+    /// Appends the rows of a batch encoded in `store`, if it is the encoding of a batch with the schema of `self`.
+    ///
+    /// Returns `None`, leaving the batch unchanged, if the encoding has a different number of columns,
+    /// if a column's rows cannot be read as the type of the corresponding column of `self`, or if
+    /// the columns have different numbers of rows.
+    pub fn try_decode(&mut self, store: &[u64]) -> Option<()> {
+        let (count, rest) = store.split_first()?;
+        if usize::try_from(*count).ok()? != self.columns.len() || rest.len() < self.columns.len() { return None; }
+        let (lengths, mut rest) = rest.split_at(self.columns.len());
+        let mut encoded = Vec::with_capacity(self.columns.len());
+        for length in lengths {
+            let length = usize::try_from(*length).ok().filter(|length| *length <= rest.len())?;
+            let (column, remaining) = rest.split_at(length);
+            encoded.push(column);
+            rest = remaining;
+        }
+        // Decode into empty columns first, so that a malformed column leaves `self` unchanged.
+        let mut decoded = self.empty();
+        for (column, encoded) in decoded.columns.iter_mut().zip(encoded.iter()) {
+            if !column.try_decode(encoded) { return None; }
+        }
+        if !decoded.is_aligned() { return None; }
+        for (column, encoded) in self.columns.iter_mut().zip(encoded) {
+            column.decode(encoded);
+        }
+        Some(())
+    }
+}
+
+/// The reasons a column cannot be added to a batch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BatchError {
+    /// The batch already has a column with this name.
+    DuplicateName { name: String },
+    /// The column has `rows` rows, where the batch has `expected`.
+    RowMismatch { name: String, rows: usize, expected: usize },
+}
+
+impl std::fmt::Display for BatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BatchError::DuplicateName { name } => write!(f, "duplicate column name: {:?}", name),
+            BatchError::RowMismatch { name, rows, expected } => write!(f, "column {:?} has {} rows, rather than {}", name, rows, expected),
+        }
+    }
+}
+impl std::error::Error for BatchError { }
+
+#[cfg(test)]
+mod test {
+
+    use super::{Batch, BatchError};
+    use crate::{Columnar, Container};
+
+    #[test]
+    fn round_trip() {
+        let batch = Batch::new()
+            .with_column::<u64>("id", Columnar::into_columns(0 .. 10u64))
+            .with_column::<String>("name", Columnar::into_columns((0 .. 10).map(|i| format!("name{}", i))))
+            .with_column::<Option<i32>>("score", Columnar::into_columns((0 .. 10).map(|i| if i % 3 == 0 { None } else { Some(i) })));
+
+        assert_eq!(batch.rows(), 10);
+        assert_eq!(batch.schema().map(|(name, _)| name).collect::<Vec<_>>(), ["id", "name", "score"]);
+        assert!(batch.typed::<String>("id").is_none());
+        assert_eq!(batch.typed::<String>("name").unwrap().materialize(4), "name4");
+
+        let mut store = Vec::new();
+        batch.encode(&mut store);
+        assert_eq!(store.len(), batch.length_in_words());
+
+        let mut decoded = batch.empty();
+        decoded.decode(&store);
+        decoded.decode(&store);
+        assert_eq!(decoded.rows(), 20);
+        assert!(decoded.is_aligned());
+        assert_eq!(decoded.typed::<Option<i32>>("score").unwrap().materialize(14), Some(4));
+
+        assert_eq!(decoded.try_decode(&store), Some(()));
+        assert_eq!(decoded.rows(), 30);
+        // Truncated encodings, and encodings of other schemas, leave the batch unchanged.
+        for length in 0 .. store.len() {
+            assert_eq!(decoded.try_decode(&store[.. length]), None);
+        }
+        let mut other = Vec::new();
+        Batch::new().with_column::<u64>("id", Columnar::into_columns(0 .. 10u64)).encode(&mut other);
+        assert_eq!(decoded.try_decode(&other), None);
+        assert_eq!(decoded.rows(), 30);
+        assert!(decoded.is_aligned());
+    }
+
+    #[test]
+    fn try_push_column() {
+        use crate::any::AnyColumn;
+        let mut batch = Batch::new().with_column::<u64>("a", Columnar::into_columns(0 .. 10u64));
+        let error = batch.try_push_column("a", <dyn AnyColumn>::new::<u64>(Columnar::into_columns(0 .. 10u64)));
+        assert_eq!(error, Err(BatchError::DuplicateName { name: "a".to_string() }));
+        let error = batch.try_push_column("b", <dyn AnyColumn>::new::<u64>(Columnar::into_columns(0 .. 9u64)));
+        assert_eq!(error, Err(BatchError::RowMismatch { name: "b".to_string(), rows: 9, expected: 10 }));
+        assert_eq!(batch.width(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn misaligned() {
+        Batch::new()
+            .with_column::<u64>("a", Columnar::into_columns(0 .. 10u64))
+            .with_column::<u64>("b", Columnar::into_columns(0 .. 9u64));
+    }
+}
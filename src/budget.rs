@@ -0,0 +1,109 @@
+//! Builders that apply backpressure when their rows reach a memory budget.
+//!
+//! A `Budgeted` builder wraps a container, and before each push compares the container's active
+//! heap size against a budget. If the budget is reached, it calls a callback with the container,
+//! and the callback decides what happens to the push: it may flush or spill the rows elsewhere and
+//! have them cleared, admit the push regardless, or reject it. Because the check precedes each push,
+//! the rows exceed the budget by at most the size of one row, and the callback is called at the
+//! same points for the same sequence of pushes.
+
+use crate::{Clear, HeapSize, Len, Push, TryPush};
+
+/// The action a `Budgeted` builder takes for a push that finds it at or over its budget.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Backpressure {
+    /// The callback has taken the rows, as by flushing or spilling them: clear them and accept the push.
+    Clear,
+    /// Accept the push, exceeding the budget.
+    Admit,
+    /// Refuse the push, returning the item to the caller.
+    Reject,
+}
+
+/// An item refused by a builder over its budget.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rejected<T>(pub T);
+
+impl<T> std::fmt::Display for Rejected<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "builder is over its memory budget")
+    }
+}
+impl<T: std::fmt::Debug> std::error::Error for Rejected<T> { }
+
+/// A container that calls `callback` when a push finds its rows at or over `budget` bytes.
+pub struct Budgeted<C, F> {
+    inner: C,
+    budget: usize,
+    callback: F,
+}
+
+impl<C: HeapSize, F: FnMut(&mut C) -> Backpressure> Budgeted<C, F> {
+    /// Wraps `inner` with a budget of `budget_bytes` of active heap size.
+    pub fn new(inner: C, budget_bytes: usize, callback: F) -> Self {
+        Self { inner, budget: budget_bytes, callback }
+    }
+    /// The rows of the builder.
+    pub fn inner(&self) -> &C { &self.inner }
+    /// Unwraps the rows of the builder.
+    pub fn into_inner(self) -> C { self.inner }
+    /// The budget, in bytes.
+    pub fn budget(&self) -> usize { self.budget }
+    /// The bytes remaining before the budget is reached.
+    pub fn remaining(&self) -> usize { self.budget.saturating_sub(self.inner.heap_size().0) }
+}
+
+impl<T, C: Push<T> + HeapSize + Clear, F: FnMut(&mut C) -> Backpressure> TryPush<T> for Budgeted<C, F> {
+    type Error = Rejected<T>;
+    fn try_push(&mut self, item: T) -> Result<(), Self::Error> {
+        if self.inner.heap_size().0 >= self.budget {
+            match (self.callback)(&mut self.inner) {
+                Backpressure::Clear => self.inner.clear(),
+                Backpressure::Admit => { },
+                Backpressure::Reject => return Err(Rejected(item)),
+            }
+        }
+        self.inner.push(item);
+        Ok(())
+    }
+}
+
+impl<C: Len, F> Len for Budgeted<C, F> {
+    #[inline(always)] fn len(&self) -> usize { self.inner.len() }
+}
+impl<C: HeapSize, F> HeapSize for Budgeted<C, F> {
+    fn heap_size(&self) -> (usize, usize) { self.inner.heap_size() }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::{HeapSize, Len, TryPush};
+    use super::{Backpressure, Budgeted, Rejected};
+
+    #[test]
+    fn backpressure() {
+        // Flushes move the rows elsewhere, and the budget bounds each flush.
+        let mut flushed: Vec<Vec<u64>> = Vec::new();
+        let mut builder = Budgeted::new(Vec::<u64>::new(), 64, |rows: &mut Vec<u64>| {
+            flushed.push(rows.clone());
+            Backpressure::Clear
+        });
+        builder.try_extend(0 .. 100u64).unwrap();
+        assert_eq!(builder.len(), 4);
+        assert!(builder.heap_size().0 <= 64);
+        drop(builder);
+        assert_eq!(flushed.len(), 12);
+        assert!(flushed.iter().all(|rows| rows.len() == 8));
+
+        // Rejected items are returned, and leave the rows unchanged.
+        let mut builder = Budgeted::new(Vec::<u64>::new(), 16, |_: &mut Vec<u64>| Backpressure::Reject);
+        assert_eq!(builder.try_extend(0 .. 10u64), Err(Rejected(2)));
+        assert_eq!(builder.inner(), &[0, 1]);
+        assert_eq!(builder.remaining(), 0);
+
+        let mut builder = Budgeted::new(Vec::<u64>::new(), 16, |_: &mut Vec<u64>| Backpressure::Admit);
+        builder.try_extend(0 .. 10u64).unwrap();
+        assert_eq!(builder.into_inner().len(), 10);
+    }
+}
@@ -0,0 +1,327 @@
+//! Compressed encodings of primitive columns, for storage and transmission.
+//!
+//! Each codec encodes a slice of values into a sequence of `u64` words, which is the unit of
+//! the `bytes::serialization` layout, and decodes words back into a `Vec` of values. The
+//! encodings start with the number of values and the number of words that follow, so that
+//! several can be written one after another and decoded in order. Decoding returns `None` for
+//! words that are not a valid encoding, rather than panicking or reading out of bounds.
+//!
+//! The codecs are standalone, and `bytes::serialization` neither selects nor records them.
+
+/// Writes values of up to 64 bits each into a sequence of words, least significant bits first.
+struct BitWriter<'a> {
+    store: &'a mut Vec<u64>,
+    word: u64,
+    used: u32,
+}
+
+impl<'a> BitWriter<'a> {
+    fn new(store: &'a mut Vec<u64>) -> Self { Self { store, word: 0, used: 0 } }
+    /// Writes the low `bits` bits of `value`.
+    #[inline(always)] fn write(&mut self, value: u64, bits: u32) {
+        if bits == 0 { return; }
+        let value = if bits == 64 { value } else { value & ((1 << bits) - 1) };
+        self.word |= value << self.used;
+        if self.used + bits >= 64 {
+            self.store.push(self.word);
+            self.word = value.checked_shr(64 - self.used).unwrap_or(0);
+            self.used = self.used + bits - 64;
+        } else {
+            self.used += bits;
+        }
+    }
+    /// Writes any incomplete word.
+    fn finish(self) {
+        if self.used > 0 { self.store.push(self.word); }
+    }
+}
+
+/// Reads values written by a `BitWriter`.
+struct BitReader<'a> {
+    words: &'a [u64],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(words: &'a [u64]) -> Self { Self { words, position: 0 } }
+    /// Reads `bits` bits as the low bits of the result, or `None` if fewer remain.
+    #[inline(always)] fn read(&mut self, bits: u32) -> Option<u64> {
+        if bits == 0 { return Some(0); }
+        if self.position + bits as usize > 64 * self.words.len() { return None; }
+        let index = self.position / 64;
+        let offset = (self.position % 64) as u32;
+        let mut value = self.words[index] >> offset;
+        if offset + bits > 64 {
+            value |= self.words[index + 1] << (64 - offset);
+        }
+        self.position += bits as usize;
+        Some(if bits == 64 { value } else { value & ((1 << bits) - 1) })
+    }
+}
+
+/// Writes the header of an encoding, and returns the position of its word count.
+fn begin(store: &mut Vec<u64>, count: usize) -> usize {
+    store.push(count as u64);
+    store.push(0);
+    store.len() - 1
+}
+/// Records the number of words written since `begin`.
+fn end(store: &mut [u64], position: usize) {
+    store[position] = (store.len() - position - 1) as u64;
+}
+/// Reads the header of an encoding, returning the number of values and their words.
+fn header(store: &[u64]) -> Option<(usize, &[u64])> {
+    let count = usize::try_from(*store.first()?).ok()?;
+    let words = usize::try_from(*store.get(1)?).ok()?;
+    Some((count, store.get(2 .. words.checked_add(2)?)?))
+}
+
+pub use gorilla::XorFloat;
+/// XOR-based compression for floating point values, as in Facebook's Gorilla.
+///
+/// Each value is XORed with its predecessor. Values that repeat take a single bit, and values
+/// close to their predecessor share their sign, exponent, and leading mantissa bits, leaving a
+/// short run of meaningful bits to record. Slowly varying measurements compress substantially.
+///
+/// As with `delta`, `bytes::serialization` does not use this codec: it borrows each column as a
+/// slice of the serialized words, and an XOR-encoded column has no such slice until it is decoded
+/// into a `Vec`. Encode float columns with `encode` before writing them, and `decode` after reading.
+pub mod gorilla {
+
+    use super::{BitReader, BitWriter};
+
+    /// A floating point type, viewed as bits for XOR compression.
+    pub trait XorFloat: Copy {
+        /// The number of bits in the type.
+        const BITS: u32;
+        /// The bits of `self`, in the low bits of the result.
+        fn to_word(self) -> u64;
+        /// The value whose bits are the low bits of `word`.
+        fn from_word(word: u64) -> Self;
+    }
+    impl XorFloat for f64 {
+        const BITS: u32 = 64;
+        #[inline(always)] fn to_word(self) -> u64 { self.to_bits() }
+        #[inline(always)] fn from_word(word: u64) -> Self { f64::from_bits(word) }
+    }
+    impl XorFloat for f32 {
+        const BITS: u32 = 32;
+        #[inline(always)] fn to_word(self) -> u64 { self.to_bits() as u64 }
+        #[inline(always)] fn from_word(word: u64) -> Self { f32::from_bits(word as u32) }
+    }
+
+    /// Appends the compressed form of `values` to `store`.
+    pub fn encode<F: XorFloat>(values: &[F], store: &mut Vec<u64>) {
+        let header = super::begin(store, values.len());
+        let mut writer = BitWriter::new(store);
+        if let Some(first) = values.first() {
+            writer.write(first.to_word(), F::BITS);
+            let mut prior = first.to_word();
+            // The leading and trailing zeros of the prior meaningful window.
+            let mut window: Option<(u32, u32)> = None;
+            for value in values[1..].iter() {
+                let word = value.to_word();
+                let xor = word ^ prior;
+                prior = word;
+                if xor == 0 {
+                    writer.write(0, 1);
+                    continue;
+                }
+                writer.write(1, 1);
+                // Leading zeros within `F::BITS`, capped to fit in five bits.
+                let leading = (xor.leading_zeros() - (64 - F::BITS)).min(31);
+                let trailing = xor.trailing_zeros();
+                match window {
+                    Some((prior_leading, prior_trailing)) if leading >= prior_leading && trailing >= prior_trailing => {
+                        writer.write(0, 1);
+                        writer.write(xor >> prior_trailing, F::BITS - prior_leading - prior_trailing);
+                    }
+                    _ => {
+                        let meaningful = F::BITS - leading - trailing;
+                        writer.write(1, 1);
+                        writer.write(leading as u64, 5);
+                        writer.write((meaningful - 1) as u64, 6);
+                        writer.write(xor >> trailing, meaningful);
+                        window = Some((leading, trailing));
+                    }
+                }
+            }
+        }
+        writer.finish();
+        super::end(store, header);
+    }
+
+    /// Decodes values compressed by `encode` at the start of `store`.
+    pub fn decode<F: XorFloat>(store: &[u64]) -> Option<Vec<F>> {
+        let (count, words) = super::header(store)?;
+        // Each value takes at least one bit, which bounds the allocation for corrupt counts.
+        let mut values = Vec::with_capacity(count.min(64 * words.len() + 1));
+        if count == 0 { return Some(values); }
+        let mut reader = BitReader::new(words);
+        let mut prior = reader.read(F::BITS)?;
+        values.push(F::from_word(prior));
+        let (mut leading, mut trailing) = (0, 0);
+        while values.len() < count {
+            if reader.read(1)? == 1 {
+                if reader.read(1)? == 1 {
+                    leading = reader.read(5)? as u32;
+                    let meaningful = reader.read(6)? as u32 + 1;
+                    trailing = F::BITS.checked_sub(leading + meaningful)?;
+                }
+                prior ^= reader.read(F::BITS - leading - trailing)? << trailing;
+            }
+            values.push(F::from_word(prior));
+        }
+        Some(values)
+    }
+
+    #[cfg(test)]
+    mod test {
+
+        #[test]
+        fn round_trip() {
+            let series: Vec<f64> = (0 .. 1000).map(|i| 20.0 + ((i / 10) as f64) * 0.25).collect();
+            let mut store = Vec::new();
+            super::encode(&series, &mut store);
+            assert!(store.len() < series.len() / 4);
+            assert_eq!(super::decode::<f64>(&store), Some(series));
+
+            let awkward = vec![0.0, -0.0, f64::NAN, f64::INFINITY, f64::MIN_POSITIVE, 1e300, -1.5, -1.5];
+            let mut store = Vec::new();
+            super::encode(&awkward, &mut store);
+            let decoded = super::decode::<f64>(&store).unwrap();
+            assert!(decoded.iter().zip(awkward.iter()).all(|(a, b)| a.to_bits() == b.to_bits()));
+
+            let floats: Vec<f32> = (0 .. 100).map(|i| (i as f32).sin()).collect();
+            let mut store = vec![7];
+            super::encode(&floats, &mut store);
+            super::encode::<f32>(&[], &mut store);
+            assert_eq!(super::decode::<f32>(&store[1..]), Some(floats));
+            assert_eq!(super::decode::<f32>(&store[store.len() - 2 ..]), Some(Vec::<f32>::new()));
+        }
+
+        #[test]
+        fn malformed() {
+            let mut store = Vec::new();
+            super::encode(&[1.0f64, 2.0, 3.0], &mut store);
+            assert_eq!(super::decode::<f64>(&store[.. store.len() - 1]), None);
+            store[0] = u64::MAX;
+            assert_eq!(super::decode::<f64>(&store), None);
+            // A window of more meaningful bits than the type holds.
+            assert_eq!(super::decode::<f32>(&[2, 1, u64::MAX]), None);
+        }
+    }
+}
+
+/// Delta-of-delta encoding for integer columns that increase steadily, like timestamps.
+///
+/// The first value is recorded in full, then the difference to the second, and from then on
+/// the change in the difference. Regularly spaced values have changes of zero, and each change
+/// is zigzag encoded and written as a variable length integer, taking a single byte when small.
+///
+/// The codec is not selected by `bytes::serialization`, which presents columns as borrowed
+/// slices of the serialized words; an encoded column must be decoded into owned storage first.
+/// Callers encode a column's values into words of their own, and decode them on receipt.
+pub mod delta {
+
+    use super::{BitReader, BitWriter};
+
+    /// Maps signed integers to unsigned, so that values near zero have few significant bits.
+    #[inline(always)] fn zigzag(value: i64) -> u64 { ((value << 1) ^ (value >> 63)) as u64 }
+    /// Inverts `zigzag`.
+    #[inline(always)] fn unzigzag(value: u64) -> i64 { ((value >> 1) as i64) ^ -((value & 1) as i64) }
+
+    /// Writes `value` seven bits at a time, with the high bit of each byte set if more follow.
+    fn write_varint(writer: &mut BitWriter, mut value: u64) {
+        while value >= 0x80 {
+            writer.write((value & 0x7f) | 0x80, 8);
+            value >>= 7;
+        }
+        writer.write(value, 8);
+    }
+    /// Reads a value written by `write_varint`, or `None` if it is truncated or exceeds 64 bits.
+    fn read_varint(reader: &mut BitReader) -> Option<u64> {
+        let mut value = 0;
+        let mut shift = 0;
+        loop {
+            let byte = reader.read(8)?;
+            if shift > 63 { return None; }
+            value |= (byte & 0x7f) << shift;
+            if byte & 0x80 == 0 { return Some(value); }
+            shift += 7;
+        }
+    }
+
+    /// Appends the delta-of-delta encoding of `values` to `store`.
+    ///
+    /// Values need not increase, though the encoding is most compact when they increase steadily.
+    /// Differences are computed with wrapping arithmetic, and all values round trip exactly.
+    pub fn encode(values: &[u64], store: &mut Vec<u64>) {
+        let header = super::begin(store, values.len());
+        let mut writer = BitWriter::new(store);
+        if let Some(first) = values.first() {
+            writer.write(*first, 64);
+            let mut prior = *first;
+            let mut prior_delta = 0i64;
+            for value in values[1..].iter() {
+                let delta = value.wrapping_sub(prior) as i64;
+                write_varint(&mut writer, zigzag(delta.wrapping_sub(prior_delta)));
+                prior = *value;
+                prior_delta = delta;
+            }
+        }
+        writer.finish();
+        super::end(store, header);
+    }
+
+    /// Decodes values encoded by `encode` at the start of `store`.
+    pub fn decode(store: &[u64]) -> Option<Vec<u64>> {
+        let (count, words) = super::header(store)?;
+        // Each value takes at least one byte, which bounds the allocation for corrupt counts.
+        let mut values = Vec::with_capacity(count.min(8 * words.len() + 1));
+        if count == 0 { return Some(values); }
+        let mut reader = BitReader::new(words);
+        let mut prior = reader.read(64)?;
+        let mut prior_delta = 0i64;
+        values.push(prior);
+        while values.len() < count {
+            let delta = prior_delta.wrapping_add(unzigzag(read_varint(&mut reader)?));
+            prior = prior.wrapping_add(delta as u64);
+            prior_delta = delta;
+            values.push(prior);
+        }
+        Some(values)
+    }
+
+    #[cfg(test)]
+    mod test {
+
+        #[test]
+        fn round_trip() {
+            let timestamps: Vec<u64> = (0 .. 1000).map(|i| 1_700_000_000_000 + 1000 * i + (i % 7 == 0) as u64).collect();
+            let mut store = Vec::new();
+            super::encode(&timestamps, &mut store);
+            assert!(store.len() < timestamps.len() / 6);
+            assert_eq!(super::decode(&store), Some(timestamps));
+
+            let awkward = vec![u64::MAX, 0, u64::MAX, 5, 3, 3, 1 << 63];
+            let mut store = Vec::new();
+            super::encode(&awkward, &mut store);
+            assert_eq!(super::decode(&store), Some(awkward));
+            for value in [0i64, -1, 1, i64::MIN, i64::MAX] {
+                assert_eq!(super::unzigzag(super::zigzag(value)), value);
+            }
+        }
+
+        #[test]
+        fn malformed() {
+            let mut store = Vec::new();
+            super::encode(&[5, 10, 15], &mut store);
+            assert_eq!(super::decode(&store[.. store.len() - 1]), None);
+            store[0] = 100;
+            assert_eq!(super::decode(&store), None);
+            // A varint whose continuation bits run past 64 bits.
+            assert_eq!(super::decode(&[2, 3, 0, u64::MAX, u64::MAX]), None);
+        }
+    }
+}
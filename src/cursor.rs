@@ -0,0 +1,113 @@
+//! Stateful navigation through sorted containers.
+//!
+//! A cursor tracks a position in a container, and moves forward by stepping one row
+//! at a time or by seeking to the first row at least some key. Operators that walk
+//! several sorted inputs in tandem, like merge joins, can keep a cursor for each input
+//! rather than repeatedly binary searching from the start.
+
+use crate::{Index, Len};
+
+/// A position in a container, whose rows are assumed to be sorted.
+///
+/// The cursor only moves forward, except when explicitly rewound.
+#[derive(Debug)]
+pub struct Cursor<'a, C> {
+    container: &'a C,
+    position: usize,
+}
+
+impl<C> Clone for Cursor<'_, C> {
+    fn clone(&self) -> Self { *self }
+}
+impl<C> Copy for Cursor<'_, C> { }
+
+impl<'a, C: Index + Len> Cursor<'a, C> {
+    /// A cursor positioned at the first row of `container`.
+    pub fn new(container: &'a C) -> Self {
+        Self { container, position: 0 }
+    }
+    /// The index of the row the cursor is positioned at.
+    #[inline(always)] pub fn position(&self) -> usize { self.position }
+    /// True if the cursor is positioned at a row, rather than past the last row.
+    #[inline(always)] pub fn valid(&self) -> bool { self.position < self.container.len() }
+    /// The row the cursor is positioned at, if it is valid.
+    #[inline(always)] pub fn peek(&self) -> Option<C::Ref> {
+        if self.valid() { Some(self.container.get(self.position)) } else { None }
+    }
+    /// Advances the cursor by one row, if it is valid.
+    #[inline(always)] pub fn step(&mut self) {
+        if self.valid() { self.position += 1; }
+    }
+    /// Returns the cursor to the first row.
+    #[inline(always)] pub fn rewind(&mut self) { self.position = 0; }
+
+    /// Advances the cursor to the first row at least `key`, or past the end if there is none.
+    ///
+    /// The cursor never moves backwards, so keys less than the current row have no effect.
+    pub fn seek(&mut self, key: &C::Ref) where C::Ref: Ord {
+        self.seek_by(|item| item < key);
+    }
+
+    /// Advances the cursor past rows for which `less` returns true.
+    ///
+    /// The predicate must be true for some prefix of the remaining rows, and false after.
+    /// Rows are visited by galloping: exponentially larger steps followed by a binary search,
+    /// which takes time logarithmic in the distance moved rather than the container's length.
+    pub fn seek_by<F: FnMut(&C::Ref) -> bool>(&mut self, mut less: F) {
+        let len = self.container.len();
+        if self.position >= len || !less(&self.container.get(self.position)) { return; }
+        // Invariant: `less` holds at `self.position`.
+        let mut step = 1;
+        while self.position + step < len && less(&self.container.get(self.position + step)) {
+            self.position += step;
+            step <<= 1;
+        }
+        // `less` holds at `self.position` and fails at `self.position + step` (or the end).
+        step >>= 1;
+        while step > 0 {
+            if self.position + step < len && less(&self.container.get(self.position + step)) {
+                self.position += step;
+            }
+            step >>= 1;
+        }
+        self.position += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::{Columnar, Container};
+    use super::Cursor;
+
+    #[test]
+    fn seek() {
+        let targets: Vec<u64> = (0 .. 3000).collect();
+        let column = Columnar::into_columns((0 .. 1000u64).map(|i| 3 * i));
+        let borrowed = Container::<u64>::borrow(&column);
+        let mut cursor = Cursor::new(&borrowed);
+        assert_eq!(cursor.peek(), Some(&0));
+        cursor.seek(&&10);
+        assert_eq!(cursor.peek(), Some(&12));
+        cursor.step();
+        assert_eq!(cursor.peek(), Some(&15));
+        cursor.seek(&&5);
+        assert_eq!(cursor.peek(), Some(&15));
+        cursor.seek(&&2997);
+        assert_eq!(cursor.peek(), Some(&2997));
+        cursor.seek(&&2998);
+        assert!(!cursor.valid());
+        cursor.rewind();
+        for target in targets.iter() {
+            cursor.seek(&target);
+            assert_eq!(cursor.peek(), Some(&(target.div_ceil(3) * 3)).filter(|x| **x < 3000));
+        }
+
+        let names: Vec<String> = ["ant", "bee", "cat", "dog"].iter().map(|x| x.to_string()).collect();
+        let column = Columnar::as_columns(names.iter());
+        let borrowed = Container::<String>::borrow(&column);
+        let mut cursor = Cursor::new(&borrowed);
+        cursor.seek(&"bird");
+        assert_eq!(cursor.peek(), Some("cat"));
+    }
+}
@@ -0,0 +1,423 @@
+//! Differences between containers of the same type.
+//!
+//! Differences are reported as row indices into each input, which can be gathered
+//! into containers of their own using `Container::take`.
+//!
+//! A `Delta` packages a difference for transmission: the removed rows of a base container
+//! by index, and the inserted rows as a container. A replica holding the base can rebuild
+//! the next container with `apply_delta`, after receiving only the changed rows.
+//!
+//! A patch is a difference between two encodings, rather than between rows. `diff_encoded`
+//! compares encodings in the `bytes::serialization` layout buffer by buffer, and records for
+//! each buffer of the new encoding only the bytes that differ from the corresponding buffer of
+//! the old encoding. A replica holding the old encoding rebuilds the new one with `apply_patch`.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::{AsBytes, Columnar, Container, Index, Len};
+use crate::bytes::serialization::try_decode;
+
+/// Rows present in one container but not in another.
+///
+/// Rows are treated as a multiset: a row that occurs more times in one container
+/// than the other will be reported for each of its unmatched occurrences.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Diff {
+    /// Rows of the old container without a matching row in the new container, in order.
+    pub removed: Vec<usize>,
+    /// Rows of the new container without a matching row in the old container, in order.
+    pub inserted: Vec<usize>,
+}
+
+impl Diff {
+    /// True when the two containers held the same rows.
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.inserted.is_empty()
+    }
+}
+
+/// Compares two containers that are each sorted by `cmp`.
+///
+/// This performs a single merge-like pass over both containers.
+pub fn diff_sorted<C, F>(old: C, new: C, mut cmp: F) -> Diff
+where
+    C: Index + Len,
+    F: FnMut(&C::Ref, &C::Ref) -> Ordering,
+{
+    let mut diff = Diff::default();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        match cmp(&old.get(i), &new.get(j)) {
+            Ordering::Less => { diff.removed.push(i); i += 1; },
+            Ordering::Greater => { diff.inserted.push(j); j += 1; },
+            Ordering::Equal => { i += 1; j += 1; },
+        }
+    }
+    diff.removed.extend(i .. old.len());
+    diff.inserted.extend(j .. new.len());
+    diff
+}
+
+/// Compares two containers in arbitrary order, by hashing the rows of `old`.
+///
+/// Matching rows are paired in order of occurrence, so that repeated rows are
+/// matched first-to-first and unmatched occurrences are the later ones.
+pub fn diff_hashed<C>(old: C, new: C) -> Diff
+where
+    C: Index + Len,
+    C::Ref: Hash + Eq,
+{
+    let mut unmatched: HashMap<C::Ref, std::collections::VecDeque<usize>> = HashMap::with_capacity(old.len());
+    for index in 0 .. old.len() {
+        unmatched.entry(old.get(index)).or_default().push_back(index);
+    }
+    let mut diff = Diff::default();
+    for index in 0 .. new.len() {
+        match unmatched.get_mut(&new.get(index)).and_then(|rows| rows.pop_front()) {
+            Some(_) => { },
+            None => diff.inserted.push(index),
+        }
+    }
+    diff.removed = unmatched.into_values().flatten().collect();
+    diff.removed.sort();
+    diff
+}
+
+/// Compares two containers in arbitrary order, using the rows' hashes.
+///
+/// A convenience wrapper around `diff_hashed` for containers of `T`.
+pub fn diff<T: Columnar>(old: &T::Container, new: &T::Container) -> Diff
+where
+    for<'a> T::Ref<'a>: Hash + Eq,
+{
+    diff_hashed(old.borrow(), new.borrow())
+}
+
+/// The changes from a base container to a next container, with inserted rows held in `C`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Delta<C> {
+    /// Rows of the base container absent from the next container, in increasing order.
+    pub removed: Vec<usize>,
+    /// Rows of the next container absent from the base container.
+    pub inserted: C,
+}
+
+/// Appends the encoded `delta` to `store`.
+///
+/// The encoding is the number of removed rows, their indices, and then the inserted rows
+/// in the `bytes::serialization` layout. A base container can be sent with that layout too.
+pub fn encode_delta<T: Columnar>(delta: &Delta<T::Container>, store: &mut Vec<u64>) {
+    store.push(delta.removed.len() as u64);
+    std::iter::Extend::extend(store, delta.removed.iter().map(|index| *index as u64));
+    crate::bytes::serialization::encode(store, delta.inserted.borrow().as_bytes());
+}
+
+/// Decodes a delta encoded by `encode_delta` in `store`.
+///
+/// Returns `None` if `store` is not in the layout `encode_delta` produces, if the removed rows are
+/// not in strictly increasing order, or if the inserted rows are not a container of `T` whose rows
+/// can be read, as checked by `bytes::serialization::try_decode_owned`.
+pub fn decode_delta<T: Columnar>(store: &[u64]) -> Option<Delta<T::Container>> {
+    let (count, rest) = store.split_first()?;
+    let count = usize::try_from(*count).ok().filter(|count| *count <= rest.len())?;
+    let (removed, rest) = rest.split_at(count);
+    if removed.windows(2).any(|pair| pair[0] >= pair[1]) { return None; }
+    let removed = removed.iter().map(|index| usize::try_from(*index).ok()).collect::<Option<Vec<_>>>()?;
+    let inserted = crate::bytes::serialization::try_decode_owned::<T>(rest)?;
+    Some(Delta { removed, inserted })
+}
+
+/// The delta from `base` to `next`, matching rows as a multiset by their hashes.
+///
+/// Applying the delta to `base` produces the rows of `next`, though not necessarily in their order.
+pub fn delta<T: Columnar>(base: &T::Container, next: &T::Container) -> Delta<T::Container>
+where
+    for<'a> T::Ref<'a>: Hash + Eq,
+{
+    let diff = diff::<T>(base, next);
+    Delta { removed: diff.removed, inserted: next.take(&diff.inserted) }
+}
+
+/// The delta from `base` to `next`, when `next` consists of the rows of `base` followed by new rows.
+///
+/// Returns `None` if the rows of `base` are not a prefix of the rows of `next`.
+pub fn delta_appended<T: Columnar>(base: &T::Container, next: &T::Container) -> Option<Delta<T::Container>>
+where
+    for<'a> T::Ref<'a>: PartialEq,
+{
+    let (base_rows, next_rows) = (base.borrow(), next.borrow());
+    if base_rows.len() > next_rows.len() || (0 .. base_rows.len()).any(|index| base_rows.get(index) != next_rows.get(index)) {
+        return None;
+    }
+    let mut inserted = T::Container::default();
+    for index in base_rows.len() .. next_rows.len() {
+        crate::Push::push(&mut inserted, next_rows.get(index));
+    }
+    Some(Delta { removed: Vec::new(), inserted })
+}
+
+/// A reason a delta cannot be applied to a base container.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InvalidDelta {
+    /// The removed row at position `index` does not exceed the removed row before it.
+    Unsorted { index: usize },
+    /// The removed row `row` is not a row of the base container, which has `rows` rows.
+    OutOfBounds { row: usize, rows: usize },
+}
+
+impl std::fmt::Display for InvalidDelta {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvalidDelta::Unsorted { index } => write!(f, "removed row at position {} is not in increasing order", index),
+            InvalidDelta::OutOfBounds { row, rows } => write!(f, "removed row {} is out of bounds for {} rows", row, rows),
+        }
+    }
+}
+impl std::error::Error for InvalidDelta { }
+
+/// Applies `delta` to `base`, producing the rows of `base` not removed, followed by those inserted.
+///
+/// Returns an error if the removed rows are not in strictly increasing order, or are not rows of `base`.
+pub fn apply_delta<T: Columnar>(base: &T::Container, delta: &Delta<T::Container>) -> Result<T::Container, InvalidDelta> {
+    let (base_rows, inserted) = (base.borrow(), delta.inserted.borrow());
+    if let Some(index) = (1 .. delta.removed.len()).find(|index| delta.removed[index - 1] >= delta.removed[*index]) {
+        return Err(InvalidDelta::Unsorted { index });
+    }
+    if let Some(&row) = delta.removed[..].last().filter(|row| **row >= base_rows.len()) {
+        return Err(InvalidDelta::OutOfBounds { row, rows: base_rows.len() });
+    }
+    let mut result = T::Container::default();
+    let mut removed = delta.removed.iter().peekable();
+    for index in 0 .. base_rows.len() {
+        if removed.next_if_eq(&&index).is_none() {
+            crate::Push::push(&mut result, base_rows.get(index));
+        }
+    }
+    for index in 0 .. inserted.len() {
+        crate::Push::push(&mut result, inserted.get(index));
+    }
+    Ok(result)
+}
+
+/// A patch that rebuilds the encoding `new` from the encoding `old`, with `apply_patch`.
+///
+/// Each buffer of `new` is recorded as the lengths of the prefix and suffix it shares with the
+/// buffer of `old` at the same position, and the bytes between them. An unchanged buffer costs
+/// two words, and a buffer with a small change costs about the size of the change. Encodings
+/// not in the `bytes::serialization` layout, like those of batches, are compared as one buffer.
+///
+/// The patch is a word indicating whether the encodings are in the layout, the number of buffers,
+/// the prefix and suffix lengths of each buffer, and then the changed bytes in the layout.
+pub fn diff_encoded(old: &[u64], new: &[u64]) -> Vec<u64> {
+    let (layout, old_buffers, new_buffers) = match (try_decode(old), try_decode(new)) {
+        (Some(old_buffers), Some(new_buffers)) => (1, old_buffers, new_buffers),
+        _ => (0, vec![bytemuck::cast_slice(old)], vec![bytemuck::cast_slice(new)]),
+    };
+    let mut patch = vec![layout, new_buffers.len() as u64];
+    let mut changes = Vec::with_capacity(new_buffers.len());
+    for (index, new) in new_buffers.iter().copied().enumerate() {
+        let old = <[&[u8]]>::get(&old_buffers, index).copied().unwrap_or_default();
+        let prefix = old.iter().zip(new.iter()).take_while(|(x, y)| x == y).count();
+        let suffix = old[prefix ..].iter().rev().zip(new[prefix ..].iter().rev()).take_while(|(x, y)| x == y).count();
+        patch.push(prefix as u64);
+        patch.push(suffix as u64);
+        changes.push((1, &new[prefix .. new.len() - suffix]));
+    }
+    crate::bytes::serialization::encode(&mut patch, IntoIterator::into_iter(changes));
+    patch
+}
+
+/// Rebuilds the encoding that `patch`, produced by `diff_encoded`, was computed against `old` from.
+///
+/// Returns `None` if `patch` is malformed, or if `old` is not an encoding it could have been computed
+/// against: one in the layout the patch expects, with buffers long enough for the shared bytes.
+pub fn apply_patch(old: &[u64], patch: &[u64]) -> Option<Vec<u64>> {
+    let (layout, rest) = patch.split_first()?;
+    let (count, rest) = rest.split_first()?;
+    let layout = match layout { 0 => false, 1 => true, _ => return None };
+    let count = usize::try_from(*count).ok().filter(|count| *count <= rest.len() / 2)?;
+    if !layout && count != 1 { return None; }
+    let (lengths, rest) = rest.split_at(2 * count);
+    let changes = try_decode(rest)?;
+    if changes.len() != count { return None; }
+    let old_buffers = if layout { try_decode(old)? } else { vec![bytemuck::cast_slice(old)] };
+    let mut new_buffers = Vec::with_capacity(count);
+    for (index, (change, lengths)) in IntoIterator::into_iter(changes).zip(lengths.chunks_exact(2)).enumerate() {
+        let old = <[&[u8]]>::get(&old_buffers, index).copied().unwrap_or_default();
+        let prefix = usize::try_from(lengths[0]).ok()?;
+        let suffix = usize::try_from(lengths[1]).ok()?;
+        if prefix.checked_add(suffix)? > old.len() { return None; }
+        let mut buffer = Vec::with_capacity(prefix + change.len() + suffix);
+        buffer.extend_from_slice(&old[.. prefix]);
+        buffer.extend_from_slice(change);
+        buffer.extend_from_slice(&old[old.len() - suffix ..]);
+        new_buffers.push(buffer);
+    }
+
+    let mut result = Vec::new();
+    if layout {
+        crate::bytes::serialization::encode(&mut result, new_buffers.iter().map(|buffer| (1, &buffer[..])));
+    } else {
+        if !new_buffers[0].len().is_multiple_of(8) { return None; }
+        Extend::extend(&mut result, new_buffers[0].chunks_exact(8).map(|word| u64::from_ne_bytes(word.try_into().unwrap())));
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::Columnar;
+
+    #[test]
+    fn deltas() {
+        use crate::{Container, AsBytes};
+        use super::{apply_delta, decode_delta, delta, delta_appended, encode_delta};
+
+        type Row = (u64, String);
+        let base: Vec<Row> = (0 .. 100).map(|i| (i, format!("{}", i))).collect();
+        let mut next = base.clone();
+        next[10].1 = "ten".to_string();
+        next.remove(50);
+        next.push((100, "100".to_string()));
+        let base_c = Columnar::as_columns(base.iter());
+        let next_c = Columnar::as_columns(next.iter());
+
+        let delta = delta::<Row>(&base_c, &next_c);
+        assert_eq!(delta.removed, vec![10, 50]);
+        let mut store = Vec::new();
+        encode_delta::<Row>(&delta, &mut store);
+        assert!(store.len() < next_c.borrow().length_in_words() / 10);
+        let decoded = decode_delta::<Row>(&store).unwrap();
+        assert_eq!(decoded, delta);
+        let applied_c = apply_delta::<Row>(&base_c, &decoded).unwrap();
+        let mut applied: Vec<Row> = (0 .. next.len()).map(|i| applied_c.materialize(i)).collect();
+        let mut expected = next.clone();
+        applied.sort();
+        expected.sort();
+        assert_eq!(applied, expected);
+
+        let mut grown = base.clone();
+        grown.extend((100 .. 105).map(|i| (i, format!("{}", i))));
+        let grown_c = Columnar::as_columns(grown.iter());
+        assert!(delta_appended::<Row>(&base_c, &next_c).is_none());
+        let delta = delta_appended::<Row>(&base_c, &grown_c).unwrap();
+        assert_eq!(apply_delta::<Row>(&base_c, &delta), Ok(grown_c));
+    }
+
+    #[test]
+    fn patches() {
+        use crate::{Container, AsBytes};
+        use crate::bytes::serialization;
+        use super::{apply_patch, diff_encoded};
+
+        type Row = (u64, String);
+        let old: Vec<Row> = (0 .. 1000).map(|i| (i, format!("{}", i))).collect();
+        let mut new = old.clone();
+        new[500].0 = 5000;
+        new[700].1 = "7oo".to_string();
+        let (mut old_store, mut new_store) = (Vec::new(), Vec::new());
+        serialization::encode(&mut old_store, Columnar::as_columns(old.iter()).borrow().as_bytes());
+        serialization::encode(&mut new_store, Columnar::as_columns(new.iter()).borrow().as_bytes());
+
+        let patch = diff_encoded(&old_store, &new_store);
+        assert!(patch.len() < new_store.len() / 10);
+        assert_eq!(apply_patch(&old_store, &patch), Some(new_store.clone()));
+        assert_eq!(apply_patch(&new_store, &diff_encoded(&new_store, &old_store)), Some(old_store));
+
+        // Encodings in other layouts are compared as a whole.
+        let old_words = vec![1000, 1, 2, 3];
+        let new_words = vec![1000, 1, 4, 3, 5];
+        assert_eq!(apply_patch(&old_words, &diff_encoded(&old_words, &new_words)), Some(new_words));
+    }
+
+    #[test]
+    fn malformed_patches() {
+        use crate::{Container, AsBytes};
+        use crate::bytes::serialization;
+        use super::{apply_patch, decode_delta, diff_encoded, Delta, encode_delta};
+
+        type Row = (u64, String);
+        let old: Vec<Row> = (0 .. 100).map(|i| (i, format!("{}", i))).collect();
+        let mut new = old.clone();
+        new[50].1 = "fifty".to_string();
+        let (mut old_store, mut new_store) = (Vec::new(), Vec::new());
+        serialization::encode(&mut old_store, Columnar::as_columns(old.iter()).borrow().as_bytes());
+        serialization::encode(&mut new_store, Columnar::as_columns(new.iter()).borrow().as_bytes());
+        let patch = diff_encoded(&old_store, &new_store);
+
+        // Truncations of the patch are rejected, rather than read past.
+        for length in 0 .. patch.len() {
+            assert_eq!(apply_patch(&old_store, &patch[.. length]), None);
+        }
+        // Lengths that reach beyond the base, or counts beyond the patch, are rejected.
+        let mut hostile = patch.clone();
+        hostile[2] = u64::MAX;
+        assert_eq!(apply_patch(&old_store, &hostile), None);
+        let mut hostile = patch.clone();
+        hostile[1] = u64::MAX;
+        assert_eq!(apply_patch(&old_store, &hostile), None);
+        let mut hostile = patch.clone();
+        hostile[0] = 2;
+        assert_eq!(apply_patch(&old_store, &hostile), None);
+        // A base that is not in the layout, or too short for the shared bytes, is rejected.
+        assert_eq!(apply_patch(&[1000, 1, 2], &patch), None);
+        assert_eq!(apply_patch(&[], &patch), None);
+
+        let delta = Delta { removed: vec![3, 5], inserted: Columnar::as_columns(new[.. 10].iter()) };
+        let mut store = Vec::new();
+        encode_delta::<Row>(&delta, &mut store);
+        for length in 0 .. store.len() {
+            assert!(decode_delta::<Row>(&store[.. length]).is_none());
+        }
+        store[0] = u64::MAX;
+        assert!(decode_delta::<Row>(&store).is_none());
+        // Slices of the wrong count or length for the type are rejected.
+        assert!(decode_delta::<u64>(&[0, 3, 0]).is_none());
+        assert!(decode_delta::<(u64, u64)>(&[0, 8, 0]).is_none());
+        // Inserted strings whose bounds reach past their bytes are rejected.
+        let mut store = vec![0];
+        serialization::encode(&mut store, [(8, bytemuck::cast_slice(&[100u64][..])), (1, &b"a"[..])].into_iter());
+        assert!(decode_delta::<String>(&store).is_none());
+        // Removed rows that are unsorted or repeated are rejected.
+        for removed in [[5, 3], [3, 3]] {
+            let mut store = vec![2];
+            store.extend(removed);
+            serialization::encode(&mut store, Columnar::as_columns(new[.. 10].iter()).borrow().as_bytes());
+            assert!(decode_delta::<Row>(&store).is_none());
+        }
+    }
+
+    #[test]
+    fn invalid_deltas() {
+        use super::{apply_delta, Delta, InvalidDelta};
+
+        let base = Columnar::into_columns((0 .. 10u64).map(|i| format!("{}", i)));
+        let inserted = Columnar::as_columns(["new".to_string()].iter());
+        let delta = Delta { removed: vec![2, 7, 4], inserted: inserted.clone() };
+        assert_eq!(apply_delta::<String>(&base, &delta), Err(InvalidDelta::Unsorted { index: 2 }));
+        let delta = Delta { removed: vec![2, 10], inserted };
+        assert_eq!(apply_delta::<String>(&base, &delta), Err(InvalidDelta::OutOfBounds { row: 10, rows: 10 }));
+    }
+
+    #[test]
+    fn diff_strings() {
+        let old: Vec<String> = ["a", "b", "b", "c", "d"].iter().map(|x| x.to_string()).collect();
+        let new: Vec<String> = ["b", "c", "e", "a", "f"].iter().map(|x| x.to_string()).collect();
+        let old_c = Columnar::as_columns(old.iter());
+        let new_c = Columnar::as_columns(new.iter());
+        let diff = super::diff::<String>(&old_c, &new_c);
+        assert_eq!(diff.removed, vec![2, 4]);
+        assert_eq!(diff.inserted, vec![2, 4]);
+
+        let mut old_sorted = old.clone();
+        let mut new_sorted = new.clone();
+        old_sorted.sort();
+        new_sorted.sort();
+        let diff = super::diff_sorted(&old_sorted, &new_sorted, |x, y| x.cmp(y));
+        assert_eq!(diff.removed, vec![2, 4]);
+        assert_eq!(diff.inserted, vec![3, 4]);
+    }
+}
@@ -0,0 +1,155 @@
+//! Columnar stores for bitflags-style types, as columns of their underlying integers.
+//!
+//! A type that is a set of flags packed into an integer, like those produced by the
+//! `bitflags` crate or a hand-written `struct Permissions(u8)`, is stored as a column of
+//! its bits and reconstructed on access. The column of bits can be filtered directly by
+//! mask, in a loop the compiler is able to vectorize.
+//!
+//! Implement `AsBits` for the type and invoke `columnar_flags!`, or invoke it with the
+//! newtype's underlying integer to derive both, as in `columnar_flags!(Permissions(u8))`.
+
+use std::marker::PhantomData;
+use std::ops::BitAnd;
+
+use crate::{Clear, ExtendFromRange, HeapSize, Index, IndexAs, Len, Permute, Push};
+
+/// A type that converts losslessly to and from an integer of bits.
+pub trait AsBits: Copy + 'static {
+    /// The integer representation.
+    type Bits: Copy + Default + Eq + BitAnd<Output = Self::Bits> + 'static;
+    /// The bits of `self`.
+    fn to_bits(self) -> Self::Bits;
+    /// The value whose bits are `bits`.
+    fn from_bits(bits: Self::Bits) -> Self;
+}
+
+/// A column of flags, stored as their bits.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Flags<T: AsBits, BC = Vec<<T as AsBits>::Bits>> {
+    /// The bits of each value.
+    pub bits: BC,
+    marker: PhantomData<T>,
+}
+
+impl<T: AsBits, BC> Flags<T, BC> {
+    /// A column from a container of bits.
+    pub fn from_bits(bits: BC) -> Self { Self { bits, marker: PhantomData } }
+}
+
+impl<T: AsBits, BC: std::ops::Deref<Target = [T::Bits]>> Flags<T, BC> {
+    /// The indices of values with any of the flags in `mask` set.
+    pub fn any_set(&self, mask: T) -> Vec<usize> {
+        let mask = mask.to_bits();
+        let zero = T::Bits::default();
+        self.bits.iter().enumerate().filter(|(_, bits)| (**bits & mask) != zero).map(|(index, _)| index).collect()
+    }
+    /// The indices of values with all of the flags in `mask` set.
+    pub fn all_set(&self, mask: T) -> Vec<usize> {
+        let mask = mask.to_bits();
+        self.bits.iter().enumerate().filter(|(_, bits)| (**bits & mask) == mask).map(|(index, _)| index).collect()
+    }
+}
+
+impl<T: AsBits, BC: Clone> Clone for Flags<T, BC> {
+    fn clone(&self) -> Self { Self::from_bits(self.bits.clone()) }
+}
+impl<T: AsBits, BC: Copy> Copy for Flags<T, BC> { }
+impl<T: AsBits, BC: Default> Default for Flags<T, BC> {
+    fn default() -> Self { Self::from_bits(BC::default()) }
+}
+
+impl<T: AsBits, BC: Len> Len for Flags<T, BC> {
+    #[inline(always)] fn len(&self) -> usize { self.bits.len() }
+}
+impl<T: AsBits, BC: IndexAs<T::Bits>> Index for Flags<T, BC> {
+    type Ref = T;
+    #[inline(always)] fn get(&self, index: usize) -> Self::Ref { T::from_bits(self.bits.index_as(index)) }
+}
+impl<T: AsBits, BC: IndexAs<T::Bits>> Index for &Flags<T, BC> {
+    type Ref = T;
+    #[inline(always)] fn get(&self, index: usize) -> Self::Ref { T::from_bits(self.bits.index_as(index)) }
+}
+impl<T: AsBits, BC: Push<T::Bits>> Push<T> for Flags<T, BC> {
+    #[inline(always)] fn push(&mut self, item: T) { self.bits.push(item.to_bits()) }
+}
+impl<T: AsBits, BC: Push<T::Bits>> Push<&T> for Flags<T, BC> {
+    #[inline(always)] fn push(&mut self, item: &T) { self.bits.push(item.to_bits()) }
+}
+impl<T: AsBits, BC: Clear> Clear for Flags<T, BC> {
+    fn clear(&mut self) { self.bits.clear() }
+}
+impl<T: AsBits, BC: Permute> Permute for Flags<T, BC> {
+    fn permute(&mut self, perm: &[usize]) { self.bits.permute(perm) }
+}
+impl<T: AsBits, BC: ExtendFromRange> ExtendFromRange for Flags<T, BC> {
+    fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) { self.bits.extend_from_range(&other.bits, range) }
+}
+impl<T: AsBits, BC: HeapSize> HeapSize for Flags<T, BC> {
+    fn heap_size(&self) -> (usize, usize) { self.bits.heap_size() }
+}
+impl<'a, T: AsBits, BC: crate::AsBytes<'a>> crate::AsBytes<'a> for Flags<T, BC> {
+    fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> { self.bits.as_bytes() }
+}
+impl<'a, T: AsBits, BC: crate::FromBytes<'a>> crate::FromBytes<'a> for Flags<T, BC> {
+    fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self { Self::from_bits(BC::from_bytes(bytes)) }
+}
+
+/// Implements `Columnar` for a type implementing `AsBits`, using `Flags` as its container.
+///
+/// With a tuple newtype and its integer type, as in `columnar_flags!(Permissions(u8))`,
+/// this also implements `AsBits` using the newtype's field.
+#[macro_export]
+macro_rules! columnar_flags {
+    ($name:ident($bits:ty)) => {
+        impl $crate::flags::AsBits for $name {
+            type Bits = $bits;
+            #[inline(always)] fn to_bits(self) -> $bits { self.0 }
+            #[inline(always)] fn from_bits(bits: $bits) -> Self { $name(bits) }
+        }
+        $crate::columnar_flags!($name);
+    };
+    ($name:ty) => {
+        impl $crate::Columnar for $name {
+            type Ref<'a> = $name;
+            #[inline(always)] fn into_owned<'a>(other: Self::Ref<'a>) -> Self { other }
+            type Container = $crate::flags::Flags<$name>;
+        }
+        impl $crate::IntoOwned for $name {
+            type Owned = $name;
+            #[inline(always)] fn into_owned(self) -> Self::Owned { self }
+        }
+        impl $crate::Container<$name> for $crate::flags::Flags<$name> {
+            type Borrowed<'a> = $crate::flags::Flags<$name, &'a [<$name as $crate::flags::AsBits>::Bits]>;
+            fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
+                $crate::flags::Flags::from_bits(&self.bits[..])
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::{Columnar, Container, Index, Len};
+
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    struct Permissions(u8);
+    impl Permissions {
+        const READ: Self = Permissions(1);
+        const WRITE: Self = Permissions(2);
+        const EXECUTE: Self = Permissions(4);
+    }
+    columnar_flags!(Permissions(u8));
+
+    #[test]
+    fn any_set() {
+        let rows: Vec<Permissions> = (0 .. 8).map(Permissions).collect();
+        let column = Columnar::as_columns(rows.iter());
+        assert_eq!(column.len(), 8);
+        let borrowed = Container::<Permissions>::borrow(&column);
+        assert_eq!(borrowed.iter().collect::<Vec<_>>(), rows);
+        assert_eq!(column.any_set(Permissions::EXECUTE), vec![4, 5, 6, 7]);
+        assert_eq!(borrowed.any_set(Permissions(Permissions::READ.0 | Permissions::WRITE.0)), vec![1, 2, 3, 5, 6, 7]);
+        assert_eq!(borrowed.all_set(Permissions(Permissions::READ.0 | Permissions::WRITE.0)), vec![3, 7]);
+    }
+}
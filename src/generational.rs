@@ -0,0 +1,114 @@
+//! Containers whose `clear` takes constant time, for containers recycled at a steady cadence.
+//!
+//! Clearing most containers is cheap, but not all: a `Vec<T>` must drop each of its elements,
+//! and a container with an index (like `Interned`) must reset it. A container that holds many
+//! rows and is cleared at each batch interval then pauses for the whole of that work at once.
+//!
+//! A `Generational` container holds two containers, and writes to one of them. Clearing it
+//! retires the written container and starts a new generation in the other, which was cleared
+//! beforehand and retains its capacity. The retired container is cleared when the caller
+//! chooses, with `reclaim`, and at the latest by the following `clear`.
+
+use crate::{Clear, HeapSize, Index, Len, Push};
+
+/// A container whose `clear` retires its contents for later reclamation, in constant time.
+///
+/// The retired rows are not visible, but continue to occupy memory until reclaimed, so the
+/// container may hold up to twice the memory of its current rows.
+#[derive(Clone, Debug, Default)]
+pub struct Generational<C> {
+    /// The rows of the current generation.
+    current: C,
+    /// The rows of the prior generation, if `stale`, and otherwise an empty container.
+    retired: C,
+    /// True if `retired` holds rows that have not been reclaimed.
+    stale: bool,
+    /// The number of times the container has been cleared.
+    epoch: u64,
+}
+
+impl<C> Generational<C> {
+    /// The rows of the current generation.
+    pub fn current(&self) -> &C { &self.current }
+    /// The number of times the container has been cleared.
+    pub fn epoch(&self) -> u64 { self.epoch }
+    /// True if a prior generation awaits `reclaim`.
+    pub fn is_stale(&self) -> bool { self.stale }
+    /// Unwraps the rows of the current generation.
+    pub fn into_inner(self) -> C { self.current }
+}
+
+impl<C: Clear> Generational<C> {
+    /// Clears the rows of the prior generation, if they have not already been cleared.
+    ///
+    /// This is the work `clear` defers, and can be done at a time when it is less disruptive.
+    pub fn reclaim(&mut self) {
+        if self.stale {
+            self.retired.clear();
+            self.stale = false;
+        }
+    }
+}
+
+impl<C: Clear> Clear for Generational<C> {
+    /// Starts a new generation, retiring the current rows.
+    ///
+    /// This takes constant time if the prior generation has been reclaimed, and otherwise
+    /// reclaims it first.
+    fn clear(&mut self) {
+        self.reclaim();
+        std::mem::swap(&mut self.current, &mut self.retired);
+        self.stale = true;
+        self.epoch += 1;
+    }
+}
+
+impl<T, C: Push<T>> Push<T> for Generational<C> {
+    #[inline(always)] fn push(&mut self, item: T) { self.current.push(item) }
+    #[inline(always)] fn extend(&mut self, iter: impl IntoIterator<Item=T>) { self.current.extend(iter) }
+}
+impl<C: Len> Len for Generational<C> {
+    #[inline(always)] fn len(&self) -> usize { self.current.len() }
+}
+impl<C: Index> Index for Generational<C> {
+    type Ref = C::Ref;
+    #[inline(always)] fn get(&self, index: usize) -> Self::Ref { self.current.get(index) }
+}
+impl<'a, C> Index for &'a Generational<C> where &'a C: Index {
+    type Ref = <&'a C as Index>::Ref;
+    #[inline(always)] fn get(&self, index: usize) -> Self::Ref { (&self.current).get(index) }
+}
+impl<C: HeapSize> HeapSize for Generational<C> {
+    /// The active size of the current rows, and the allocations of both generations.
+    fn heap_size(&self) -> (usize, usize) {
+        let (l0, c0) = self.current.heap_size();
+        let (l1, c1) = self.retired.heap_size();
+        (l0 + if self.stale { l1 } else { 0 }, c0 + c1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::{Clear, Index, Len, Push};
+    use super::Generational;
+
+    #[test]
+    fn generations() {
+        let mut column: Generational<Vec<String>> = Default::default();
+        for round in 0 .. 4 {
+            for i in 0 .. 100 { column.push(format!("{}:{}", round, i)); }
+            assert_eq!(column.len(), 100);
+            assert_eq!((&column).get(99), &format!("{}:99", round));
+            column.clear();
+            assert_eq!(column.len(), 0);
+            assert!(column.is_stale());
+            if round % 2 == 0 { column.reclaim(); }
+        }
+        assert_eq!(column.epoch(), 4);
+        column.push("again".to_string());
+        assert_eq!(column.current(), &["again".to_string()]);
+        column.reclaim();
+        assert!(!column.is_stale());
+    }
+}
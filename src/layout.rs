@@ -0,0 +1,279 @@
+//! A traversal of the structure of borrowed containers, for code written once for all containers.
+//!
+//! `AsBytes` presents a container as a flat sequence of byte slices. `Layout` presents the same
+//! slices in the same order, but as a tree: each container visits its component columns under
+//! the names of their roles (for example `bounds` and `values` for `Vecs`, or `0` and `1` for
+//! tuples), and the leaves are the raw buffers. A `Visitor` sees the components as they are
+//! entered and left, and each buffer with its alignment, which is enough to write serializers,
+//! memory accountants, or exporters to other columnar formats over the traversal.
+//!
+//! `#[derive(Columnar)]` implements `Layout` for the containers it derives, with struct fields
+//! and enum variants visited by name.
+
+use crate::AsBytes;
+
+/// Receives the components and buffers of a container, in the order of `AsBytes::as_bytes`.
+pub trait Visitor<'a> {
+    /// Begins the component with role `role` of the column being visited.
+    fn enter(&mut self, _role: &str) { }
+    /// Ends the component with role `role`, after all of its buffers.
+    fn leave(&mut self, _role: &str) { }
+    /// Visits a buffer of the column being visited, with its required alignment.
+    fn buffer(&mut self, align: u64, bytes: &'a [u8]);
+}
+
+/// A borrowed container whose structure can be visited.
+pub trait Layout<'a>: AsBytes<'a> {
+    /// Visits the components and buffers of `self`.
+    fn visit(&self, visitor: &mut impl Visitor<'a>);
+}
+
+/// Visits `column` as the component of the current column with role `role`.
+#[inline(always)]
+pub fn component<'a>(visitor: &mut impl Visitor<'a>, role: &str, column: &impl Layout<'a>) {
+    visitor.enter(role);
+    column.visit(visitor);
+    visitor.leave(role);
+}
+
+/// Visits a single buffer as the component of the current column with role `role`.
+#[inline(always)]
+fn leaf<'a>(visitor: &mut impl Visitor<'a>, role: &str, align: u64, bytes: &'a [u8]) {
+    visitor.enter(role);
+    visitor.buffer(align, bytes);
+    visitor.leave(role);
+}
+
+/// The number of bytes in each buffer of `column`, keyed by the roles leading to it, joined by `.`.
+pub fn footprint<'a>(column: &impl Layout<'a>) -> Vec<(String, usize)> {
+    #[derive(Default)]
+    struct Footprint { path: Vec<String>, sizes: Vec<(String, usize)> }
+    impl<'a> Visitor<'a> for Footprint {
+        fn enter(&mut self, role: &str) { self.path.push(role.to_string()); }
+        fn leave(&mut self, _role: &str) { self.path.pop(); }
+        fn buffer(&mut self, _align: u64, bytes: &'a [u8]) { self.sizes.push((self.path.join("."), bytes.len())); }
+    }
+    let mut footprint = Footprint::default();
+    column.visit(&mut footprint);
+    footprint.sizes
+}
+
+impl<'a, T> Layout<'a> for &'a [T] where &'a [T]: AsBytes<'a> {
+    fn visit(&self, visitor: &mut impl Visitor<'a>) {
+        for (align, bytes) in self.as_bytes() { visitor.buffer(align, bytes); }
+    }
+}
+
+mod primitive {
+
+    use crate::primitive::{Bools, Chars, Durations, Empties, I128s, Isizes, U128s, Usizes};
+    use super::{component, leaf, Layout, Visitor};
+
+    impl<'a, CV: Layout<'a>> Layout<'a> for Usizes<CV> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) { component(visitor, "values", &self.values) }
+    }
+    impl<'a, CV: Layout<'a>> Layout<'a> for Isizes<CV> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) { component(visitor, "values", &self.values) }
+    }
+    impl<'a, HC: Layout<'a>, LC: Layout<'a>> Layout<'a> for U128s<HC, LC> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "high", &self.high);
+            component(visitor, "low", &self.low);
+        }
+    }
+    impl<'a, HC: Layout<'a>, LC: Layout<'a>> Layout<'a> for I128s<HC, LC> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "high", &self.high);
+            component(visitor, "low", &self.low);
+        }
+    }
+    impl<'a, M> Layout<'a> for Empties<&'a u64, M> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            leaf(visitor, "count", 8, bytemuck::cast_slice(std::slice::from_ref(self.count)));
+        }
+    }
+    impl<'a, VC: Layout<'a>> Layout<'a> for Bools<VC, &'a u64> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "values", &self.values);
+            leaf(visitor, "last_word", 8, bytemuck::cast_slice(std::slice::from_ref(self.last_word)));
+            leaf(visitor, "last_bits", 1, bytemuck::cast_slice(std::slice::from_ref(self.last_bits)));
+        }
+    }
+    impl<'a, LC: Layout<'a>, HC: Layout<'a>> Layout<'a> for Chars<LC, HC> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "low", &self.low);
+            component(visitor, "high", &self.high);
+        }
+    }
+    impl<'a, SC: Layout<'a>, NC: Layout<'a>> Layout<'a> for Durations<SC, NC> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "seconds", &self.seconds);
+            component(visitor, "nanoseconds", &self.nanoseconds);
+        }
+    }
+}
+
+mod containers {
+
+    use crate::{ColumnBytes, Strings, Strs, RunBounds, Vecs, Ranges, RangesInclusive, RankSelect, Results, Options, DenseOptions, NullableVecs, Constants, Interned};
+    use crate::vector::{Spans, Strided};
+    use crate::flags::{AsBits, Flags};
+    use super::{component, leaf, Layout, Visitor};
+
+    /// Strings are visited as bounds and values, as `ColumnBytes` are.
+    impl<'a, BC: Layout<'a>, VC: Layout<'a>> Layout<'a> for Strings<BC, VC> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "bounds", &self.bounds);
+            component(visitor, "values", &self.values);
+        }
+    }
+    impl<'a, BC: Layout<'a>, VC: Layout<'a>> Layout<'a> for Strs<BC, VC> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) { self.0.visit(visitor) }
+    }
+    impl<'a, BC: Layout<'a>, VC: Layout<'a>> Layout<'a> for ColumnBytes<BC, VC> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "bounds", &self.bounds);
+            component(visitor, "values", &self.values);
+        }
+    }
+    impl<'a, VC: Layout<'a>, EC: Layout<'a>> Layout<'a> for RunBounds<VC, EC> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "values", &self.values);
+            component(visitor, "ends", &self.ends);
+        }
+    }
+    impl<'a, TC: Layout<'a>, BC: Layout<'a>> Layout<'a> for Vecs<TC, BC> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "bounds", &self.bounds);
+            component(visitor, "values", &self.values);
+        }
+    }
+    impl<'a, C: Layout<'a>> Layout<'a> for Spans<C> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "starts", &self.starts);
+            component(visitor, "lengths", &self.lengths);
+        }
+    }
+    impl<'a, BC: Layout<'a>> Layout<'a> for Strided<BC, &'a u64> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "bounds", &self.bounds);
+            leaf(visitor, "stride", 8, bytemuck::cast_slice(std::slice::from_ref(self.stride)));
+            leaf(visitor, "uniform", 8, bytemuck::cast_slice(std::slice::from_ref(self.uniform)));
+        }
+    }
+    impl<'a, C: Layout<'a>> Layout<'a> for Ranges<C> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "starts", &self.starts);
+            component(visitor, "ends", &self.ends);
+        }
+    }
+    impl<'a, C: Layout<'a>> Layout<'a> for RangesInclusive<C> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "starts", &self.starts);
+            component(visitor, "ends", &self.ends);
+        }
+    }
+    impl<'a, CC: Layout<'a>, VC: Layout<'a>> Layout<'a> for RankSelect<CC, VC, &'a u64> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "counts", &self.counts);
+            component(visitor, "values", &self.values);
+        }
+    }
+    impl<'a, SC: Layout<'a>, TC: Layout<'a>, CC: Layout<'a>, VC: Layout<'a>> Layout<'a> for Results<SC, TC, CC, VC, &'a u64> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "indexes", &self.indexes);
+            component(visitor, "oks", &self.oks);
+            component(visitor, "errs", &self.errs);
+        }
+    }
+    impl<'a, TC: Layout<'a>, CC: Layout<'a>, VC: Layout<'a>> Layout<'a> for Options<TC, CC, VC, &'a u64> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "indexes", &self.indexes);
+            component(visitor, "somes", &self.somes);
+        }
+    }
+    impl<'a, TC: Layout<'a>, VC: Layout<'a>> Layout<'a> for DenseOptions<TC, VC, &'a u64> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "validity", &self.validity);
+            component(visitor, "values", &self.values);
+        }
+    }
+    impl<'a, TC: Layout<'a>, BC: Layout<'a>, VC: Layout<'a>> Layout<'a> for NullableVecs<TC, BC, VC, &'a u64> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "validity", &self.validity);
+            component(visitor, "lists", &self.lists);
+        }
+    }
+    impl<'a, TC: Layout<'a>> Layout<'a> for Constants<TC, &'a u64> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "values", &self.values);
+            leaf(visitor, "repeats", 8, bytemuck::cast_slice(std::slice::from_ref(self.repeats)));
+        }
+    }
+    impl<'a, TC: Layout<'a>> Layout<'a> for Interned<TC, &'a [u32], ()> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) {
+            component(visitor, "rows", &self.rows);
+            component(visitor, "ids", &self.ids);
+        }
+    }
+    impl<'a, T: AsBits, BC: Layout<'a>> Layout<'a> for Flags<T, BC> {
+        fn visit(&self, visitor: &mut impl Visitor<'a>) { component(visitor, "bits", &self.bits) }
+    }
+}
+
+macro_rules! implement_tuple {
+    ($($name:ident $index:tt)+) => (
+        /// Tuples visit their columns by position.
+        impl<'a, $($name: Layout<'a>),*> Layout<'a> for ($($name,)*) {
+            fn visit(&self, visitor: &mut impl Visitor<'a>) {
+                $( component(visitor, stringify!($index), &self.$index); )*
+            }
+        }
+    )
+}
+
+implement_tuple!(A 0);
+implement_tuple!(A 0 B 1);
+implement_tuple!(A 0 B 1 C 2);
+implement_tuple!(A 0 B 1 C 2 D 3);
+implement_tuple!(A 0 B 1 C 2 D 3 E 4);
+implement_tuple!(A 0 B 1 C 2 D 3 E 4 F 5);
+implement_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6);
+implement_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7);
+implement_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8);
+implement_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8 J 9);
+
+#[cfg(test)]
+mod test {
+
+    use crate::{AsBytes, Columnar, Container};
+    use super::{Layout, Visitor};
+
+    type Row = (u64, Option<String>, Vec<(u8, bool)>);
+
+    #[test]
+    fn traversal() {
+        let rows: Vec<Row> = (0 .. 10u64).map(|i| (i, if i % 3 == 0 { None } else { Some(format!("{}", i)) }, vec![(i as u8, i % 2 == 0); i as usize])).collect();
+        let column = Columnar::as_columns(rows.iter());
+        let borrowed = Container::<Row>::borrow(&column);
+
+        // The buffers are visited in the order `as_bytes` presents them.
+        struct Buffers<'a>(Vec<(u64, &'a [u8])>, usize);
+        impl<'a> Visitor<'a> for Buffers<'a> {
+            fn enter(&mut self, _role: &str) { self.1 += 1; }
+            fn leave(&mut self, _role: &str) { self.1 -= 1; }
+            fn buffer(&mut self, align: u64, bytes: &'a [u8]) { self.0.push((align, bytes)); }
+        }
+        let mut buffers = Buffers(Vec::new(), 0);
+        borrowed.visit(&mut buffers);
+        assert_eq!(buffers.1, 0);
+        assert_eq!(buffers.0, borrowed.as_bytes().collect::<Vec<_>>());
+
+        let footprint = super::footprint(&borrowed);
+        let roles = footprint.iter().map(|(role, _)| role.as_str()).collect::<Vec<_>>();
+        assert_eq!(roles[0], "0");
+        assert!(roles.contains(&"1.somes.values"));
+        assert!(roles.contains(&"2.values.1.last_bits"));
+        assert_eq!(footprint.iter().map(|(_, size)| size).sum::<usize>(), borrowed.as_bytes().map(|(_, bytes)| bytes.len()).sum::<usize>());
+    }
+}
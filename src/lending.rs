@@ -0,0 +1,104 @@
+//! Iteration over items that borrow from the iterator itself.
+//!
+//! The items of `IndexMut` borrow the container mutably, and so cannot outlive the call
+//! that produced them. A plain `Iterator` cannot express this, as its items must all be
+//! able to coexist. A lending iterator hands out one item at a time, each borrowing the
+//! iterator until it is dropped.
+
+use crate::{IndexMut, Len};
+
+/// The type of item a lending iterator produces when borrowed for `'a`.
+///
+/// This is a separate trait, rather than an associated type of `LendingIterator` with a
+/// `where Self: 'a` bound, so that bounds like `for<'a> FnMut(Item<'a, I>)` only range over
+/// lifetimes for which `&'a I` is valid. The second parameter exists only to imply this, and
+/// should be left as its default.
+pub trait Lend<'a, Valid = &'a Self> {
+    /// The type of item.
+    type Item;
+}
+
+/// The type of item `I` produces when borrowed for `'a`.
+pub type Item<'a, I> = <I as Lend<'a>>::Item;
+
+/// An iterator whose items may borrow from the iterator.
+pub trait LendingIterator : for<'a> Lend<'a> {
+    /// Advances the iterator and returns the next item, should one exist.
+    fn next(&mut self) -> Option<Item<'_, Self>>;
+
+    /// Calls `action` on each remaining item.
+    fn for_each<F>(mut self, mut action: F) where Self: Sized, F: FnMut(Item<'_, Self>) {
+        while let Some(item) = self.next() {
+            action(item);
+        }
+    }
+    /// The number of remaining items, consuming them.
+    fn count(mut self) -> usize where Self: Sized {
+        let mut count = 0;
+        while self.next().is_some() { count += 1; }
+        count
+    }
+    /// A plain iterator of the results of `map` applied to each item.
+    ///
+    /// The results must not borrow from the iterator, which allows them to coexist.
+    fn map<T, F>(self, map: F) -> Map<Self, F> where Self: Sized, F: FnMut(Item<'_, Self>) -> T {
+        Map { iter: self, map }
+    }
+}
+
+/// A lending iterator over the mutable references of a container.
+pub struct IterMut<'c, C: ?Sized> {
+    container: &'c mut C,
+    index: usize,
+}
+
+impl<'c, C: IndexMut + Len + ?Sized> IterMut<'c, C> {
+    /// Iterates over the mutable references of `container`, in order.
+    pub fn new(container: &'c mut C) -> Self { Self { container, index: 0 } }
+}
+
+impl<'a, C: IndexMut + Len + ?Sized> Lend<'a> for IterMut<'_, C> {
+    type Item = C::IndexMut<'a>;
+}
+impl<C: IndexMut + Len + ?Sized> LendingIterator for IterMut<'_, C> {
+    #[inline(always)] fn next(&mut self) -> Option<Item<'_, Self>> {
+        if self.index < self.container.len() {
+            self.index += 1;
+            Some(self.container.get_mut(self.index - 1))
+        } else {
+            None
+        }
+    }
+}
+
+/// The iterator returned by `LendingIterator::map`.
+pub struct Map<I, F> {
+    iter: I,
+    map: F,
+}
+
+impl<T, I: LendingIterator, F: FnMut(Item<'_, I>) -> T> Iterator for Map<I, F> {
+    type Item = T;
+    #[inline(always)] fn next(&mut self) -> Option<T> {
+        let item = self.iter.next()?;
+        Some((self.map)(item))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::{IterMut, LendingIterator};
+
+    #[test]
+    fn iter_mut() {
+        let mut column: (Vec<u64>, Vec<i32>) = ((0 .. 10).collect(), (0 .. 10).collect());
+        IterMut::new(&mut column).for_each(|(a, b)| { *a += 1; *b = -*b; });
+        assert_eq!(column.0, (1 .. 11).collect::<Vec<_>>());
+        assert_eq!(column.1, (0 .. 10).map(|x| -x).collect::<Vec<_>>());
+
+        let sums: Vec<i64> = IterMut::new(&mut column).map(|(a, b)| *a as i64 + *b as i64).collect();
+        assert_eq!(sums, (0 .. 10).map(|_| 1).collect::<Vec<_>>());
+        assert_eq!(IterMut::new(&mut column).count(), 10);
+    }
+}
@@ -9,8 +9,34 @@
 // Re-export derive crate.
 extern crate columnar_derive;
 pub use columnar_derive::Columnar;
+// Re-exported for derived code, which cannot name the renamed dependency.
+#[doc(hidden)]
+pub extern crate bytemuck_crate as bytemuck;
 
 pub mod adts;
+pub mod stats;
+pub mod sort;
+pub mod diff;
+pub mod cursor;
+pub mod flags;
+pub mod lending;
+pub mod any;
+pub mod batch;
+pub mod codec;
+pub mod memcmp;
+pub mod store;
+pub mod tagged;
+pub mod generational;
+pub mod window;
+pub mod path;
+pub mod layout;
+pub mod open;
+pub mod budget;
+pub mod sharded;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+#[cfg(any(test, feature = "bytemuck"))]
+pub mod pod;
 
 /// A type that can be represented in columnar form.
 ///
@@ -28,13 +54,15 @@ pub trait Columnar : 'static {
         *self = Self::into_owned(other);
     }
     /// Produce an instance of `Self` from `Self::Ref<'a>`.
-    fn into_owned<'a>(other: Self::Ref<'a>) -> Self;
+    ///
+    /// Unsized types like `str` and `[T]` can be pushed by reference, but not produced.
+    fn into_owned<'a>(other: Self::Ref<'a>) -> Self where Self: Sized;
 
     /// The type that stores the columnar representation.
     ///
-    /// The container must support pushing both `&Self` and `Self::Ref<'_>`.
+    /// The container must support copying in both `&Self` and `Self::Ref<'_>`.
     /// In our running example this might be `(Vec<A>, Vecs<Vec<B>>)`.
-    type Container: Len + Clear + Default + for<'a> Push<&'a Self> + for<'a> Push<Self::Ref<'a>> + Container<Self>;
+    type Container: Len + Clear + Default + CopyRef<Self> + for<'a> Push<Self::Ref<'a>> + Container<Self>;
 
     /// Converts a sequence of the references to the type into columnar form.
     fn as_columns<'a, I>(selves: I) -> Self::Container where I: IntoIterator<Item =&'a Self>, Self: 'a {
@@ -60,16 +88,115 @@ pub trait Columnar : 'static {
 /// A container that can hold `C`, and provide its preferred references.
 ///
 /// As an example, `(Vec<A>, Vecs<Vec<B>>)`.
+///
+/// Containers implement `PartialEq` and `Eq` when their contents do. Each sequence of rows
+/// has exactly one layout, however it was assembled, so containers compare equal exactly
+/// when they hold equal sequences of rows.
 pub trait Container<C: Columnar + ?Sized> {
     /// The type of a borrowed container.
     ///
     /// Corresponding to our example, `(&'a [A], Vecs<&'a [B], &'a [u64]>)`.
-    type Borrowed<'a>: Copy + Len + AsBytes<'a> + FromBytes<'a> + Index<Ref = C::Ref<'a>> where Self: 'a;
+    type Borrowed<'a>: Copy + Len + AsBytes<'a> + FromBytes<'a> + crate::layout::Layout<'a> + Index<Ref = C::Ref<'a>> where Self: 'a;
     /// Converts a reference to the type to a borrowed variant.
     fn borrow<'a>(&'a self) -> Self::Borrowed<'a>;
+
+    /// Gathers the rows at `indices`, in the order given, into a new container.
+    ///
+    /// Indices may repeat, and need not cover all rows. This is the usual way to apply
+    /// the row identifiers produced by sorting, filtering, or joining.
+    fn take(&self, indices: &[usize]) -> Self where Self: Default + for<'a> Push<C::Ref<'a>> {
+        let borrowed = self.borrow();
+        let mut result = Self::default();
+        for index in indices {
+            result.push(borrowed.get(*index));
+        }
+        result
+    }
+
+    /// Builds an owned copy of the row at `index`, leaving the container unchanged.
+    fn materialize(&self, index: usize) -> C where C: Sized {
+        C::into_owned(self.borrow().get(index))
+    }
+
+    /// Removes the rows in `range`, returning them as owned values.
+    ///
+    /// The remaining rows are copied into freshly allocated storage, in order, which
+    /// releases the space held by the removed rows.
+    fn drain(&mut self, range: std::ops::Range<usize>) -> Vec<C> where Self: Len + Default + ExtendFromRange, C: Sized {
+        assert!(range.start <= range.end && range.end <= self.len(), "range {:?} out of bounds for length {}", range, self.len());
+        let borrowed = self.borrow();
+        let drained = range.clone().map(|index| C::into_owned(borrowed.get(index))).collect();
+        let mut remaining = Self::default();
+        remaining.extend_from_range(self, 0 .. range.start);
+        remaining.extend_from_range(self, range.end .. self.len());
+        *self = remaining;
+        drained
+    }
+
+    /// Inserts `item` as the row at `index`, shifting later rows up by one.
+    ///
+    /// This copies all rows into freshly allocated storage, and takes time linear in the
+    /// size of the container. It suits occasional corrections, rather than building containers.
+    fn insert(&mut self, index: usize, item: &C) where Self: Len + Default + ExtendFromRange + for<'a> Push<&'a C> {
+        assert!(index <= self.len(), "insertion index {} out of bounds for length {}", index, self.len());
+        let mut result = Self::default();
+        result.extend_from_range(self, 0 .. index);
+        result.push(item);
+        result.extend_from_range(self, index .. self.len());
+        *self = result;
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    #[test]
+    fn take() {
+
+        use crate::{Columnar, Container, Len};
+
+        type Row = (u64, String);
+        let column = Columnar::into_columns((0 .. 5u64).map(|i| (i, format!("{}", i))));
+        let taken = Container::<Row>::take(&column, &[4, 1, 1, 0, 4]);
+        assert_eq!(taken.len(), 5);
+        assert_eq!((0 .. taken.len()).map(|i| Container::<Row>::materialize(&taken, i).0).collect::<Vec<_>>(), [4, 1, 1, 0, 4]);
+        assert_eq!(Container::<Row>::materialize(&taken, 2), (1, "1".to_string()));
+        assert!(Container::<Row>::take(&column, &[]).is_empty());
+    }
+
+    #[test]
+    fn drain() {
+
+        use crate::{Columnar, Container, Len};
+
+        type Row = (usize, Vec<String>);
+        let mut column = Columnar::into_columns((0 .. 10).map(|i| (i, vec![format!("{}", i); i])));
+        let drained = Container::<Row>::drain(&mut column, 0 .. 3);
+        assert_eq!(drained, (0 .. 3).map(|i| (i, vec![format!("{}", i); i])).collect::<Vec<_>>());
+        assert_eq!(column.len(), 7);
+        assert_eq!(Container::<Row>::materialize(&column, 0), (3, vec!["3".to_string(); 3]));
+        assert_eq!(Container::<Row>::drain(&mut column, 2 .. 4).len(), 2);
+        assert_eq!((0 .. column.len()).map(|i| Container::<Row>::materialize(&column, i).0).collect::<Vec<_>>(), [3, 4, 7, 8, 9]);
+    }
+
+    #[test]
+    fn insert() {
+
+        use crate::{Columnar, Container, Index};
+
+        let mut column = Columnar::into_columns(["a", "c", "d"].map(String::from));
+        Container::<String>::insert(&mut column, 1, &"b".to_string());
+        Container::<String>::insert(&mut column, 4, &"e".to_string());
+        assert_eq!(Container::<String>::borrow(&column).into_iter().collect::<Vec<_>>(), ["a", "b", "c", "d", "e"]);
+
+        let mut strs = <str as Columnar>::Container::default();
+        Container::<str>::insert(&mut strs, 0, "z");
+        Container::<str>::insert(&mut strs, 0, "y");
+        assert_eq!(Container::<str>::borrow(&strs).get(1), "z");
+    }
 }
 
-pub use common::{Clear, Len, Push, IndexMut, Index, IndexAs, HeapSize, Slice, AsBytes, FromBytes};
+pub use common::{Clear, Len, Push, TryPush, CopyRef, ColumnRead, IndexMut, Index, IndexAs, HeapSize, Slice, Indirect, IndexNode, Nodes, IntoOwned, AsBytes, FromBytes, Permute, ExtendFromRange, AsSlice, AsMutSlice, InvalidParts};
 /// Common traits and types that are re-used throughout the module.
 pub mod common {
 
@@ -99,6 +226,10 @@ pub mod common {
     }
 
     /// A type that can accept items of type `T`.
+    ///
+    /// Pushing an owned `T` moves it into the container, and places no further requirements
+    /// on `T`. Containers whose items are not `Clone`, for example because they hold
+    /// `Box<dyn Trait>` handles, can still be built by value this way.
     pub trait Push<T> {
         /// Pushes an item onto `self`.
         fn push(&mut self, item: T);
@@ -129,6 +260,35 @@ pub mod common {
         #[inline(always)] fn push(&mut self, item: &'a [T]) { self.clone_from_slice(item) }
     }
 
+    /// A type that can accept copies of borrowed items of type `T`.
+    ///
+    /// This is `Push<&T>` for all lifetimes, and is implemented for any such type.
+    /// It is the requirement `Columnar` places on its containers, on top of pushing references,
+    /// and the one that move-only types may be unable to meet; they can use `Push<T>` instead.
+    pub trait CopyRef<T: ?Sized> : for<'a> Push<&'a T> { }
+    impl<T: ?Sized, C: for<'a> Push<&'a T>> CopyRef<T> for C { }
+
+    /// A type that can accept items of type `T` after validating them.
+    ///
+    /// This is the fallible counterpart to `Push`, for containers whose invariants an item
+    /// may violate, like strings that must be UTF-8. An item that fails validation is
+    /// reported as an error, and leaves `self` unchanged.
+    pub trait TryPush<T> {
+        /// The reason an item was not accepted.
+        type Error;
+        /// Pushes an item onto `self`, unless it fails validation.
+        fn try_push(&mut self, item: T) -> Result<(), Self::Error>;
+        /// Pushes elements of an iterator onto `self`, stopping at the first to fail validation.
+        ///
+        /// Elements preceding the failing element remain pushed.
+        fn try_extend(&mut self, iter: impl IntoIterator<Item=T>) -> Result<(), Self::Error> {
+            for item in iter {
+                self.try_push(item)?;
+            }
+            Ok(())
+        }
+    }
+
 
     pub use index::{Index, IndexMut, IndexAs};
     /// Traits for accessing elements by `usize` indexes.
@@ -139,7 +299,7 @@ pub mod common {
     pub mod index {
 
         use crate::Len;
-        use crate::common::IterOwn;
+        use crate::common::{IterOwn, Enumerated};
 
         /// A type that can be mutably accessed by `usize`.
         pub trait IndexMut {
@@ -187,6 +347,11 @@ pub mod common {
                 if self.is_empty() { None }
                 else { Some(self.get(self.len()-1)) }
             }
+            /// The element at `index`, or `None` if `index` is out of bounds.
+            #[inline(always)] fn try_get(&self, index: usize) -> Option<Self::Ref> where Self: Len {
+                if index < self.len() { Some(self.get(index)) }
+                else { None }
+            }
             fn iter(&self) -> IterOwn<&Self> {
                 IterOwn {
                     index: 0,
@@ -199,6 +364,17 @@ pub mod common {
                     slice: self,
                 }
             }
+            /// Iterates over each row together with its index.
+            fn iter_enumerated(&self) -> Enumerated<'_, Self> where Self: Len {
+                Enumerated::new(0 .. self.len(), self)
+            }
+            /// Iterates over the rows in `range`, each together with its index.
+            ///
+            /// Panics if `range` extends past the end of the container.
+            fn iter_range(&self, range: std::ops::Range<usize>) -> Enumerated<'_, Self> where Self: Len {
+                assert!(range.end <= self.len(), "range end {} out of bounds for length {}", range.end, self.len());
+                Enumerated::new(range, self)
+            }
         }
 
         // These implementations aim to reveal a longer lifetime, or to copy results to avoid a lifetime.
@@ -263,11 +439,146 @@ pub mod common {
         #[inline(always)] fn clear(&mut self) { *self = &[]; }
     }
 
+    /// A type whose rows can be reordered in place.
+    ///
+    /// Implementations reuse their existing allocations where they can, which is
+    /// fully the case for `Vec<T>` and partially the case for containers whose rows
+    /// have variable sizes, which rebuild their values in a single pass.
+    pub trait Permute {
+        /// Reorders rows so that row `i` becomes the row previously at `perm[i]`.
+        ///
+        /// The argument `perm` must be a permutation of the row indices.
+        fn permute(&mut self, perm: &[usize]);
+    }
+    impl<T> Permute for Vec<T> {
+        fn permute(&mut self, perm: &[usize]) {
+            assert_eq!(perm.len(), self.len());
+            // Rotate each cycle of the permutation into place, swapping as we go.
+            let mut done = vec![false; perm.len()];
+            for start in 0 .. perm.len() {
+                let mut index = start;
+                while !done[index] {
+                    done[index] = true;
+                    let source = perm[index];
+                    if source == start { break; }
+                    self.swap(index, source);
+                    index = source;
+                }
+            }
+        }
+    }
+
+    /// A type that can append a range of rows from another instance of itself.
+    ///
+    /// Implementations copy their underlying buffers in bulk where the layout allows,
+    /// rebasing any offsets, rather than reading out and pushing each row.
+    pub trait ExtendFromRange {
+        /// Appends rows `range` of `other` to `self`.
+        fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>);
+        /// Copies rows `range` of `self` into a new container, with offsets rebased to start at zero.
+        ///
+        /// This is an owned alternative to slicing a borrowed container, for example to split an
+        /// oversized container into containers of a more manageable size.
+        fn extract_range(&self, range: std::ops::Range<usize>) -> Self where Self: Default {
+            let mut result = Self::default();
+            result.extend_from_range(self, range);
+            result
+        }
+    }
+    impl<T: Clone> ExtendFromRange for Vec<T> {
+        #[inline(always)] fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+            self.extend_from_slice(&other[range]);
+        }
+    }
+
+    /// A reference that can be converted into an owned value.
+    ///
+    /// This is implemented for the references containers present, so that a row can be
+    /// handed off as an owned value: for example, `&str` as `String` and `Slice<_>` as `Vec<_>`.
+    pub trait IntoOwned {
+        /// The type of the owned value.
+        type Owned;
+        /// Converts `self` into an owned value.
+        fn into_owned(self) -> Self::Owned;
+    }
+
+    /// A reason that buffers do not form a valid container, from `try_from_parts`.
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum InvalidParts {
+        /// The bound at `index` is less than the bound before it.
+        DecreasingBounds { index: usize },
+        /// The last bound is `bound`, but there are `values` values.
+        LengthMismatch { bound: u64, values: usize },
+        /// The bytes of the string at `index` are not UTF-8.
+        Utf8 { index: usize },
+        /// The value at `index` is not a Unicode scalar value.
+        Scalar { index: usize },
+    }
+
+    impl std::fmt::Display for InvalidParts {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            match self {
+                InvalidParts::DecreasingBounds { index } => write!(f, "bound {} is less than the bound before it", index),
+                InvalidParts::LengthMismatch { bound, values } => write!(f, "last bound {} does not match {} values", bound, values),
+                InvalidParts::Utf8 { index } => write!(f, "string {} is not UTF-8", index),
+                InvalidParts::Scalar { index } => write!(f, "value {} is not a Unicode scalar value", index),
+            }
+        }
+    }
+    impl std::error::Error for InvalidParts { }
+
+    /// Checks that `bounds` are non-decreasing cumulative offsets that end at `values`.
+    pub(crate) fn validate_bounds<BC: Len + IndexAs<u64>>(bounds: &BC, values: usize) -> Result<(), InvalidParts> {
+        let mut prior = 0;
+        for index in 0 .. bounds.len() {
+            let bound = bounds.index_as(index);
+            if bound < prior { return Err(InvalidParts::DecreasingBounds { index }); }
+            prior = bound;
+        }
+        if prior != values as u64 { return Err(InvalidParts::LengthMismatch { bound: prior, values }); }
+        Ok(())
+    }
+
+    /// A container whose rows are stored contiguously as a slice of `T`.
+    ///
+    /// This exposes the underlying buffer of primitive columns, for routines that work on
+    /// slices, like vectorized kernels or compression libraries, without naming container fields.
+    pub trait AsSlice<T> {
+        /// The rows, as a slice.
+        fn as_slice(&self) -> &[T];
+    }
+    /// A container whose rows are stored contiguously as a mutable slice of `T`.
+    pub trait AsMutSlice<T> : AsSlice<T> {
+        /// The rows, as a mutable slice.
+        fn as_mut_slice(&mut self) -> &mut [T];
+    }
+    impl<T> AsSlice<T> for Vec<T> {
+        #[inline(always)] fn as_slice(&self) -> &[T] { &self[..] }
+    }
+    impl<T> AsMutSlice<T> for Vec<T> {
+        #[inline(always)] fn as_mut_slice(&mut self) -> &mut [T] { &mut self[..] }
+    }
+    impl<T> AsSlice<T> for &[T] {
+        #[inline(always)] fn as_slice(&self) -> &[T] { self }
+    }
+    impl<T> AsSlice<T> for &mut [T] {
+        #[inline(always)] fn as_slice(&self) -> &[T] { self }
+    }
+    impl<T> AsMutSlice<T> for &mut [T] {
+        #[inline(always)] fn as_mut_slice(&mut self) -> &mut [T] { self }
+    }
+
     pub trait HeapSize {
         /// Active (len) and allocated (cap) heap sizes in bytes.
         /// This should not include the size of `self` itself.
         fn heap_size(&self) -> (usize, usize) { (0, 0) }
     }
+    impl<T: HeapSize + ?Sized> HeapSize for &T {
+        #[inline(always)] fn heap_size(&self) -> (usize, usize) { (**self).heap_size() }
+    }
+    impl<T: HeapSize + ?Sized> HeapSize for &mut T {
+        #[inline(always)] fn heap_size(&self) -> (usize, usize) { (**self).heap_size() }
+    }
     impl HeapSize for serde_json::Number { }
     impl HeapSize for String {
         fn heap_size(&self) -> (usize, usize) {
@@ -299,10 +610,18 @@ pub mod common {
         }
     }
 
+    /// The type-erased operations common to all containers.
+    ///
+    /// Unlike `Columnar` and `Index`, this trait is object safe, so that columns of different
+    /// types can be held together as `Vec<Box<dyn ColumnRead>>` and measured or recycled uniformly.
+    /// Access to rows requires the concrete type, through `Index::try_get` or `Index::get`.
+    pub trait ColumnRead : Len + Clear + HeapSize { }
+    impl<C: Len + Clear + HeapSize> ColumnRead for C { }
+
     /// A struct representing a slice of a range of values.
     ///
     /// The lower and upper bounds should be meaningfully set on construction.
-    #[derive(Copy, Clone, Debug)]
+    #[derive(Copy, Clone)]
     pub struct Slice<S> {
         lower: usize,
         upper: usize,
@@ -333,6 +652,25 @@ pub mod common {
         pub fn len(&self) -> usize { self.upper - self.lower }
     }
 
+    // Slices print as lists of their elements, as would the `Vec<T>` they reference.
+    impl<S: Index> std::fmt::Debug for Slice<S> where S::Ref: std::fmt::Debug {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_list().entries((0 .. self.len()).map(|index| self.get(index))).finish()
+        }
+    }
+
+    // Slices serialize as sequences of their elements, as would the `Vec<T>` they reference.
+    impl<S: Index> serde::Serialize for Slice<S> where S::Ref: serde::Serialize {
+        fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+            use serde::ser::SerializeSeq;
+            let mut seq = serializer.serialize_seq(Some(self.len()))?;
+            for index in 0 .. self.len() {
+                seq.serialize_element(&self.get(index))?;
+            }
+            seq.end()
+        }
+    }
+
     impl<S: Index> PartialEq for Slice<S> where S::Ref: PartialEq {
         fn eq(&self, other: &Self) -> bool {
             if self.len() != other.len() { return false; }
@@ -395,6 +733,15 @@ pub mod common {
         }
     }
 
+    impl<S: Index> std::hash::Hash for Slice<S> where S::Ref: std::hash::Hash {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            self.len().hash(state);
+            for i in 0 .. self.len() {
+                self.get(i).hash(state);
+            }
+        }
+    }
+
     impl<S> Len for Slice<S> {
         #[inline(always)] fn len(&self) -> usize { self.len() }
     }
@@ -417,6 +764,13 @@ pub mod common {
         }
     }
 
+    impl<S: Index> IntoOwned for Slice<S> where S::Ref: IntoOwned {
+        type Owned = Vec<<S::Ref as IntoOwned>::Owned>;
+        fn into_owned(self) -> Self::Owned {
+            self.into_iter().map(IntoOwned::into_owned).collect()
+        }
+    }
+
     impl<S: IndexMut> IndexMut for Slice<S> {
         type IndexMut<'a> = S::IndexMut<'a> where S: 'a;
         #[inline(always)] fn get_mut(&mut self, index: usize) -> Self::IndexMut<'_> {
@@ -447,6 +801,122 @@ pub mod common {
                 None
             }
         }
+        #[inline(always)] fn size_hint(&self) -> (usize, Option<usize>) {
+            let remaining = self.slice.len().saturating_sub(self.index);
+            (remaining, Some(remaining))
+        }
+    }
+    impl<S: Index + Len> ExactSizeIterator for IterOwn<S> { }
+    impl<S: Index + Len> std::iter::FusedIterator for IterOwn<S> { }
+
+    /// An iterator over the rows in a range of indices, each together with its index.
+    pub struct Enumerated<'a, S: ?Sized> {
+        range: std::ops::Range<usize>,
+        slice: &'a S,
+    }
+
+    impl<'a, S: ?Sized> Enumerated<'a, S> {
+        /// Iterates over the rows of `slice` in `range`, which should be in bounds.
+        pub fn new(range: std::ops::Range<usize>, slice: &'a S) -> Self {
+            Self { range, slice }
+        }
+    }
+
+    impl<S: Index + ?Sized> Iterator for Enumerated<'_, S> {
+        type Item = (usize, S::Ref);
+        #[inline(always)] fn next(&mut self) -> Option<Self::Item> {
+            self.range.next().map(|index| (index, self.slice.get(index)))
+        }
+        #[inline(always)] fn size_hint(&self) -> (usize, Option<usize>) {
+            self.range.size_hint()
+        }
+    }
+    impl<S: Index + ?Sized> DoubleEndedIterator for Enumerated<'_, S> {
+        #[inline(always)] fn next_back(&mut self) -> Option<Self::Item> {
+            self.range.next_back().map(|index| (index, self.slice.get(index)))
+        }
+    }
+    impl<S: Index + ?Sized> ExactSizeIterator for Enumerated<'_, S> { }
+    impl<S: Index + ?Sized> std::iter::FusedIterator for Enumerated<'_, S> { }
+
+    /// A sequence of rows of a container, identified by their indices.
+    ///
+    /// Derived containers for recursive types, like `struct Tree { value: u64, children: Vec<Tree> }`,
+    /// record the recursive field as a list of indices of nodes in the container itself.
+    /// References to such a field present the nodes through this type, with `Nodes` as the container.
+    #[derive(Copy, Clone)]
+    pub struct Indirect<I, C> {
+        /// The indices of the rows, in order.
+        pub rows: I,
+        /// The container holding the rows.
+        pub container: C,
+    }
+
+    impl<I, C> Indirect<I, C> {
+        pub fn new(rows: I, container: C) -> Self {
+            Self { rows, container }
+        }
+    }
+
+    /// A container that records nodes, some of which are its rows, as derived for recursive types.
+    ///
+    /// Each row of the container is a node, and the other nodes are reachable from rows only
+    /// through the recursive fields of nodes, as the children of a tree are from its root.
+    pub trait IndexNode {
+        /// The type of a reference to a node, which is also that of a row.
+        type Ref;
+        /// A reference to the node at `node`, which need not be a row.
+        fn get_node(&self, node: usize) -> Self::Ref;
+    }
+
+    /// The nodes of a container, indexed by node rather than by row.
+    #[derive(Copy, Clone, Debug)]
+    pub struct Nodes<C>(pub C);
+
+    impl<C: IndexNode> Index for Nodes<C> {
+        type Ref = C::Ref;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref { self.0.get_node(index) }
+    }
+
+    impl<I: Len, C> Len for Indirect<I, C> {
+        #[inline(always)] fn len(&self) -> usize { self.rows.len() }
+    }
+
+    impl<I: IndexAs<u64>, C: Index> Index for Indirect<I, C> {
+        type Ref = C::Ref;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+            self.container.get(self.rows.index_as(index) as usize)
+        }
+    }
+    impl<'a, I: IndexAs<u64>, C> Index for &'a Indirect<I, C> where &'a C: Index {
+        type Ref = <&'a C as Index>::Ref;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+            (&self.container).get(self.rows.index_as(index) as usize)
+        }
+    }
+
+    impl<I: IndexAs<u64> + Len, C: Index> IntoOwned for Indirect<I, C> where C::Ref: IntoOwned {
+        type Owned = Vec<<C::Ref as IntoOwned>::Owned>;
+        fn into_owned(self) -> Self::Owned {
+            self.into_iter().map(IntoOwned::into_owned).collect()
+        }
+    }
+
+    impl<I: IndexAs<u64> + Len, C: Index> std::fmt::Debug for Indirect<I, C> where C::Ref: std::fmt::Debug {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_list().entries((0 .. self.len()).map(|index| self.get(index))).finish()
+        }
+    }
+
+    impl<I: IndexAs<u64> + Len, C: Index> serde::Serialize for Indirect<I, C> where C::Ref: serde::Serialize {
+        fn serialize<Ser: serde::Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+            use serde::ser::SerializeSeq;
+            let mut seq = serializer.serialize_seq(Some(self.len()))?;
+            for index in 0 .. self.len() {
+                seq.serialize_element(&self.get(index))?;
+            }
+            seq.end()
+        }
     }
 
     /// A type that can be viewed as byte slices with lifetime `'a`.
@@ -473,6 +943,114 @@ pub mod common {
         fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self;
     }
 
+    #[cfg(test)]
+    mod test {
+
+        #[test]
+        fn column_read() {
+
+            use crate::{Columnar, ColumnRead, Index};
+
+            let numbers = Columnar::into_columns(0 .. 100u64);
+            let names = Columnar::into_columns((0 .. 10).map(|i| format!("name{}", i)));
+            assert_eq!(numbers.try_get(99), Some(99));
+            assert_eq!(numbers.try_get(100), None);
+
+            let mut columns: Vec<Box<dyn ColumnRead>> = vec![Box::new(numbers), Box::new(names)];
+            assert_eq!(columns.iter().map(|c| c.len()).sum::<usize>(), 110);
+            assert!(columns.iter().all(|c| c.heap_size().0 > 0));
+            for column in columns.iter_mut() { column.clear(); }
+            assert!(columns.iter().all(|c| c.is_empty()));
+        }
+
+        #[test]
+        fn as_slice() {
+
+            use crate::{AsMutSlice, AsSlice, Columnar, Container};
+
+            fn sum<C: AsSlice<u64>>(column: &C) -> u64 { column.as_slice().iter().sum() }
+
+            let mut column = Columnar::into_columns(0 .. 100u64);
+            for value in AsMutSlice::as_mut_slice(&mut column) { *value *= 2; }
+            assert_eq!(sum(&column), 9900);
+            assert_eq!(sum(&Container::<u64>::borrow(&column)), 9900);
+            let sizes = Columnar::into_columns(0 .. 100usize);
+            assert_eq!(sum(&sizes), 4950);
+        }
+
+        #[test]
+        fn from_parts() {
+
+            use crate::{Columnar, ColumnBytes, InvalidParts, Strings, Vecs};
+
+            let strings = Columnar::into_columns(["ab", "", "cde"].map(String::from));
+            let (bounds, values) = strings.clone().into_parts();
+            assert_eq!(Strings::try_from_parts(bounds, values), Ok(strings));
+            assert_eq!(Strings::try_from_parts(vec![2, 1], b"ab".to_vec()), Err(InvalidParts::DecreasingBounds { index: 1 }));
+            assert_eq!(Strings::try_from_parts(vec![1, 2], vec![b'a', 0xff]), Err(InvalidParts::Utf8 { index: 1 }));
+            assert!(ColumnBytes::try_from_parts(vec![1, 2], vec![b'a', 0xff]).is_ok());
+            assert_eq!(Vecs::try_from_parts(vec![1, 3], vec![0u64; 4]), Err(InvalidParts::LengthMismatch { bound: 3, values: 4 }));
+        }
+
+        #[test]
+        fn iter_enumerated() {
+
+            use crate::{Columnar, Container, Index};
+
+            let column = Columnar::into_columns((0 .. 10).map(|i| format!("{}", i * i)));
+            let borrowed = Container::<String>::borrow(&column);
+            let selected: Vec<usize> = borrowed.iter_enumerated().filter(|(_, s)| s.ends_with('1')).map(|(i, _)| i).collect();
+            assert_eq!(selected, [1, 9]);
+            assert_eq!(borrowed.iter_range(3 .. 5).collect::<Vec<_>>(), [(3, "9"), (4, "16")]);
+            assert_eq!(borrowed.iter_range(3 .. 5).next_back(), Some((4, "16")));
+            assert_eq!(borrowed.iter_range(10 .. 10).len(), 0);
+        }
+
+        #[test]
+        fn shared_references() {
+
+            use std::time::Duration;
+            use crate::{Columnar, Container, HeapSize, Index, Indirect, Len};
+
+            // Written once, for owned containers by reference and for borrowed views alike.
+            fn last<C: Len + Index>(column: C) -> Option<C::Ref> {
+                column.try_get(column.len().wrapping_sub(1))
+            }
+            fn size<C: HeapSize>(column: C) -> (usize, usize) { column.heap_size() }
+
+            let durations = Columnar::into_columns((0 .. 10u64).map(Duration::from_secs));
+            assert_eq!(last(&durations), Some(Duration::from_secs(9)));
+            assert_eq!(last(Container::<Duration>::borrow(&durations)), Some(Duration::from_secs(9)));
+            assert_eq!(size(&durations), durations.heap_size());
+
+            let mut repeats: crate::Repeats<Vec<u64>> = Default::default();
+            for item in [3u64, 3, 5, 5, 5] { crate::Push::push(&mut repeats, &item); }
+            assert_eq!(last(&repeats), Some(&5));
+
+            let indirect = Indirect::new(vec![2u64, 0], Container::<Duration>::borrow(&durations));
+            assert_eq!(last(&indirect), Some(Duration::from_secs(0)));
+        }
+
+        #[test]
+        fn serialize_rows() {
+
+            use crate::{Columnar, Container, Index};
+
+            type Row = (u64, String, Vec<Option<i32>>, Result<bool, Vec<String>>, std::ops::Range<u8>);
+            let rows: Vec<Row> = (0 .. 10).map(|i| {
+                let list = (0 .. i).map(|j| if j % 2 == 0 { Some(j as i32) } else { None }).collect();
+                let result = if i % 3 == 0 { Ok(i % 2 == 0) } else { Err(vec![format!("{}", i); i % 3]) };
+                (i as u64, format!("row {}", i), list, result, 0 .. i as u8)
+            }).collect();
+            let column = Columnar::as_columns(rows.iter());
+            let borrowed = Container::<Row>::borrow(&column);
+            for (index, row) in rows.iter().enumerate() {
+                let expected = serde_json::to_string(row).unwrap();
+                assert_eq!(serde_json::to_string(&borrowed.get(index)).unwrap(), expected);
+                assert_eq!(format!("{:?}", borrowed.get(index)), format!("{:?}", row));
+            }
+        }
+    }
 }
 
 /// Logic related to the transformation to and from bytes.
@@ -524,6 +1102,54 @@ pub mod bytes {
             Decoder { store }
         }
 
+        /// Decodes a sequence of byte slices, if `store` is exactly such a sequence.
+        ///
+        /// Unlike `decode`, this returns `None` rather than panicking when a length reaches past the end of `store`.
+        pub fn try_decode(store: &[u64]) -> Option<Vec<&[u8]>> {
+            let mut slices = Vec::new();
+            let mut rest = store;
+            while let Some((length, tail)) = rest.split_first() {
+                let words = length.div_ceil(8);
+                if words > tail.len() as u64 { return None; }
+                let (words, tail) = tail.split_at(words as usize);
+                slices.push(&bytemuck::cast_slice(words)[.. *length as usize]);
+                rest = tail;
+            }
+            Some(slices)
+        }
+
+        /// Decodes a borrowed container of `T`, if `store` holds exactly the byte slices of one.
+        ///
+        /// The slices must be as many as the containers of `T` present, and each must be a whole number
+        /// of elements at its alignment. Their contents, like the bounds of lists, are not checked;
+        /// `try_decode_owned` checks them.
+        pub fn try_decode_container<T: crate::Columnar>(store: &[u64]) -> Option<<T::Container as crate::Container<T>>::Borrowed<'_>> {
+            let slices = try_decode(store)?;
+            let layout = super::schema::Schema::of::<T>().layout;
+            let fits = |(slice, align): (&&[u8], &u64)| (slice.len() as u64).is_multiple_of(*align) && (slice.as_ptr() as u64).is_multiple_of(*align);
+            if slices.len() != layout.len() || !slices.iter().zip(layout.iter()).all(fits) { return None; }
+            Some(crate::FromBytes::from_bytes(&mut slices.into_iter()))
+        }
+
+        /// Decodes a container of `T` as `try_decode_container` does, and copies its rows into an owned container.
+        ///
+        /// Unlike `try_decode_container`, this checks the contents of the slices: an encoding whose rows
+        /// cannot be read, for example because the bounds of lists reach past their values, is reported
+        /// as `None` rather than panicking when the rows are later read. Contents are checked by copying
+        /// each row, and an inconsistent row is detected as the panic it causes, which is caught here but
+        /// is still reported to the panic hook.
+        pub fn try_decode_owned<T: crate::Columnar>(store: &[u64]) -> Option<T::Container> {
+            use crate::{Index, Len, Push};
+            let borrowed = try_decode_container::<T>(store)?;
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                let mut container = T::Container::default();
+                for index in 0 .. borrowed.len() {
+                    container.push(borrowed.get(index));
+                }
+                container
+            })).ok()
+        }
+
         /// An iterator over byte slices, decoding from a sequence of lengths followed by bytes.
         pub struct Decoder<'a> {
             store: &'a [u64],
@@ -546,9 +1172,260 @@ pub mod bytes {
         }
     }
 
+    /// A relocatable layout placing each byte slice at its natural alignment.
+    ///
+    /// The `serialization` layout aligns slices to eight bytes, which does not suffice for types
+    /// like `u128` whose alignment is sixteen. A slab starts with a table of contents, and places
+    /// each slice at an offset from its start that is a multiple of the slice's alignment.
+    /// As all offsets are relative to the start, the slab can be copied to and read from any
+    /// address aligned to the slab's alignment, as shared memory segments are, without copies.
+    ///
+    /// The layout, in `u64` words, is the number of slices, the alignment of the slab in bytes,
+    /// then for each slice its offset and its length in bytes, and then the slices themselves,
+    /// each padded with zeros out to a whole number of words.
+    ///
+    /// Slabs encoded with `encode_padded` place every slice at a multiple of a larger alignment,
+    /// like the 256 bytes GPU buffer bindings may require, and `descriptors` presents each slice
+    /// as a pointer and length, suitable for copying to a device with no repacking.
+    pub mod slab {
+
+        /// Appends a slab holding a sequence of byte slices, each with its required alignment.
+        pub fn encode<'a>(store: &mut Vec<u64>, bytes: impl Iterator<Item=(u64, &'a [u8])>) {
+            encode_padded(store, bytes, 8)
+        }
+
+        /// Appends a slab holding a sequence of byte slices, each aligned to at least `align` bytes.
+        ///
+        /// The alignment must be a power of two.
+        pub fn encode_padded<'a>(store: &mut Vec<u64>, bytes: impl Iterator<Item=(u64, &'a [u8])>, align: u64) {
+            assert!(align.is_power_of_two());
+            let slices = bytes.map(|(slice_align, slice)| (std::cmp::max(slice_align, align), slice)).collect::<Vec<_>>();
+            let align = slices.iter().map(|(align, _)| *align).fold(std::cmp::max(align, 8), std::cmp::max);
+            let start = store.len();
+            store.push(slices.len() as u64);
+            store.push(align);
+            store.resize(start + 2 + 2 * slices.len(), 0);
+            for (index, (align, bytes)) in slices.iter().enumerate() {
+                assert!(align.is_power_of_two());
+                // Pad with zero words until the offset from `start` is a multiple of `align`.
+                while !(8 * (store.len() - start) as u64).is_multiple_of(*align) { store.push(0); }
+                store[start + 2 + 2 * index] = 8 * (store.len() - start) as u64;
+                store[start + 3 + 2 * index] = bytes.len() as u64;
+                let words = store.len();
+                store.resize(words + bytes.len().div_ceil(8), 0);
+                bytemuck::cast_slice_mut::<u64, u8>(&mut store[words..])[.. bytes.len()].copy_from_slice(bytes);
+            }
+        }
+
+        /// The number of `u64` words in the slab at the start of `store`.
+        pub fn length_in_words(store: &[u64]) -> usize {
+            let count = store[0] as usize;
+            (0 .. count).map(|index| (store[2 + 2 * index] + store[3 + 2 * index]).div_ceil(8) as usize).fold(2 + 2 * count, std::cmp::max)
+        }
+
+        /// The byte slice at position `index` in the slab at the start of `store`.
+        ///
+        /// Panics if `store` is not aligned to the slab's alignment.
+        pub fn get(store: &[u64], index: usize) -> &[u8] {
+            let align = store[1] as usize;
+            assert_eq!(store.as_ptr() as usize % align, 0, "slab requires alignment {}", align);
+            let offset = store[2 + 2 * index] as usize;
+            let length = store[3 + 2 * index] as usize;
+            &bytemuck::cast_slice::<u64, u8>(store)[offset .. offset + length]
+        }
+
+        /// Decodes the byte slices of the slab at the start of `store`, in order.
+        ///
+        /// Panics if `store` is not aligned to the slab's alignment.
+        pub fn decode(store: &[u64]) -> impl Iterator<Item=&[u8]> {
+            (0 .. store[0] as usize).map(move |index| get(store, index))
+        }
+
+        /// The location of a slice within a slab, for copying to a device.
+        #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+        pub struct Descriptor {
+            /// The address of the first byte of the slice.
+            pub ptr: *const u8,
+            /// The offset of the slice from the start of the slab, in bytes.
+            pub offset: usize,
+            /// The length of the slice in bytes.
+            pub len: usize,
+        }
+
+        /// Descriptors of the slices of the slab at the start of `store`, in order.
+        ///
+        /// The slab can be copied to a device as a single buffer of `8 * length_in_words(store)`
+        /// bytes, with each slice bound at its `offset`, or each slice copied on its own from `ptr`.
+        /// Panics if `store` is not aligned to the slab's alignment.
+        pub fn descriptors(store: &[u64]) -> impl Iterator<Item=Descriptor> + '_ {
+            (0 .. store[0] as usize).map(move |index| {
+                let slice = get(store, index);
+                Descriptor { ptr: slice.as_ptr(), offset: store[2 + 2 * index] as usize, len: slice.len() }
+            })
+        }
+    }
+
+    /// A layout that records the schema of the encoded container, and checks it on decode.
+    ///
+    /// The layout, in `u64` words, is a fingerprint of the schema, followed in the `serialization`
+    /// layout by the schema's type name, its slice alignments, and the byte slices of the container.
+    /// Decoding as a type with a different schema fails with an error that describes both schemas,
+    /// rather than presenting the bytes as columns of the wrong types.
+    pub mod schema {
+
+        use crate::{AsBytes, Columnar, Container, FromBytes};
+        use super::serialization;
+
+        /// A description of the columns of a type: its name, and the alignment of each byte slice.
+        ///
+        /// The name is that of `std::any::type_name`, which spells out the structure of the type.
+        /// It is the same for each build of a type, but may change with the compiler version.
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub struct Schema {
+            /// The name of the type.
+            pub name: String,
+            /// The alignment of each byte slice of the type's containers, in order.
+            pub layout: Vec<u64>,
+        }
+
+        impl Schema {
+            /// The schema of the containers of `T`.
+            pub fn of<T: Columnar>() -> Self {
+                let container = T::Container::default();
+                let layout = container.borrow().as_bytes().map(|(align, _)| align).collect();
+                Self { name: std::any::type_name::<T>().to_string(), layout }
+            }
+            /// A deterministic hash of the schema, by FNV-1a.
+            pub fn fingerprint(&self) -> u64 {
+                let layout = self.layout.iter().flat_map(|align| align.to_le_bytes());
+                self.name.bytes().chain(layout).fold(0xcbf29ce484222325, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3))
+            }
+        }
+
+        impl std::fmt::Display for Schema {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{} with slice alignments {:?}", self.name, self.layout)
+            }
+        }
+
+        /// A reason an encoding cannot be decoded as the requested type.
+        #[derive(Clone, Debug, PartialEq, Eq)]
+        pub enum SchemaError {
+            /// The encoding is too short to hold a schema.
+            Missing,
+            /// The encoding has schema `found`, rather than `expected`.
+            Mismatch { expected: Schema, found: Schema },
+        }
+
+        impl std::fmt::Display for SchemaError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    SchemaError::Missing => write!(f, "encoding does not hold a schema"),
+                    SchemaError::Mismatch { expected, found } => write!(f, "expected {}, found {}", expected, found),
+                }
+            }
+        }
+        impl std::error::Error for SchemaError { }
+
+        /// Appends the schema of `T` and the byte slices of `container` to `store`.
+        pub fn encode<T: Columnar>(store: &mut Vec<u64>, container: <T::Container as Container<T>>::Borrowed<'_>) {
+            let schema = Schema::of::<T>();
+            store.push(schema.fingerprint());
+            let header = [(1, schema.name.as_bytes()), (8, bytemuck::cast_slice(&schema.layout[..]))];
+            serialization::encode(store, header.into_iter());
+            serialization::encode(store, container.as_bytes());
+        }
+
+        /// Decodes a container of `T` encoded by `encode`, if it was encoded with the schema of `T`.
+        pub fn decode<T: Columnar>(store: &[u64]) -> Result<<T::Container as Container<T>>::Borrowed<'_>, SchemaError> {
+            let (fingerprint, rest) = store.split_first().ok_or(SchemaError::Missing)?;
+            let mut slices = serialization::decode(rest);
+            let name = slices.next().ok_or(SchemaError::Missing)?;
+            let layout = slices.next().ok_or(SchemaError::Missing)?;
+            let expected = Schema::of::<T>();
+            if *fingerprint != expected.fingerprint() {
+                let found = Schema {
+                    name: String::from_utf8_lossy(name).into_owned(),
+                    layout: bytemuck::try_cast_slice(layout).map(<[u64]>::to_vec).unwrap_or_default(),
+                };
+                return Err(SchemaError::Mismatch { expected, found });
+            }
+            Ok(FromBytes::from_bytes(&mut slices))
+        }
+    }
+
 
     #[cfg(test)]
     mod test {
+        #[test]
+        fn slab() {
+
+            use crate::{Columnar, Container, AsBytes, FromBytes, Index};
+            use crate::bytes::slab;
+
+            type Row = (u8, u128, String);
+            let rows: Vec<Row> = (0 .. 100).map(|i| (i as u8, (i as u128) << 100, format!("{}", i))).collect();
+            let column = Columnar::as_columns(rows.iter());
+            let borrowed = Container::<Row>::borrow(&column);
+
+            let mut store = vec![7];
+            slab::encode(&mut store, borrowed.as_bytes());
+            let slab = &store[1..];
+            assert_eq!(slab[1], 16);
+            assert_eq!(slab::length_in_words(slab), slab.len());
+
+            // Relocate the slab to memory aligned to sixteen bytes, and read it in place.
+            let mut aligned = vec![0u128; slab.len().div_ceil(2)];
+            bytemuck::cast_slice_mut::<u128, u64>(&mut aligned)[.. slab.len()].copy_from_slice(slab);
+            let slab = &bytemuck::cast_slice::<u128, u64>(&aligned)[.. slab.len()];
+            let decoded = <<<Row as Columnar>::Container as Container<Row>>::Borrowed<'_> as FromBytes>::from_bytes(&mut slab::decode(slab));
+            for (index, row) in rows.iter().enumerate() {
+                assert_eq!(decoded.get(index), (&row.0, &row.1, row.2.as_str()));
+            }
+
+            // Padded slabs place each slice at a multiple of the requested alignment.
+            let mut store = Vec::new();
+            slab::encode_padded(&mut store, borrowed.as_bytes(), 256);
+            let mut buffer = vec![0u64; store.len() + 32];
+            let start = (256 - buffer.as_ptr() as usize % 256) % 256 / 8;
+            buffer[start .. start + store.len()].copy_from_slice(&store);
+            let slab = &buffer[start .. start + store.len()];
+            let descriptors = slab::descriptors(slab).collect::<Vec<_>>();
+            assert_eq!(descriptors.len(), borrowed.as_bytes().count());
+            for (descriptor, bytes) in descriptors.iter().zip(slab::decode(slab)) {
+                assert_eq!(descriptor.offset % 256, 0);
+                assert_eq!(descriptor.ptr as usize % 256, 0);
+                assert_eq!(descriptor.len, bytes.len());
+            }
+        }
+
+        #[test]
+        fn schema() {
+
+            use crate::{Columnar, Container, Index, Len};
+            use crate::bytes::schema::{self, Schema, SchemaError};
+
+            type Row = (u64, String);
+            let rows: Vec<Row> = vec![(1, "one".to_string()), (2, "two".to_string())];
+            let column = Columnar::as_columns(rows.iter());
+            let mut store = Vec::new();
+            schema::encode::<Row>(&mut store, Container::<Row>::borrow(&column));
+
+            let decoded = schema::decode::<Row>(&store).unwrap();
+            assert_eq!(decoded.len(), 2);
+            assert_eq!(decoded.get(1), (&2, "two"));
+
+            match schema::decode::<(u32, String)>(&store) {
+                Err(SchemaError::Mismatch { expected, found }) => {
+                    assert_eq!(expected, Schema::of::<(u32, String)>());
+                    assert_eq!(found, Schema::of::<Row>());
+                }
+                _ => panic!("decoded with the wrong schema"),
+            }
+            assert!(schema::decode::<Row>(&[]) == Err(SchemaError::Missing));
+            assert_ne!(Schema::of::<Vec<u8>>().fingerprint(), Schema::of::<Vec<i8>>().fingerprint());
+        }
+
         #[test]
         fn round_trip() {
 
@@ -605,6 +1482,15 @@ pub mod primitive {
 
             impl crate::HeapSize for $index_type { }
 
+            impl crate::IntoOwned for $index_type {
+                type Owned = $index_type;
+                #[inline(always)] fn into_owned(self) -> Self::Owned { self }
+            }
+            impl<'a> crate::IntoOwned for &'a $index_type {
+                type Owned = $index_type;
+                #[inline(always)] fn into_owned(self) -> Self::Owned { *self }
+            }
+
             impl<'a> crate::AsBytes<'a> for &'a [$index_type] {
                 fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
                     std::iter::once((std::mem::align_of::<$index_type>() as u64, bytemuck::cast_slice(&self[..])))
@@ -626,11 +1512,16 @@ pub mod primitive {
     /// Columnar stores for `usize` and `isize`, stored as 64 bits.
     mod sizes {
 
-        use crate::{Clear, Columnar, Len, IndexMut, Index, IndexAs, Push, HeapSize};
+        use crate::{Clear, Columnar, Len, IndexMut, Index, IndexAs, Push, HeapSize, Permute, ExtendFromRange};
 
-        #[derive(Copy, Clone, Default)]
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
         pub struct Usizes<CV = Vec<u64>> { pub values: CV }
 
+        impl crate::IntoOwned for usize {
+            type Owned = usize;
+            #[inline(always)] fn into_owned(self) -> Self::Owned { self }
+        }
+
         impl Columnar for usize {
             type Ref<'a> = usize;
             fn into_owned<'a>(other: Self::Ref<'a>) -> Self { other }
@@ -645,6 +1536,8 @@ pub mod primitive {
         }
 
         impl<CV: Len> Len for Usizes<CV> { fn len(&self) -> usize { self.values.len() }}
+        impl<CV: crate::AsSlice<u64>> crate::AsSlice<u64> for Usizes<CV> { fn as_slice(&self) -> &[u64] { self.values.as_slice() }}
+        impl<CV: crate::AsMutSlice<u64>> crate::AsMutSlice<u64> for Usizes<CV> { fn as_mut_slice(&mut self) -> &mut [u64] { self.values.as_mut_slice() }}
         impl IndexMut for Usizes {
             type IndexMut<'a> = &'a mut u64;
             #[inline(always)] fn get_mut(&mut self, index: usize) -> Self::IndexMut<'_> { &mut self.values[index] }
@@ -664,6 +1557,8 @@ pub mod primitive {
             fn push(&mut self, item: &usize) { self.values.push((*item).try_into().unwrap()) }
         }
         impl<CV: Clear> Clear for Usizes<CV> { fn clear(&mut self) { self.values.clear() }}
+        impl<CV: Permute> Permute for Usizes<CV> { fn permute(&mut self, perm: &[usize]) { self.values.permute(perm) }}
+        impl<CV: ExtendFromRange> ExtendFromRange for Usizes<CV> { fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) { self.values.extend_from_range(&other.values, range) }}
 
         impl<CV: HeapSize> HeapSize for Usizes<CV> {
             fn heap_size(&self) -> (usize, usize) {
@@ -684,9 +1579,14 @@ pub mod primitive {
         }
 
 
-        #[derive(Copy, Clone, Default)]
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
         pub struct Isizes<CV = Vec<i64>> { pub values: CV }
 
+        impl crate::IntoOwned for isize {
+            type Owned = isize;
+            #[inline(always)] fn into_owned(self) -> Self::Owned { self }
+        }
+
         impl Columnar for isize {
             type Ref<'a> = isize;
             fn into_owned<'a>(other: Self::Ref<'a>) -> Self { other }
@@ -701,6 +1601,8 @@ pub mod primitive {
         }
 
         impl<CV: Len> Len for Isizes<CV> { fn len(&self) -> usize { self.values.len() }}
+        impl<CV: crate::AsSlice<i64>> crate::AsSlice<i64> for Isizes<CV> { fn as_slice(&self) -> &[i64] { self.values.as_slice() }}
+        impl<CV: crate::AsMutSlice<i64>> crate::AsMutSlice<i64> for Isizes<CV> { fn as_mut_slice(&mut self) -> &mut [i64] { self.values.as_mut_slice() }}
         impl IndexMut for Isizes {
             type IndexMut<'a> = &'a mut i64;
             #[inline(always)] fn get_mut(&mut self, index: usize) -> Self::IndexMut<'_> { &mut self.values[index] }
@@ -720,6 +1622,8 @@ pub mod primitive {
             fn push(&mut self, item: &isize) { self.values.push((*item).try_into().unwrap()) }
         }
         impl<CV: Clear> Clear for Isizes<CV> { fn clear(&mut self) { self.values.clear() }}
+        impl<CV: Permute> Permute for Isizes<CV> { fn permute(&mut self, perm: &[usize]) { self.values.permute(perm) }}
+        impl<CV: ExtendFromRange> ExtendFromRange for Isizes<CV> { fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) { self.values.extend_from_range(&other.values, range) }}
 
         impl<CV: HeapSize> HeapSize for Isizes<CV> {
             fn heap_size(&self) -> (usize, usize) {
@@ -740,15 +1644,168 @@ pub mod primitive {
         }
     }
 
+    pub use wide::{U128s, I128s};
+    /// Alternative columnar stores for `u128` and `i128`, as separate high and low halves.
+    ///
+    /// These are not the default containers, which are `Vec<u128>` and `Vec<i128>`.
+    /// Storing each half in its own `u64`-aligned column halves the alignment requirement,
+    /// and lets range predicates compare whole runs of 64-bit high halves before consulting
+    /// the low halves.
+    mod wide {
+
+        use std::ops::RangeInclusive;
+        use crate::{Clear, Len, Index, IndexAs, Push, HeapSize, Permute, ExtendFromRange};
+
+        macro_rules! implement_wide {
+            ($name:ident, $wide:ty, $high:ty) => {
+
+                #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+                pub struct $name<HC = Vec<$high>, LC = Vec<u64>> {
+                    /// The high 64 bits of each value.
+                    pub high: HC,
+                    /// The low 64 bits of each value.
+                    pub low: LC,
+                }
+
+                impl<HC: IndexAs<$high> + Len, LC: IndexAs<u64>> $name<HC, LC> {
+                    /// The indices of values in `range`, in order.
+                    ///
+                    /// Rows are rejected or accepted by their high halves alone, unless they
+                    /// share a high half with an endpoint of the range.
+                    pub fn positions_in(&self, range: RangeInclusive<$wide>) -> Vec<usize> {
+                        let (lo_high, lo_low) = ((*range.start() >> 64) as $high, *range.start() as u64);
+                        let (hi_high, hi_low) = ((*range.end() >> 64) as $high, *range.end() as u64);
+                        let mut result = Vec::new();
+                        for index in 0 .. self.high.len() {
+                            let high = self.high.index_as(index);
+                            let keep = if lo_high < high && high < hi_high { true }
+                            else if high < lo_high || hi_high < high { false }
+                            else {
+                                let low = self.low.index_as(index);
+                                (high > lo_high || low >= lo_low) && (high < hi_high || low <= hi_low)
+                            };
+                            if keep { result.push(index); }
+                        }
+                        result
+                    }
+                }
+
+                impl<HC: Len, LC> Len for $name<HC, LC> { fn len(&self) -> usize { self.high.len() }}
+                impl<HC: IndexAs<$high>, LC: IndexAs<u64>> Index for $name<HC, LC> {
+                    type Ref = $wide;
+                    #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+                        ((self.high.index_as(index) as $wide) << 64) | (self.low.index_as(index) as $wide)
+                    }
+                }
+                impl<'a, HC: IndexAs<$high>, LC: IndexAs<u64>> Index for &'a $name<HC, LC> {
+                    type Ref = $wide;
+                    #[inline(always)] fn get(&self, index: usize) -> Self::Ref { (*self).get(index) }
+                }
+                impl<HC: Push<$high>, LC: Push<u64>> Push<$wide> for $name<HC, LC> {
+                    fn push(&mut self, item: $wide) {
+                        self.high.push((item >> 64) as $high);
+                        self.low.push(item as u64);
+                    }
+                }
+                impl<HC: Push<$high>, LC: Push<u64>> Push<&$wide> for $name<HC, LC> {
+                    fn push(&mut self, item: &$wide) { self.push(*item) }
+                }
+                impl<HC: Clear, LC: Clear> Clear for $name<HC, LC> {
+                    fn clear(&mut self) {
+                        self.high.clear();
+                        self.low.clear();
+                    }
+                }
+                impl<HC: Permute, LC: Permute> Permute for $name<HC, LC> {
+                    fn permute(&mut self, perm: &[usize]) {
+                        self.high.permute(perm);
+                        self.low.permute(perm);
+                    }
+                }
+                impl<HC: ExtendFromRange, LC: ExtendFromRange> ExtendFromRange for $name<HC, LC> {
+                    fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+                        self.high.extend_from_range(&other.high, range.clone());
+                        self.low.extend_from_range(&other.low, range);
+                    }
+                }
+                impl<HC: HeapSize, LC: HeapSize> HeapSize for $name<HC, LC> {
+                    fn heap_size(&self) -> (usize, usize) {
+                        let (l0, c0) = self.high.heap_size();
+                        let (l1, c1) = self.low.heap_size();
+                        (l0 + l1, c0 + c1)
+                    }
+                }
+                impl<'a, HC: crate::AsBytes<'a>, LC: crate::AsBytes<'a>> crate::AsBytes<'a> for $name<HC, LC> {
+                    fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
+                        self.high.as_bytes().chain(self.low.as_bytes())
+                    }
+                }
+                impl<'a, HC: crate::FromBytes<'a>, LC: crate::FromBytes<'a>> crate::FromBytes<'a> for $name<HC, LC> {
+                    fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self {
+                        Self {
+                            high: crate::FromBytes::from_bytes(bytes),
+                            low: crate::FromBytes::from_bytes(bytes),
+                        }
+                    }
+                }
+            }
+        }
+
+        implement_wide!(U128s, u128, u64);
+        implement_wide!(I128s, i128, i64);
+
+        #[cfg(test)]
+        mod test {
+
+            use crate::{Index, Len, Push};
+            use super::{U128s, I128s};
+
+            #[test]
+            fn positions_in() {
+                let values: Vec<i128> = (-50 .. 50).map(|i: i128| i * (1 << 63) + i).collect();
+                let mut column: I128s = Default::default();
+                column.extend(values.iter());
+                assert_eq!(column.len(), 100);
+                assert_eq!(column.iter().collect::<Vec<_>>(), values);
+                let range = values[10] + 1 ..= values[20];
+                let expected: Vec<usize> = (0 .. 100).filter(|i| range.contains(&values[*i])).collect();
+                assert_eq!(column.positions_in(range), expected);
+
+                let mut column: U128s = Default::default();
+                column.extend((0 .. 100u128).map(|i| (i << 60) | i));
+                assert_eq!(column.positions_in((3 << 60) ..= (9 << 60) + 9), (3 .. 10).collect::<Vec<_>>());
+            }
+        }
+    }
+
     pub use empty::Empties;
-    /// A columnar store for `()`.
+    /// A columnar store for `()`, and other zero-sized types like `PhantomData<T>`.
     mod empty {
 
+        use std::marker::PhantomData;
         use crate::common::index::CopyAs;
-        use crate::{Clear, Columnar, Len, IndexMut, Index, Push, HeapSize};
+        use crate::{Clear, Columnar, Len, IndexMut, Index, Push, HeapSize, Permute, ExtendFromRange};
+
+        /// A count of zero-sized items, each equal to `empty`.
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+        pub struct Empties<CC = u64, M = ()> { pub count: CC, pub empty: M }
 
-        #[derive(Copy, Clone, Debug, Default)]
-        pub struct Empties<CC = u64> { pub count: CC, pub empty: () }
+        impl crate::IntoOwned for () {
+            type Owned = ();
+            #[inline(always)] fn into_owned(self) -> Self::Owned { }
+        }
+        impl crate::IntoOwned for &() {
+            type Owned = ();
+            #[inline(always)] fn into_owned(self) -> Self::Owned { }
+        }
+        impl<T: ?Sized> crate::IntoOwned for PhantomData<T> {
+            type Owned = PhantomData<T>;
+            #[inline(always)] fn into_owned(self) -> Self::Owned { self }
+        }
+        impl<T: ?Sized> crate::IntoOwned for &PhantomData<T> {
+            type Owned = PhantomData<T>;
+            #[inline(always)] fn into_owned(self) -> Self::Owned { PhantomData }
+        }
 
         impl Columnar for () {
             type Ref<'a> = ();
@@ -761,46 +1818,66 @@ pub mod primitive {
             fn borrow<'a>(&'a self) -> Self::Borrowed<'a> { Empties { count: &self.count, empty: () } }
         }
 
-        impl<CC: CopyAs<u64> + Copy> Len for Empties<CC> {
+        impl<T: ?Sized + 'static> Columnar for PhantomData<T> {
+            type Ref<'a> = PhantomData<T>;
+            fn into_owned<'a>(other: Self::Ref<'a>) -> Self { other }
+            type Container = Empties<u64, PhantomData<T>>;
+        }
+
+        impl<T: ?Sized + 'static> crate::Container<PhantomData<T>> for Empties<u64, PhantomData<T>> {
+            type Borrowed<'a> = Empties<&'a u64, PhantomData<T>>;
+            fn borrow<'a>(&'a self) -> Self::Borrowed<'a> { Empties { count: &self.count, empty: PhantomData } }
+        }
+
+        impl<CC: CopyAs<u64> + Copy, M> Len for Empties<CC, M> {
             fn len(&self) -> usize { self.count.copy_as() as usize }
         }
-        impl<CC> IndexMut for Empties<CC> {
-            type IndexMut<'a> = &'a mut () where CC: 'a;
+        impl<CC, M> IndexMut for Empties<CC, M> {
+            type IndexMut<'a> = &'a mut M where CC: 'a, M: 'a;
             // TODO: panic if out of bounds?
             #[inline(always)] fn get_mut(&mut self, _index: usize) -> Self::IndexMut<'_> { &mut self.empty }
         }
-        impl<CC> Index for Empties<CC> {
-            type Ref = ();
-            fn get(&self, _index: usize) -> Self::Ref { () }
+        impl<CC, M: Copy> Index for Empties<CC, M> {
+            type Ref = M;
+            fn get(&self, _index: usize) -> Self::Ref { self.empty }
         }
-        impl<'a, CC> Index for &'a Empties<CC> {
-            type Ref = &'a ();
-            fn get(&self, _index: usize) -> Self::Ref { &() }
+        impl<'a, CC, M> Index for &'a Empties<CC, M> {
+            type Ref = &'a M;
+            fn get(&self, _index: usize) -> Self::Ref { &self.empty }
         }
-        impl Push<()> for Empties {
+        impl<M> Push<M> for Empties<u64, M> {
             // TODO: check for overflow?
-            fn push(&mut self, _item: ()) { self.count += 1; }
+            fn push(&mut self, _item: M) { self.count += 1; }
         }
-        impl Push<&()> for Empties {
+        impl<M> Push<&M> for Empties<u64, M> {
             // TODO: check for overflow?
-            fn push(&mut self, _item: &()) { self.count += 1; }
+            fn push(&mut self, _item: &M) { self.count += 1; }
         }
 
-        impl HeapSize for Empties {
+        impl<M> HeapSize for Empties<u64, M> {
             fn heap_size(&self) -> (usize, usize) { (0, 0) }
         }
-        impl Clear for Empties {
+        impl<M> Clear for Empties<u64, M> {
             fn clear(&mut self) { self.count = 0; }
         }
+        impl<M> Permute for Empties<u64, M> {
+            fn permute(&mut self, perm: &[usize]) { assert_eq!(perm.len() as u64, self.count); }
+        }
+        impl<M> ExtendFromRange for Empties<u64, M> {
+            fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+                assert!(range.start <= range.end && range.end as u64 <= other.count);
+                self.count += range.len() as u64;
+            }
+        }
 
-        impl<'a> crate::AsBytes<'a> for crate::primitive::Empties<&'a u64> {
+        impl<'a, M> crate::AsBytes<'a> for crate::primitive::Empties<&'a u64, M> {
             fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
                 std::iter::once((8, bytemuck::cast_slice(std::slice::from_ref(self.count))))
             }
         }
-        impl<'a> crate::FromBytes<'a> for crate::primitive::Empties<&'a u64> {
+        impl<'a, M: Default> crate::FromBytes<'a> for crate::primitive::Empties<&'a u64, M> {
             fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self {
-                Self { count: &bytemuck::try_cast_slice(bytes.next().unwrap()).unwrap()[0], empty: () }
+                Self { count: &bytemuck::try_cast_slice(bytes.next().unwrap()).unwrap()[0], empty: M::default() }
             }
         }
     }
@@ -810,10 +1887,10 @@ pub mod primitive {
     mod boolean {
 
         use crate::common::index::CopyAs;
-        use crate::{Clear, Len, Index, IndexAs, Push, HeapSize};
+        use crate::{Clear, Len, Index, IndexAs, Push, HeapSize, Permute, ExtendFromRange};
 
         /// A store for maintaining `Vec<bool>`.
-        #[derive(Copy, Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
         pub struct Bools<VC = Vec<u64>, WC = u64> {
             /// The bundles of bits that form complete `u64` values.
             pub values: VC,
@@ -823,6 +1900,11 @@ pub mod primitive {
             pub last_bits: WC,
         }
 
+        impl crate::IntoOwned for bool {
+            type Owned = bool;
+            #[inline(always)] fn into_owned(self) -> Self::Owned { self }
+        }
+
         impl crate::Columnar for bool {
             type Ref<'a> = bool;
             fn into_owned<'a>(other: Self::Ref<'a>) -> Self { other }
@@ -875,6 +1957,62 @@ pub mod primitive {
             }
         }
 
+        impl<VC: Len + IndexAs<u64>, WC: Copy + CopyAs<u64>> Bools<VC, WC> {
+            /// The word of bits holding bits `64 * block ..`, including the incomplete last word.
+            #[inline(always)] fn word(&self, block: usize) -> u64 {
+                if block == self.values.len() { self.last_word.copy_as() } else { self.values.index_as(block) }
+            }
+            /// The number of set bits strictly preceding `index`.
+            ///
+            /// This counts the set bits of every prior word, and takes time linear in `index`.
+            /// Callers that rank repeatedly should use `RankSelect`, which keeps cumulative counts.
+            pub fn rank(&self, index: usize) -> usize {
+                assert!(index <= self.len());
+                let block = index / 64;
+                let mut count: usize = (0 .. block).map(|b| self.values.index_as(b).count_ones() as usize).sum();
+                let bit = index % 64;
+                if bit > 0 { count += (self.word(block) & ((1 << bit) - 1)).count_ones() as usize; }
+                count
+            }
+            /// The number of set bits in `range`.
+            ///
+            /// This ranks both ends of `range`, and takes time linear in `range.end`.
+            pub fn count_ones(&self, range: std::ops::Range<usize>) -> usize {
+                self.rank(range.end) - self.rank(range.start)
+            }
+            /// The index of the set bit preceded by `rank` set bits, should one exist.
+            ///
+            /// This scans words from the start, and takes time linear in the returned index.
+            /// Callers that select repeatedly should use `RankSelect`, which searches its counts.
+            pub fn select(&self, mut rank: usize) -> Option<usize> {
+                let blocks = self.values.len() + if self.last_bits.copy_as() > 0 { 1 } else { 0 };
+                for block in 0 .. blocks {
+                    let mut word = self.word(block);
+                    let ones = word.count_ones() as usize;
+                    if rank < ones {
+                        // Clear the lowest `rank` set bits, and locate the next.
+                        for _ in 0 .. rank { word &= word - 1; }
+                        return Some(64 * block + word.trailing_zeros() as usize);
+                    }
+                    rank -= ones;
+                }
+                None
+            }
+            /// The indices of set bits, in increasing order.
+            pub fn ones(&self) -> impl Iterator<Item=usize> + '_ {
+                let blocks = self.values.len() + if self.last_bits.copy_as() > 0 { 1 } else { 0 };
+                (0 .. blocks).flat_map(move |block| {
+                    let mut word = self.word(block);
+                    std::iter::from_fn(move || {
+                        if word == 0 { return None; }
+                        let bit = word.trailing_zeros() as usize;
+                        word &= word - 1;
+                        Some(64 * block + bit)
+                    })
+                })
+            }
+        }
+
         impl<VC: Len + IndexAs<u64>, WC: Copy + CopyAs<u64>> Index for &Bools<VC, WC> {
             type Ref = bool;
             #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
@@ -909,11 +2047,54 @@ pub mod primitive {
             }
         }
 
+        impl<VC: Push<u64> + Len + IndexAs<u64> + Default> Permute for Bools<VC> {
+            fn permute(&mut self, perm: &[usize]) {
+                assert_eq!(perm.len(), self.len());
+                let mut result = Self::default();
+                for index in perm {
+                    result.push(self.get(*index));
+                }
+                *self = result;
+            }
+        }
+
+        impl<VC: Push<u64> + Len + IndexAs<u64>> ExtendFromRange for Bools<VC> {
+            fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+                assert!(range.end <= other.len());
+                // Bits are generally not word-aligned in both `self` and `other`.
+                for index in range {
+                    self.push(other.get(index));
+                }
+            }
+        }
+
         impl<VC: HeapSize> HeapSize for Bools<VC> {
             fn heap_size(&self) -> (usize, usize) {
                 self.values.heap_size()
             }
         }
+
+        #[cfg(test)]
+        mod test {
+
+            use crate::{Columnar, Container};
+
+            #[test]
+            fn rank_select() {
+                let bits: Vec<bool> = (0 .. 1000).map(|i| i % 3 == 0 || i % 7 == 0).collect();
+                let column = Columnar::as_columns(bits.iter());
+                let ones: Vec<usize> = (0 .. 1000).filter(|i| bits[*i]).collect();
+                assert_eq!(column.ones().collect::<Vec<_>>(), ones);
+                assert_eq!(Container::<bool>::borrow(&column).ones().count(), ones.len());
+                for (rank, index) in ones.iter().enumerate() {
+                    assert_eq!(column.select(rank), Some(*index));
+                    assert_eq!(column.rank(*index), rank);
+                }
+                assert_eq!(column.select(ones.len()), None);
+                assert_eq!(column.rank(1000), ones.len());
+                assert_eq!(column.count_ones(10 .. 30), (10 .. 30).filter(|i| bits[*i]).count());
+            }
+        }
     }
 
     pub use duration::Durations;
@@ -921,15 +2102,20 @@ pub mod primitive {
     mod duration {
 
         use std::time::Duration;
-        use crate::{Len, Index, IndexAs, Push, Clear, HeapSize};
+        use crate::{Len, Index, IndexAs, Push, Clear, HeapSize, Permute, ExtendFromRange};
 
         // `std::time::Duration` is equivalent to `(u64, u32)`, corresponding to seconds and nanoseconds.
-        #[derive(Copy, Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
         pub struct Durations<SC = Vec<u64>, NC = Vec<u32>> {
             pub seconds: SC,
             pub nanoseconds: NC,
         }
 
+        impl crate::IntoOwned for Duration {
+            type Owned = Duration;
+            #[inline(always)] fn into_owned(self) -> Self::Owned { self }
+        }
+
         impl crate::Columnar for Duration {
             type Ref<'a> = Duration;
             fn into_owned<'a>(other: Self::Ref<'a>) -> Self { other }
@@ -970,6 +2156,12 @@ pub mod primitive {
                 Duration::new(self.seconds.index_as(index), self.nanoseconds.index_as(index))
             }
         }
+        impl<SC: IndexAs<u64>, NC: IndexAs<u32>> Index for &Durations<SC, NC> {
+            type Ref = Duration;
+            #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+                Duration::new(self.seconds.index_as(index), self.nanoseconds.index_as(index))
+            }
+        }
 
         impl<SC: Push<u64>, NC: Push<u32>> Push<std::time::Duration> for Durations<SC, NC> {
             fn push(&mut self, item: std::time::Duration) {
@@ -996,6 +2188,20 @@ pub mod primitive {
             }
         }
 
+        impl<SC: Permute, NC: Permute> Permute for Durations<SC, NC> {
+            fn permute(&mut self, perm: &[usize]) {
+                self.seconds.permute(perm);
+                self.nanoseconds.permute(perm);
+            }
+        }
+
+        impl<SC: ExtendFromRange, NC: ExtendFromRange> ExtendFromRange for Durations<SC, NC> {
+            fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+                self.seconds.extend_from_range(&other.seconds, range.clone());
+                self.nanoseconds.extend_from_range(&other.nanoseconds, range);
+            }
+        }
+
         impl<SC: HeapSize, NC: HeapSize> HeapSize for Durations<SC, NC> {
             fn heap_size(&self) -> (usize, usize) {
                 let (l0, c0) = self.seconds.heap_size();
@@ -1003,16 +2209,199 @@ pub mod primitive {
                 (l0 + l1, c0 + c1)
             }
         }
+
+        #[cfg(test)]
+        mod test {
+
+            use std::time::Duration;
+            use crate::{Columnar, Container, HeapSize, Index, Len};
+
+            #[test]
+            fn layout() {
+                let durations: Vec<Duration> = (0 .. 100).map(|i| Duration::new(i, i as u32 * 1000)).collect();
+                let column = Columnar::as_columns(durations.iter());
+                // Twelve bytes per row, rather than the sixteen of a padded `Vec<Duration>`.
+                assert_eq!(column.len(), 100);
+                assert_eq!(column.heap_size().0, 1200);
+                let borrowed = Container::<Duration>::borrow(&column);
+                for (index, duration) in durations.iter().enumerate() {
+                    assert_eq!(&borrowed.get(index), duration);
+                }
+            }
+        }
+    }
+
+    pub use chars::Chars;
+    /// A columnar store for `char`.
+    mod chars {
+
+        use crate::{Len, Index, IndexAs, Push, Clear, HeapSize, Permute, ExtendFromRange, InvalidParts};
+
+        /// Unicode scalar values are at most `0x10FFFF`, and fit in 21 bits.
+        ///
+        /// The low sixteen bits and the remaining five are stored as separate columns, three bytes
+        /// per row rather than the four of a `Vec<char>`. Values are checked to be scalar values as
+        /// they are read out, and reading a value that is not, as from corrupted bytes, panics.
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+        pub struct Chars<LC = Vec<u16>, HC = Vec<u8>> {
+            /// The low sixteen bits of each scalar value.
+            pub low: LC,
+            /// The bits above the low sixteen of each scalar value.
+            pub high: HC,
+        }
+
+        impl crate::IntoOwned for char {
+            type Owned = char;
+            #[inline(always)] fn into_owned(self) -> Self::Owned { self }
+        }
+        impl crate::HeapSize for char { }
+
+        impl crate::Columnar for char {
+            type Ref<'a> = char;
+            fn into_owned<'a>(other: Self::Ref<'a>) -> Self { other }
+            type Container = Chars;
+        }
+
+        impl<LC: crate::Container<u16>, HC: crate::Container<u8>> crate::Container<char> for Chars<LC, HC> {
+            type Borrowed<'a> = Chars<LC::Borrowed<'a>, HC::Borrowed<'a>> where LC: 'a, HC: 'a;
+            fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
+                Chars {
+                    low: self.low.borrow(),
+                    high: self.high.borrow(),
+                }
+            }
+        }
+
+        impl<LC: Len + IndexAs<u16>, HC: Len + IndexAs<u8>> Chars<LC, HC> {
+            /// The columns, for use elsewhere or to rebuild with `try_from_parts`.
+            pub fn into_parts(self) -> (LC, HC) { (self.low, self.high) }
+            /// Characters from their columns, if each pair of values forms a scalar value.
+            ///
+            /// Panics if the columns have different lengths.
+            pub fn try_from_parts(low: LC, high: HC) -> Result<Self, InvalidParts> {
+                assert_eq!(low.len(), high.len());
+                for index in 0 .. low.len() {
+                    if char::from_u32(((high.index_as(index) as u32) << 16) | low.index_as(index) as u32).is_none() {
+                        return Err(InvalidParts::Scalar { index });
+                    }
+                }
+                Ok(Self { low, high })
+            }
+        }
+
+        impl<'a, LC: crate::AsBytes<'a>, HC: crate::AsBytes<'a>> crate::AsBytes<'a> for crate::primitive::Chars<LC, HC> {
+            fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
+                self.low.as_bytes().chain(self.high.as_bytes())
+            }
+        }
+        impl<'a, LC: crate::FromBytes<'a>, HC: crate::FromBytes<'a>> crate::FromBytes<'a> for crate::primitive::Chars<LC, HC> {
+            fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self {
+                Self {
+                    low: crate::FromBytes::from_bytes(bytes),
+                    high: crate::FromBytes::from_bytes(bytes),
+                }
+            }
+        }
+
+        impl<LC: Len, HC> Len for Chars<LC, HC> {
+            #[inline(always)] fn len(&self) -> usize { self.low.len() }
+        }
+
+        impl<LC: IndexAs<u16>, HC: IndexAs<u8>> Index for Chars<LC, HC> {
+            type Ref = char;
+            #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+                let value = ((self.high.index_as(index) as u32) << 16) | self.low.index_as(index) as u32;
+                char::from_u32(value).unwrap_or_else(|| panic!("invalid scalar value {:#x} at {}", value, index))
+            }
+        }
+        impl<LC: IndexAs<u16>, HC: IndexAs<u8>> Index for &Chars<LC, HC> {
+            type Ref = char;
+            #[inline(always)] fn get(&self, index: usize) -> Self::Ref { (*self).get(index) }
+        }
+
+        impl<LC: Push<u16>, HC: Push<u8>> Push<char> for Chars<LC, HC> {
+            fn push(&mut self, item: char) {
+                self.low.push(item as u32 as u16);
+                self.high.push((item as u32 >> 16) as u8);
+            }
+        }
+        impl<'a, LC: Push<u16>, HC: Push<u8>> Push<&'a char> for Chars<LC, HC> {
+            fn push(&mut self, item: &'a char) {
+                self.push(*item)
+            }
+        }
+
+        impl<LC: Clear, HC: Clear> Clear for Chars<LC, HC> {
+            fn clear(&mut self) {
+                self.low.clear();
+                self.high.clear();
+            }
+        }
+
+        impl<LC: Permute, HC: Permute> Permute for Chars<LC, HC> {
+            fn permute(&mut self, perm: &[usize]) {
+                self.low.permute(perm);
+                self.high.permute(perm);
+            }
+        }
+
+        impl<LC: ExtendFromRange, HC: ExtendFromRange> ExtendFromRange for Chars<LC, HC> {
+            fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+                self.low.extend_from_range(&other.low, range.clone());
+                self.high.extend_from_range(&other.high, range);
+            }
+        }
+
+        impl<LC: HeapSize, HC: HeapSize> HeapSize for Chars<LC, HC> {
+            fn heap_size(&self) -> (usize, usize) {
+                let (l0, c0) = self.low.heap_size();
+                let (l1, c1) = self.high.heap_size();
+                (l0 + l1, c0 + c1)
+            }
+        }
+
+        #[cfg(test)]
+        mod test {
+
+            use crate::{Columnar, Container, HeapSize, Index, InvalidParts, Len};
+            use super::Chars;
+
+            #[test]
+            fn chars() {
+                let chars: Vec<char> = "a\0é☃\u{10FFFF}\u{1F600}".chars().cycle().take(100).collect();
+                let column = Columnar::as_columns(chars.iter());
+                // Three bytes per row, rather than the four of a `Vec<char>`.
+                assert_eq!(column.len(), 100);
+                assert_eq!(column.heap_size().0, 300);
+                let borrowed = Container::<char>::borrow(&column);
+                assert!(chars.iter().enumerate().all(|(index, c)| borrowed.get(index) == *c));
+
+                // Surrogates and values beyond `char::MAX` are not scalar values.
+                assert_eq!(Chars::try_from_parts(vec![0x61, 0xD800], vec![0u8, 0]), Err(InvalidParts::Scalar { index: 1 }));
+                assert_eq!(Chars::try_from_parts(vec![0x0], vec![0x11u8]), Err(InvalidParts::Scalar { index: 0 }));
+                assert_eq!(Chars::try_from_parts(column.low.clone(), column.high.clone()), Ok(column));
+            }
+
+            #[test]
+            #[should_panic]
+            fn invalid() {
+                let column = Chars { low: vec![0xDFFFu16], high: vec![0u8] };
+                column.get(0);
+            }
+        }
     }
 }
 
-pub use string::Strings;
+pub use string::{Strings, Strs};
 pub mod string {
 
-    use super::{Clear, Columnar, Len, Index, IndexAs, Push, HeapSize};
+    use super::{Clear, Columnar, Len, Index, IndexAs, Push, HeapSize, Permute, ExtendFromRange, ColumnBytes};
 
     /// A stand-in for `Vec<String>`.
-    #[derive(Copy, Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+    ///
+    /// The bounds and bytes have the layout of a `ColumnBytes`, which `column_bytes` presents them as.
+    /// The bytes of each string are UTF-8, which is checked as strings are read out.
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
     pub struct Strings<BC = Vec<u64>, VC = Vec<u8>> {
         /// Bounds container; provides indexed access to offsets.
         pub bounds: BC,
@@ -1020,6 +2409,58 @@ pub mod string {
         pub values: VC,
     }
 
+    impl<BC, VC> Strings<BC, VC> {
+        /// Strings from their bounds and bytes, which must be UTF-8 for each string.
+        pub fn from_bytes_unchecked(bytes: ColumnBytes<BC, VC>) -> Self { Self { bounds: bytes.bounds, values: bytes.values } }
+        /// The bounds and bytes, for use elsewhere or to rebuild with `try_from_parts`.
+        pub fn into_parts(self) -> (BC, VC) { (self.bounds, self.values) }
+        /// The bounds and bytes as a `ColumnBytes`, whose rows are the bytes of the strings.
+        pub fn into_column_bytes(self) -> ColumnBytes<BC, VC> { ColumnBytes { bounds: self.bounds, values: self.values } }
+        /// A view of the bounds and bytes as a `ColumnBytes`, for its byte-level methods like `contains`.
+        pub fn column_bytes(&self) -> ColumnBytes<&[u64], &[u8]> where BC: crate::AsSlice<u64>, VC: crate::AsSlice<u8> {
+            ColumnBytes { bounds: self.bounds.as_slice(), values: self.values.as_slice() }
+        }
+        /// Applies `action` to the bounds and bytes as a `ColumnBytes`, which it must leave UTF-8 for each string.
+        fn with_column_bytes<R>(&mut self, action: impl FnOnce(&mut ColumnBytes<BC, VC>) -> R) -> R where BC: Default, VC: Default {
+            let mut bytes = std::mem::take(self).into_column_bytes();
+            let result = action(&mut bytes);
+            *self = Self::from_bytes_unchecked(bytes);
+            result
+        }
+    }
+    impl<BC: Len + IndexAs<u64>, VC: std::ops::Deref<Target=[u8]>> Strings<BC, VC> {
+        /// Strings from their bounds and bytes, if the bounds are valid and each string is UTF-8.
+        pub fn try_from_parts(bounds: BC, values: VC) -> Result<Self, crate::InvalidParts> {
+            crate::common::validate_bounds(&bounds, values.len())?;
+            let bytes = ColumnBytes { bounds, values };
+            for index in 0 .. bytes.len() {
+                if std::str::from_utf8(&bytes.values[bytes.bounds_of(index)]).is_err() {
+                    return Err(crate::InvalidParts::Utf8 { index });
+                }
+            }
+            Ok(Self::from_bytes_unchecked(bytes))
+        }
+    }
+    impl<BC: IndexAs<u64>, VC> Strings<BC, VC> {
+        /// The range of `values` holding the string at `index`.
+        #[inline(always)] pub fn bounds_of(&self, index: usize) -> std::ops::Range<usize> {
+            let lower = if index == 0 { 0 } else { self.bounds.index_as(index - 1) };
+            let upper = self.bounds.index_as(index);
+            lower as usize .. upper as usize
+        }
+    }
+    impl<BC: Push<u64>> Strings<BC> {
+        /// Completes a string of the text written since the previous string, through `std::fmt::Write`.
+        pub fn finish_row(&mut self) {
+            self.bounds.push(self.values.len() as u64);
+        }
+    }
+
+    impl crate::IntoOwned for &str {
+        type Owned = String;
+        fn into_owned(self) -> Self::Owned { self.to_string() }
+    }
+
     impl Columnar for String {
         type Ref<'a> = &'a str;
         fn copy_from<'a>(&mut self, other: Self::Ref<'a>) {
@@ -1044,7 +2485,7 @@ pub mod string {
         fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
             Strings {
                 bounds: self.bounds.borrow(),
-                values: self.values.borrow(),
+                values: &self.values[..],
             }
         }
     }
@@ -1056,10 +2497,7 @@ pub mod string {
     }
     impl<'a, BC: crate::FromBytes<'a>, VC: crate::FromBytes<'a>> crate::FromBytes<'a> for Strings<BC, VC> {
         fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self {
-            Self {
-                bounds: crate::FromBytes::from_bytes(bytes),
-                values: crate::FromBytes::from_bytes(bytes),
-            }
+            Self::from_bytes_unchecked(crate::FromBytes::from_bytes(bytes))
         }
     }
 
@@ -1070,34 +2508,30 @@ pub mod string {
     impl<'a, BC: Len+IndexAs<u64>> Index for Strings<BC, &'a [u8]> {
         type Ref = &'a str;
         #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
-            let lower = if index == 0 { 0 } else { self.bounds.index_as(index - 1) };
-            let upper = self.bounds.index_as(index);
-            let lower: usize = lower.try_into().unwrap();
-            let upper: usize = upper.try_into().unwrap();
-            std::str::from_utf8(&self.values[lower .. upper]).unwrap()
+            std::str::from_utf8(&self.values[self.bounds_of(index)]).unwrap()
         }
     }
     impl<'a, BC: Len+IndexAs<u64>> Index for &'a Strings<BC, Vec<u8>> {
         type Ref = &'a str;
         #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
-            let lower = if index == 0 { 0 } else { self.bounds.index_as(index - 1) };
-            let upper = self.bounds.index_as(index);
-            let lower: usize = lower.try_into().unwrap();
-            let upper: usize = upper.try_into().unwrap();
-            std::str::from_utf8(&self.values[lower .. upper]).unwrap()
+            std::str::from_utf8(&self.values[self.bounds_of(index)]).unwrap()
         }
     }
 
     impl<BC: Push<u64>> Push<&String> for Strings<BC> {
-        #[inline(always)] fn push(&mut self, item: &String) {
-            self.values.extend_from_slice(item.as_bytes());
-            self.bounds.push(self.values.len() as u64);
-        }
+        #[inline(always)] fn push(&mut self, item: &String) { self.push(&item[..]) }
     }
     impl<BC: Push<u64>> Push<&str> for Strings<BC> {
-        fn push(&mut self, item: &str) {
+        #[inline(always)] fn push(&mut self, item: &str) {
             self.values.extend_from_slice(item.as_bytes());
-            self.bounds.push(self.values.len() as u64);
+            self.finish_row();
+        }
+    }
+    impl<BC: Push<u64>> crate::TryPush<&[u8]> for Strings<BC> {
+        type Error = std::str::Utf8Error;
+        #[inline(always)] fn try_push(&mut self, item: &[u8]) -> Result<(), Self::Error> {
+            self.push(std::str::from_utf8(item)?);
+            Ok(())
         }
     }
     impl<BC: Clear, VC: Clear> Clear for Strings<BC, VC> {
@@ -1106,6 +2540,14 @@ pub mod string {
             self.values.clear();
         }
     }
+    impl Permute for Strings {
+        fn permute(&mut self, perm: &[usize]) { self.with_column_bytes(|bytes| bytes.permute(perm)) }
+    }
+    impl ExtendFromRange for Strings {
+        fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+            self.with_column_bytes(|bytes| bytes.extend_from_slices(&other.bounds, &other.values, range))
+        }
+    }
     impl<BC: HeapSize, VC: HeapSize> HeapSize for Strings<BC, VC> {
         fn heap_size(&self) -> (usize, usize) {
             let (l0, c0) = self.bounds.heap_size();
@@ -1113,47 +2555,620 @@ pub mod string {
             (l0 + l1, c0 + c1)
         }
     }
-}
 
-pub use vector::Vecs;
-pub mod vector {
+    /// Writes text into the string in progress, which is completed by `finish_row`.
+    ///
+    /// Formatting with `write!` appends directly to the column, without an intermediate `String`.
+    impl<BC> std::fmt::Write for Strings<BC> {
+        #[inline(always)] fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            self.values.extend_from_slice(s.as_bytes());
+            Ok(())
+        }
+    }
 
-    use super::{Clear, Columnar, Len, IndexMut, Index, IndexAs, Push, HeapSize, Slice};
+    /// A container for `str`, which accumulates borrowed strings without allocating each.
+    ///
+    /// This has the layout of `Strings`, which it wraps. It is a distinct type so that
+    /// `Strings` continues to be understood as a container of `String`.
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct Strs<BC = Vec<u64>, VC = Vec<u8>>(pub Strings<BC, VC>);
 
-    /// A stand-in for `Vec<Vec<T>>` for complex `T`.
-    #[derive(Debug, Default, Copy, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
-    pub struct Vecs<TC, BC = Vec<u64>> {
-        pub bounds: BC,
-        pub values: TC,
+    impl Columnar for str {
+        type Ref<'a> = &'a str;
+        type Container = Strs;
     }
 
-    impl<T: Columnar> Columnar for Vec<T> {
-        type Ref<'a> = Slice<<T::Container as crate::Container<T>>::Borrowed<'a>> where T: 'a;
-        fn copy_from<'a>(&mut self, other: Self::Ref<'a>) {
-            self.truncate(other.len());
-            let mut other_iter = other.into_iter();
-            for (s, o) in self.iter_mut().zip(&mut other_iter) {
-                T::copy_from(s, o);
-            }
-            for o in other_iter {
-                self.push(T::into_owned(o));
-            }
-        }
-        fn into_owned<'a>(other: Self::Ref<'a>) -> Self {
-            other.into_iter().map(|x| T::into_owned(x)).collect()
+    impl<BC: crate::Container<u64>> crate::Container<str> for Strs<BC, Vec<u8>> {
+        type Borrowed<'a> = Strs<BC::Borrowed<'a>, &'a [u8]> where BC: 'a;
+        fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
+            Strs(crate::Container::<String>::borrow(&self.0))
         }
-        type Container = Vecs<T::Container>;
     }
 
-    impl<T: Columnar, const N: usize> Columnar for [T; N] {
-        type Ref<'a> = Slice<<T::Container as crate::Container<T>>::Borrowed<'a>> where T: 'a;
-        fn copy_from<'a>(&mut self, other: Self::Ref<'a>) {
-            for (s, o) in self.iter_mut().zip(other.into_iter()) {
-                T::copy_from(s, o);
-            }
-        }
-        fn into_owned<'a>(other: Self::Ref<'a>) -> Self {
-            let vec: Vec<_> = other.into_iter().map(|x| T::into_owned(x)).collect();
+    impl<'a, BC: crate::AsBytes<'a>, VC: crate::AsBytes<'a>> crate::AsBytes<'a> for Strs<BC, VC> {
+        fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> { self.0.as_bytes() }
+    }
+    impl<'a, BC: crate::FromBytes<'a>, VC: crate::FromBytes<'a>> crate::FromBytes<'a> for Strs<BC, VC> {
+        fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self { Strs(Strings::from_bytes(bytes)) }
+    }
+    impl<BC: Len, VC> Len for Strs<BC, VC> {
+        #[inline(always)] fn len(&self) -> usize { self.0.len() }
+    }
+    impl<'a, BC: Len+IndexAs<u64>> Index for Strs<BC, &'a [u8]> {
+        type Ref = &'a str;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref { self.0.get(index) }
+    }
+    impl<'a, BC: Len+IndexAs<u64>> Index for &'a Strs<BC, Vec<u8>> {
+        type Ref = &'a str;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref { (&self.0).get(index) }
+    }
+    impl<BC: Push<u64>> Push<&str> for Strs<BC> {
+        #[inline(always)] fn push(&mut self, item: &str) { self.0.push(item) }
+    }
+    impl<BC: Clear, VC: Clear> Clear for Strs<BC, VC> {
+        fn clear(&mut self) { self.0.clear() }
+    }
+    impl Permute for Strs {
+        fn permute(&mut self, perm: &[usize]) { self.0.permute(perm) }
+    }
+    impl ExtendFromRange for Strs {
+        fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) { self.0.extend_from_range(&other.0, range) }
+    }
+    impl<BC: HeapSize, VC: HeapSize> HeapSize for Strs<BC, VC> {
+        fn heap_size(&self) -> (usize, usize) { self.0.heap_size() }
+    }
+
+    #[cfg(test)]
+    mod test {
+
+        use crate::{Columnar, Index, Len, Permute};
+
+        #[test]
+        fn permute() {
+            let words: Vec<String> = (0 .. 20).map(|i| "é".repeat(i % 3) + &format!("{}", i)).collect();
+            let mut strings = Columnar::as_columns(words.iter());
+            let perm: Vec<usize> = (0 .. 20).map(|i| (i * 7) % 20).collect();
+            strings.permute(&perm);
+            assert_eq!(strings.len(), 20);
+            for (index, source) in perm.iter().enumerate() {
+                assert_eq!((&strings).get(index), words[*source]);
+            }
+        }
+
+        #[test]
+        fn unsized_rows() {
+
+            use crate::{Columnar, Container, Len};
+
+            let line = "ant,bee,,cat";
+            let column = <str as Columnar>::as_columns(line.split(','));
+            assert_eq!(column.len(), 4);
+            let borrowed = Container::<str>::borrow(&column);
+            assert_eq!(borrowed.into_iter().collect::<Vec<_>>(), vec!["ant", "bee", "", "cat"]);
+        }
+    }
+}
+
+pub use blob::{ColumnBytes, Blob};
+/// Columns of raw binary payloads.
+///
+/// There is no `bytes` feature, and `bytes::Bytes` is not an element type: this crate does not
+/// depend on `bytes`. Payloads held as `Bytes` can be pushed into a `ColumnBytes` as `&bytes[..]`,
+/// and read back as `&[u8]`, from which `Bytes::copy_from_slice` rebuilds them if needed.
+pub mod blob {
+
+    use super::{Clear, Len, Index, IndexAs, Push, HeapSize, Permute, ExtendFromRange};
+    use super::primitive::Bools;
+
+    /// A column of byte strings, stored as bounds and concatenated bytes.
+    ///
+    /// Rows are indexed by `&[u8]`, avoiding the per-element work of `Vecs<Vec<u8>>`, whose index
+    /// is a `Slice`. This is the storage of `Strings`, which adds the requirement that each row be
+    /// UTF-8, and it is the place to manage bounds for other byte-backed columns.
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct ColumnBytes<BC = Vec<u64>, VC = Vec<u8>> {
+        /// Bounds container; provides indexed access to offsets.
+        pub bounds: BC,
+        /// Values container; provides slice access to bytes.
+        pub values: VC,
+    }
+
+    impl<BC: IndexAs<u64>, VC> ColumnBytes<BC, VC> {
+        /// The range of `values` holding row `index`.
+        #[inline(always)] pub fn bounds_of(&self, index: usize) -> std::ops::Range<usize> {
+            let lower = if index == 0 { 0 } else { self.bounds.index_as(index - 1) };
+            let upper = self.bounds.index_as(index);
+            lower as usize .. upper as usize
+        }
+    }
+
+    impl<BC: Len + IndexAs<u64>, VC: Len> ColumnBytes<BC, VC> {
+        /// The bounds and values, for use elsewhere or to rebuild with `try_from_parts`.
+        pub fn into_parts(self) -> (BC, VC) { (self.bounds, self.values) }
+        /// A column from its bounds and values, if the bounds are non-decreasing and end at the values' length.
+        pub fn try_from_parts(bounds: BC, values: VC) -> Result<Self, crate::InvalidParts> {
+            crate::common::validate_bounds(&bounds, values.len())?;
+            Ok(Self { bounds, values })
+        }
+    }
+
+    /// Predicates evaluated over the concatenated bytes, producing a bit for each row.
+    ///
+    /// These compare bytes directly, without forming each row or checking UTF-8, and the bits
+    /// can be read out as a selection with `Bools::ones`. The same methods apply to `Strings`,
+    /// through `Strings::column_bytes`, for which matching bytes is matching text.
+    impl<BC: Len + IndexAs<u64>, VC: std::ops::Deref<Target=[u8]>> ColumnBytes<BC, VC> {
+        /// Sets the bit of each row that contains `needle`.
+        ///
+        /// The search runs through all values at once, rather than row by row, and once a row
+        /// matches resumes from the start of the next row.
+        pub fn contains(&self, needle: &[u8]) -> Bools {
+            let mut selection = Bools::default();
+            let mut position = 0;
+            let mut index = 0;
+            while index < self.len() {
+                let Some(found) = find(&self.values[position ..], needle).map(|found| position + found) else { break; };
+                // Rows that end before the occurrence does cannot contain a match.
+                while index < self.len() && self.bounds_of(index).end < found + needle.len() {
+                    selection.push(false);
+                    index += 1;
+                }
+                if index < self.len() {
+                    let bounds = self.bounds_of(index);
+                    if bounds.start <= found {
+                        selection.push(true);
+                        index += 1;
+                        position = bounds.end;
+                    } else {
+                        // The occurrence straddles the start of the row; search from there.
+                        position = bounds.start;
+                    }
+                }
+            }
+            while index < self.len() {
+                selection.push(false);
+                index += 1;
+            }
+            selection
+        }
+        /// Sets the bit of each row that starts with `prefix`.
+        pub fn starts_with(&self, prefix: &[u8]) -> Bools {
+            let mut selection = Bools::default();
+            for index in 0 .. self.len() {
+                selection.push(self.values[self.bounds_of(index)].starts_with(prefix));
+            }
+            selection
+        }
+        /// Sets the bit of each row that ends with `suffix`.
+        pub fn ends_with(&self, suffix: &[u8]) -> Bools {
+            let mut selection = Bools::default();
+            for index in 0 .. self.len() {
+                selection.push(self.values[self.bounds_of(index)].ends_with(suffix));
+            }
+            selection
+        }
+    }
+
+    /// The position of the first occurrence of `needle` in `haystack`.
+    ///
+    /// Candidates are found by scanning for the first byte of `needle`, which compiles to a
+    /// vectorized loop, and are then compared in full.
+    fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+        let Some((first, rest)) = needle.split_first() else { return Some(0); };
+        let mut position = 0;
+        while position + needle.len() <= haystack.len() {
+            let candidate = position + haystack[position ..= haystack.len() - needle.len()].iter().position(|byte| byte == first)?;
+            if haystack[candidate + 1 .. candidate + needle.len()] == *rest { return Some(candidate); }
+            position = candidate + 1;
+        }
+        None
+    }
+
+    impl<BC: Push<u64>> ColumnBytes<BC> {
+        /// Appends one row, the concatenation of `parts`.
+        ///
+        /// This writes the bytes directly into the values, without first assembling the row.
+        pub fn push_parts<'a>(&mut self, parts: impl IntoIterator<Item=&'a [u8]>) {
+            for part in parts { self.values.extend_from_slice(part); }
+            self.finish_row();
+        }
+        /// Completes a row of the bytes written since the previous row.
+        ///
+        /// Bytes are written through `std::io::Write`, or for `Strings` through `std::fmt::Write`,
+        /// and do not form a row until this is called.
+        pub fn finish_row(&mut self) {
+            self.bounds.push(self.values.len() as u64);
+        }
+    }
+
+    /// Writes bytes into the row in progress, which is completed by `finish_row`.
+    impl<BC> std::io::Write for ColumnBytes<BC> {
+        #[inline(always)] fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.values.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        #[inline(always)] fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+            self.values.extend_from_slice(buf);
+            Ok(())
+        }
+        #[inline(always)] fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    }
+
+    /// A byte string, whose columnar container is `ColumnBytes`.
+    ///
+    /// `Vec<u8>` is stored as `Vecs<Vec<u8>>`, and read out as a `Slice`. Wrapping it in `Blob`
+    /// instead copies each row in and out as one slice of bytes, and reads rows as `&[u8]`.
+    #[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, serde::Serialize, serde::Deserialize)]
+    pub struct Blob(pub Vec<u8>);
+
+    impl From<Vec<u8>> for Blob {
+        fn from(bytes: Vec<u8>) -> Self { Blob(bytes) }
+    }
+    impl std::ops::Deref for Blob {
+        type Target = [u8];
+        #[inline(always)] fn deref(&self) -> &[u8] { &self.0 }
+    }
+
+    impl crate::Columnar for Blob {
+        type Ref<'a> = &'a [u8];
+        fn copy_from<'a>(&mut self, other: Self::Ref<'a>) {
+            self.0.clear();
+            self.0.extend_from_slice(other);
+        }
+        fn into_owned<'a>(other: Self::Ref<'a>) -> Self { Blob(other.to_vec()) }
+        type Container = ColumnBytes;
+    }
+
+    impl<BC: crate::Container<u64>> crate::Container<Blob> for ColumnBytes<BC, Vec<u8>> {
+        type Borrowed<'a> = ColumnBytes<BC::Borrowed<'a>, &'a [u8]> where BC: 'a;
+        fn borrow<'a>(&'a self) -> Self::Borrowed<'a> { ColumnBytes::borrow(self) }
+    }
+
+    impl<BC: crate::Container<u64>> ColumnBytes<BC, Vec<u8>> {
+        /// A borrowed view of the column.
+        pub fn borrow(&self) -> ColumnBytes<BC::Borrowed<'_>, &[u8]> {
+            ColumnBytes {
+                bounds: self.bounds.borrow(),
+                values: &self.values[..],
+            }
+        }
+    }
+
+    impl<'a, BC: crate::AsBytes<'a>, VC: crate::AsBytes<'a>> crate::AsBytes<'a> for ColumnBytes<BC, VC> {
+        fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
+            self.bounds.as_bytes().chain(self.values.as_bytes())
+        }
+    }
+    impl<'a, BC: crate::FromBytes<'a>, VC: crate::FromBytes<'a>> crate::FromBytes<'a> for ColumnBytes<BC, VC> {
+        fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self {
+            Self {
+                bounds: crate::FromBytes::from_bytes(bytes),
+                values: crate::FromBytes::from_bytes(bytes),
+            }
+        }
+    }
+
+    impl<BC: Len, VC> Len for ColumnBytes<BC, VC> {
+        #[inline(always)] fn len(&self) -> usize { self.bounds.len() }
+    }
+
+    impl<'a, BC: Len+IndexAs<u64>> Index for ColumnBytes<BC, &'a [u8]> {
+        type Ref = &'a [u8];
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref { &self.values[self.bounds_of(index)] }
+    }
+    impl<'a, BC: Len+IndexAs<u64>> Index for &'a ColumnBytes<BC, Vec<u8>> {
+        type Ref = &'a [u8];
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref { &self.values[self.bounds_of(index)] }
+    }
+
+    impl<BC: Push<u64>> Push<&[u8]> for ColumnBytes<BC> {
+        #[inline(always)] fn push(&mut self, item: &[u8]) { self.push_parts([item]) }
+    }
+    impl<BC: Push<u64>> Push<&Blob> for ColumnBytes<BC> {
+        #[inline(always)] fn push(&mut self, item: &Blob) { self.push(&item.0[..]) }
+    }
+    impl<BC: Push<u64>> Push<&Vec<u8>> for ColumnBytes<BC> {
+        #[inline(always)] fn push(&mut self, item: &Vec<u8>) { self.push(&item[..]) }
+    }
+    impl<BC: Push<u64>, const N: usize> Push<&[u8; N]> for ColumnBytes<BC> {
+        #[inline(always)] fn push(&mut self, item: &[u8; N]) { self.push(&item[..]) }
+    }
+    impl<BC: Clear, VC: Clear> Clear for ColumnBytes<BC, VC> {
+        fn clear(&mut self) {
+            self.bounds.clear();
+            self.values.clear();
+        }
+    }
+    impl Permute for ColumnBytes {
+        fn permute(&mut self, perm: &[usize]) {
+            assert_eq!(perm.len(), self.len());
+            let mut values = Vec::with_capacity(self.values.len());
+            let mut bounds = Vec::with_capacity(self.bounds.len());
+            for index in perm {
+                values.extend_from_slice(&self.values[self.bounds_of(*index)]);
+                bounds.push(values.len() as u64);
+            }
+            self.bounds = bounds;
+            self.values = values;
+        }
+    }
+    impl ExtendFromRange for ColumnBytes {
+        fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+            self.extend_from_slices(&other.bounds, &other.values, range)
+        }
+    }
+    impl ColumnBytes {
+        /// Appends the rows in `range` of the column with `bounds` and `values`.
+        pub(crate) fn extend_from_slices(&mut self, bounds: &[u64], values: &[u8], range: std::ops::Range<usize>) {
+            if range.is_empty() { return; }
+            let lower = if range.start == 0 { 0 } else { bounds[range.start - 1] };
+            let upper = bounds[range.end - 1];
+            // Rebase the bounds of `other` to follow the existing values of `self`.
+            let shift = self.values.len() as u64;
+            Extend::extend(&mut self.bounds, bounds[range].iter().map(|bound| bound - lower + shift));
+            self.values.extend_from_slice(&values[lower as usize .. upper as usize]);
+        }
+    }
+    impl<BC: HeapSize, VC: HeapSize> HeapSize for ColumnBytes<BC, VC> {
+        fn heap_size(&self) -> (usize, usize) {
+            let (l0, c0) = self.bounds.heap_size();
+            let (l1, c1) = self.values.heap_size();
+            (l0 + l1, c0 + c1)
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+
+        use crate::{AsBytes, FromBytes, Index, Len, Push};
+        use super::ColumnBytes;
+
+        #[test]
+        fn round_trip() {
+            let payloads: Vec<Vec<u8>> = (0 .. 100u8).map(|i| (0 .. i % 7).map(|j| i ^ j).collect()).collect();
+            let mut column: ColumnBytes = Default::default();
+            for payload in payloads.iter() { column.push(payload); }
+            assert_eq!(column.len(), 100);
+            for (index, payload) in payloads.iter().enumerate() {
+                assert_eq!((&column).get(index), &payload[..]);
+            }
+            let borrowed = column.borrow();
+            let decoded = ColumnBytes::<&[u64], &[u8]>::from_bytes(&mut borrowed.as_bytes().map(|(_, bytes)| bytes));
+            assert_eq!(decoded, borrowed);
+            assert_eq!(decoded.into_iter().collect::<Vec<_>>(), payloads);
+
+            column.push_parts([&b"ab"[..], b"", b"cd"]);
+            assert_eq!((&column).get(100), b"abcd");
+            assert_eq!(column.bounds_of(100), column.values.len() - 4 .. column.values.len());
+        }
+
+        #[test]
+        fn blobs() {
+            use crate::{Columnar, Container};
+            use super::Blob;
+
+            let blobs: Vec<Blob> = (0 .. 10u8).map(|i| Blob(vec![i; i as usize])).collect();
+            let column = Columnar::as_columns(blobs.iter());
+            let borrowed = Container::<Blob>::borrow(&column);
+            assert_eq!(borrowed.get(3), &[3, 3, 3]);
+            assert_eq!(Container::<Blob>::materialize(&column, 9), blobs[9]);
+            let mut blob = Blob::default();
+            blob.copy_from(borrowed.get(4));
+            assert_eq!(&blob[..], &[4, 4, 4, 4]);
+        }
+
+        #[test]
+        fn write() {
+            use std::io::Write;
+            let mut column: ColumnBytes = Default::default();
+            for i in 0 .. 10u8 {
+                column.write_all(&[i; 3]).unwrap();
+                column.write_all(&[i]).unwrap();
+                column.finish_row();
+            }
+            assert_eq!((&column).get(7), &[7, 7, 7, 7]);
+
+            let mut strings: crate::Strings = Default::default();
+            for i in 0 .. 10 {
+                std::fmt::Write::write_fmt(&mut strings, format_args!("{}-{}", i, i * i)).unwrap();
+                strings.finish_row();
+            }
+            assert_eq!((&strings).get(7), "7-49");
+        }
+
+        #[test]
+        fn strings() {
+            let strings = crate::Columnar::into_columns(["a", "bc", "def"].map(String::from));
+            assert_eq!(&strings.values[..], b"abcdef");
+            assert_eq!(strings.column_bytes().get(2), b"def");
+            assert_eq!((&strings).get(2), "def");
+        }
+
+        #[test]
+        fn try_push() {
+            use crate::{Len, TryPush};
+            let mut strings: crate::Strings = Default::default();
+            assert!(strings.try_push(b"ok").is_ok());
+            assert!(strings.try_extend([&b"also"[..], &[b'x', 0xff], b"never"]).is_err());
+            assert_eq!(strings.len(), 2);
+            assert_eq!((&strings).get(1), "also");
+        }
+
+        #[test]
+        fn predicates() {
+            let words = ["", "apple", "pineapple", "app", "le", "pple", "banana", "applesauce", "a", "ap"];
+            let strings: crate::Strings = crate::Columnar::into_columns(words.iter().map(|word| word.to_string()));
+            let borrowed = crate::Container::<String>::borrow(&strings);
+            for needle in ["", "a", "app", "apple", "le", "pp", "ana", "xyz", "eap"] {
+                let expected = |test: fn(&str, &str) -> bool| (0 .. words.len()).filter(|index| test(words[*index], needle)).collect::<Vec<_>>();
+                assert_eq!(borrowed.column_bytes().contains(needle.as_bytes()).ones().collect::<Vec<_>>(), expected(|word, needle| word.contains(needle)));
+                assert_eq!(strings.column_bytes().starts_with(needle.as_bytes()).ones().collect::<Vec<_>>(), expected(|word, needle| word.starts_with(needle)));
+                assert_eq!(strings.column_bytes().ends_with(needle.as_bytes()).ones().collect::<Vec<_>>(), expected(|word, needle| word.ends_with(needle)));
+            }
+        }
+    }
+}
+
+pub use runs::RunBounds;
+/// Bounds that record runs of repeated offsets once.
+pub mod runs {
+
+    use super::{Clear, Len, Index, IndexAs, Push, HeapSize};
+
+    /// A container of non-decreasing `u64` bounds, storing each run of equal values once.
+    ///
+    /// Rows of lists or strings that are empty repeat the preceding bound, and a column whose
+    /// rows are mostly empty has bounds made mostly of such runs. Used as the bounds of `Strings`,
+    /// `ColumnBytes`, or borrowed `Vecs`, this stores two words for each run rather than one
+    /// for each row. Access locates the run by binary search, in time logarithmic in the runs.
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct RunBounds<VC = Vec<u64>, EC = Vec<u64>> {
+        /// The value of each run.
+        pub values: VC,
+        /// The number of bounds through the end of each run.
+        pub ends: EC,
+    }
+
+    impl<VC, EC: Len + IndexAs<u64>> RunBounds<VC, EC> {
+        /// The index of the run containing bound `index`.
+        #[inline(always)] fn run(&self, index: usize) -> usize {
+            // The first run whose end exceeds `index`.
+            let (mut lower, mut upper) = (0, self.ends.len());
+            while lower < upper {
+                let middle = lower + (upper - lower) / 2;
+                if self.ends.index_as(middle) as usize <= index { lower = middle + 1; } else { upper = middle; }
+            }
+            lower
+        }
+        /// The number of runs.
+        pub fn runs(&self) -> usize { self.ends.len() }
+    }
+
+    impl<VC: crate::Container<u64>, EC: crate::Container<u64>> crate::Container<u64> for RunBounds<VC, EC> {
+        type Borrowed<'a> = RunBounds<VC::Borrowed<'a>, EC::Borrowed<'a>> where VC: 'a, EC: 'a;
+        fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
+            RunBounds { values: self.values.borrow(), ends: self.ends.borrow() }
+        }
+    }
+
+    impl<VC, EC: Len + IndexAs<u64>> Len for RunBounds<VC, EC> {
+        #[inline(always)] fn len(&self) -> usize { if self.ends.is_empty() { 0 } else { self.ends.index_as(self.ends.len() - 1) as usize } }
+    }
+    impl<VC: Index, EC: Len + IndexAs<u64>> Index for RunBounds<VC, EC> {
+        type Ref = VC::Ref;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref { self.values.get(self.run(index)) }
+    }
+    impl<'a, VC, EC: Len + IndexAs<u64>> Index for &'a RunBounds<VC, EC> where &'a VC: Index {
+        type Ref = <&'a VC as Index>::Ref;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref { (&self.values).get(self.run(index)) }
+    }
+
+    impl Push<u64> for RunBounds {
+        fn push(&mut self, item: u64) {
+            if self.values[..].last() == Some(&item) {
+                *self.ends.last_mut().unwrap() += 1;
+            } else {
+                let len = self.len() as u64;
+                self.values.push(item);
+                self.ends.push(len + 1);
+            }
+        }
+    }
+    impl Push<&u64> for RunBounds {
+        #[inline(always)] fn push(&mut self, item: &u64) { self.push(*item) }
+    }
+    impl<VC: Clear, EC: Clear> Clear for RunBounds<VC, EC> {
+        fn clear(&mut self) {
+            self.values.clear();
+            self.ends.clear();
+        }
+    }
+    impl<VC: HeapSize, EC: HeapSize> HeapSize for RunBounds<VC, EC> {
+        fn heap_size(&self) -> (usize, usize) {
+            let (l0, c0) = self.values.heap_size();
+            let (l1, c1) = self.ends.heap_size();
+            (l0 + l1, c0 + c1)
+        }
+    }
+    impl<'a, VC: crate::AsBytes<'a>, EC: crate::AsBytes<'a>> crate::AsBytes<'a> for RunBounds<VC, EC> {
+        fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
+            self.values.as_bytes().chain(self.ends.as_bytes())
+        }
+    }
+    impl<'a, VC: crate::FromBytes<'a>, EC: crate::FromBytes<'a>> crate::FromBytes<'a> for RunBounds<VC, EC> {
+        fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self {
+            Self {
+                values: crate::FromBytes::from_bytes(bytes),
+                ends: crate::FromBytes::from_bytes(bytes),
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+
+        use crate::{Container, HeapSize, Index, Len, Push, Strings};
+        use super::RunBounds;
+
+        #[test]
+        fn mostly_empty() {
+            let mut strings: Strings<RunBounds> = Default::default();
+            let mut dense: Strings = Default::default();
+            for i in 0 .. 1000 {
+                let item = if i % 100 == 0 { format!("{}", i) } else { String::new() };
+                strings.push(&item);
+                dense.push(&item);
+            }
+            assert_eq!(strings.len(), 1000);
+            assert_eq!(strings.bounds.runs(), 10);
+            assert!(strings.heap_size().0 < dense.heap_size().0 / 10);
+            let borrowed = Container::<String>::borrow(&strings);
+            let expected = Container::<String>::borrow(&dense);
+            assert!((0 .. 1000).all(|i| borrowed.get(i) == expected.get(i)));
+        }
+    }
+}
+
+pub use vector::Vecs;
+pub mod vector {
+
+    use std::collections::{BinaryHeap, VecDeque};
+    use super::{Clear, Columnar, Len, IndexMut, Index, IndexAs, Push, HeapSize, Slice, Permute, ExtendFromRange};
+
+    /// A stand-in for `Vec<Vec<T>>` for complex `T`.
+    #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct Vecs<TC, BC = Vec<u64>> {
+        pub bounds: BC,
+        pub values: TC,
+    }
+
+    impl<T: Columnar> Columnar for Vec<T> {
+        type Ref<'a> = Slice<<T::Container as crate::Container<T>>::Borrowed<'a>> where T: 'a;
+        fn copy_from<'a>(&mut self, other: Self::Ref<'a>) {
+            self.truncate(other.len());
+            let mut other_iter = other.into_iter();
+            for (s, o) in self.iter_mut().zip(&mut other_iter) {
+                T::copy_from(s, o);
+            }
+            for o in other_iter {
+                self.push(T::into_owned(o));
+            }
+        }
+        fn into_owned<'a>(other: Self::Ref<'a>) -> Self {
+            other.into_iter().map(|x| T::into_owned(x)).collect()
+        }
+        type Container = Vecs<T::Container>;
+    }
+
+    impl<T: Columnar, const N: usize> Columnar for [T; N] {
+        type Ref<'a> = Slice<<T::Container as crate::Container<T>>::Borrowed<'a>> where T: 'a;
+        fn copy_from<'a>(&mut self, other: Self::Ref<'a>) {
+            for (s, o) in self.iter_mut().zip(other.into_iter()) {
+                T::copy_from(s, o);
+            }
+        }
+        fn into_owned<'a>(other: Self::Ref<'a>) -> Self {
+            let vec: Vec<_> = other.into_iter().map(|x| T::into_owned(x)).collect();
             match vec.try_into() {
                 Ok(array) => array,
                 Err(_) => panic!("wrong length"),
@@ -1162,6 +3177,48 @@ pub mod vector {
         type Container = Vecs<T::Container>;
     }
 
+    impl<T: Columnar> Columnar for [T] {
+        type Ref<'a> = Slice<<T::Container as crate::Container<T>>::Borrowed<'a>> where T: 'a;
+        type Container = Vecs<T::Container>;
+    }
+
+    impl<T: Columnar> Columnar for VecDeque<T> {
+        type Ref<'a> = Slice<<T::Container as crate::Container<T>>::Borrowed<'a>> where T: 'a;
+        fn copy_from<'a>(&mut self, other: Self::Ref<'a>) {
+            self.truncate(other.len());
+            let mut other_iter = other.into_iter();
+            for (s, o) in self.iter_mut().zip(&mut other_iter) {
+                T::copy_from(s, o);
+            }
+            for o in other_iter {
+                self.push_back(T::into_owned(o));
+            }
+        }
+        fn into_owned<'a>(other: Self::Ref<'a>) -> Self {
+            other.into_iter().map(|x| T::into_owned(x)).collect()
+        }
+        type Container = Vecs<T::Container>;
+    }
+
+    /// Heaps are recorded as unordered lists of their elements, in the order of `BinaryHeap::iter`.
+    impl<T: Columnar + Ord> Columnar for BinaryHeap<T> {
+        type Ref<'a> = Slice<<T::Container as crate::Container<T>>::Borrowed<'a>> where T: 'a;
+        fn into_owned<'a>(other: Self::Ref<'a>) -> Self {
+            other.into_iter().map(|x| T::into_owned(x)).collect()
+        }
+        type Container = Vecs<T::Container>;
+    }
+
+    impl<T: Columnar<Container = TC>, BC: crate::Container<u64>, TC: crate::Container<T>> crate::Container<[T]> for Vecs<TC, BC> {
+        type Borrowed<'a> = Vecs<TC::Borrowed<'a>, BC::Borrowed<'a>> where BC: 'a, TC: 'a, T: 'a;
+        fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
+            Vecs {
+                bounds: self.bounds.borrow(),
+                values: self.values.borrow(),
+            }
+        }
+    }
+
     impl<T: Columnar<Container = TC>, BC: crate::Container<u64>, TC: crate::Container<T>> crate::Container<Vec<T>> for Vecs<TC, BC> {
         type Borrowed<'a> = Vecs<TC::Borrowed<'a>, BC::Borrowed<'a>> where BC: 'a, TC: 'a, T: 'a;
         fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
@@ -1170,114 +3227,654 @@ pub mod vector {
                 values: self.values.borrow(),
             }
         }
-    }
+    }
+
+    impl<T: Columnar<Container = TC>, BC: crate::Container<u64>, TC: crate::Container<T>, const N: usize> crate::Container<[T; N]> for Vecs<TC, BC> {
+        type Borrowed<'a> = Vecs<TC::Borrowed<'a>, BC::Borrowed<'a>> where BC: 'a, TC: 'a, T: 'a;
+        fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
+            Vecs {
+                bounds: self.bounds.borrow(),
+                values: self.values.borrow(),
+            }
+        }
+    }
+
+    impl<T: Columnar<Container = TC>, BC: crate::Container<u64>, TC: crate::Container<T>> crate::Container<VecDeque<T>> for Vecs<TC, BC> {
+        type Borrowed<'a> = Vecs<TC::Borrowed<'a>, BC::Borrowed<'a>> where BC: 'a, TC: 'a, T: 'a;
+        fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
+            Vecs {
+                bounds: self.bounds.borrow(),
+                values: self.values.borrow(),
+            }
+        }
+    }
+
+    impl<T: Columnar<Container = TC> + Ord, BC: crate::Container<u64>, TC: crate::Container<T>> crate::Container<BinaryHeap<T>> for Vecs<TC, BC> {
+        type Borrowed<'a> = Vecs<TC::Borrowed<'a>, BC::Borrowed<'a>> where BC: 'a, TC: 'a, T: 'a;
+        fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
+            Vecs {
+                bounds: self.bounds.borrow(),
+                values: self.values.borrow(),
+            }
+        }
+    }
+
+    impl<'a, TC: crate::AsBytes<'a>, BC: crate::AsBytes<'a>> crate::AsBytes<'a> for Vecs<TC, BC> {
+        fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
+            self.bounds.as_bytes().chain(self.values.as_bytes())
+        }
+    }
+    impl<'a, TC: crate::FromBytes<'a>, BC: crate::FromBytes<'a>> crate::FromBytes<'a> for Vecs<TC, BC> {
+        fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self {
+            Self {
+                bounds: crate::FromBytes::from_bytes(bytes),
+                values: crate::FromBytes::from_bytes(bytes),
+            }
+        }
+    }
+
+    impl<TC: Len, BC: Len + IndexAs<u64>> Vecs<TC, BC> {
+        /// The bounds and values, for use elsewhere or to rebuild with `try_from_parts`.
+        pub fn into_parts(self) -> (BC, TC) { (self.bounds, self.values) }
+        /// Lists from their bounds and values, if the bounds are non-decreasing and end at the values' length.
+        pub fn try_from_parts(bounds: BC, values: TC) -> Result<Self, crate::InvalidParts> {
+            crate::common::validate_bounds(&bounds, values.len())?;
+            Ok(Self { bounds, values })
+        }
+    }
+
+    impl<TC: Len> Vecs<TC> {
+        pub fn push_iter<I>(&mut self, iter: I) where I: IntoIterator, TC: Push<I::Item> {
+            self.values.extend(iter);
+            self.bounds.push(self.values.len() as u64);
+        }
+        /// Appends each of `lists`, as if by pushing them in order.
+        ///
+        /// The lists are visited more than once, which is why their iterator must be `Clone`.
+        /// The bounds are reserved and written in one pass, and the values are extended by a
+        /// single iterator that reports its exact length, which lets containers like `Vec`
+        /// reserve once for all values rather than growing repeatedly.
+        pub fn extend_from_lists<'a, T: 'a, I>(&mut self, lists: I) where I: IntoIterator<Item=&'a [T]>, I::IntoIter: Clone, TC: Push<&'a T> {
+            let lists = lists.into_iter();
+            let (count, total) = lists.clone().fold((0, 0), |(count, total), list| (count + 1, total + list.len()));
+            let mut upper = self.values.len() as u64;
+            self.bounds.reserve(count);
+            Extend::extend(&mut self.bounds, lists.clone().map(|list| { upper += list.len() as u64; upper }));
+            self.values.extend(Counted { iter: lists.flatten(), remaining: total });
+        }
+    }
+
+    /// An iterator whose exact length is known in advance, to report in `size_hint`.
+    struct Counted<I> {
+        iter: I,
+        remaining: usize,
+    }
+    impl<I: Iterator> Iterator for Counted<I> {
+        type Item = I::Item;
+        #[inline(always)] fn next(&mut self) -> Option<I::Item> {
+            self.remaining = self.remaining.saturating_sub(1);
+            self.iter.next()
+        }
+        #[inline(always)] fn size_hint(&self) -> (usize, Option<usize>) { (self.remaining, Some(self.remaining)) }
+    }
+
+    impl<TC, BC: Len> Len for Vecs<TC, BC> {
+        #[inline(always)] fn len(&self) -> usize { self.bounds.len() }
+    }
+
+    impl<TC: Copy, BC: Len+IndexAs<u64>> Index for Vecs<TC, BC> {
+        type Ref = Slice<TC>;
+        #[inline(always)]
+        fn get(&self, index: usize) -> Self::Ref {
+            let lower = if index == 0 { 0 } else { self.bounds.index_as(index - 1) };
+            let upper = self.bounds.index_as(index);
+            Slice::new(lower, upper, self.values)
+        }
+    }
+    impl<'a, TC, BC: Len+IndexAs<u64>> Index for &'a Vecs<TC, BC> {
+        type Ref = Slice<&'a TC>;
+        #[inline(always)]
+        fn get(&self, index: usize) -> Self::Ref {
+            let lower = if index == 0 { 0 } else { self.bounds.index_as(index - 1) };
+            let upper = self.bounds.index_as(index);
+            Slice::new(lower, upper, &self.values)
+        }
+    }
+    impl<TC, BC: Len+IndexAs<u64>> IndexMut for Vecs<TC, BC> {
+        type IndexMut<'a> = Slice<&'a mut TC> where TC: 'a, BC: 'a;
+
+        #[inline(always)]
+        fn get_mut(&mut self, index: usize) -> Self::IndexMut<'_> {
+            let lower = if index == 0 { 0 } else { self.bounds.index_as(index - 1) };
+            let upper = self.bounds.index_as(index);
+            Slice::new(lower, upper, &mut self.values)
+        }
+    }
+
+    impl<TC: Push<TC2::Ref> + Len, TC2: Index> Push<Slice<TC2>> for Vecs<TC> {
+        fn push(&mut self, item: Slice<TC2>) {
+            self.values.extend(item.into_iter());
+            self.bounds.push(self.values.len() as u64);
+        }
+    }
+    impl<T, TC: Push<T> + Len> Push<Vec<T>> for Vecs<TC> {
+        fn push(&mut self, item: Vec<T>) {
+            self.push_iter(item);
+        }
+    }
+    impl<'a, T, TC: Push<&'a T> + Len> Push<&'a Vec<T>> for Vecs<TC> {
+        fn push(&mut self, item: &'a Vec<T>) {
+            self.push(&item[..]);
+        }
+        fn extend(&mut self, iter: impl IntoIterator<Item=&'a Vec<T>>) {
+            let lists: Vec<&'a [T]> = iter.into_iter().map(|list| &list[..]).collect();
+            self.extend_from_lists(lists);
+        }
+    }
+    impl<'a, T, TC: Push<&'a T> + Len, const N: usize> Push<&'a [T; N]> for Vecs<TC> {
+        fn push(&mut self, item: &'a [T; N]) {
+            self.push(&item[..]);
+        }
+    }
+    impl<'a, T, TC: Push<&'a T> + Len> Push<&'a [T]> for Vecs<TC> {
+        fn push(&mut self, item: &'a [T]) {
+            self.values.extend(item.iter());
+            self.bounds.push(self.values.len() as u64);
+        }
+    }
+    impl<'a, T, TC: Push<&'a T> + Len> Push<&'a VecDeque<T>> for Vecs<TC> {
+        fn push(&mut self, item: &'a VecDeque<T>) {
+            self.push_iter(item.iter());
+        }
+    }
+    impl<'a, T, TC: Push<&'a T> + Len> Push<&'a BinaryHeap<T>> for Vecs<TC> {
+        fn push(&mut self, item: &'a BinaryHeap<T>) {
+            self.push_iter(item.iter());
+        }
+    }
+    impl<TC: Clear> Clear for Vecs<TC> {
+        fn clear(&mut self) {
+            self.bounds.clear();
+            self.values.clear();
+        }
+    }
+
+    impl<TC: Permute + Len> Permute for Vecs<TC> {
+        fn permute(&mut self, perm: &[usize]) {
+            assert_eq!(perm.len(), self.len());
+            // Each list moves as a contiguous run of values, which we spell out for `self.values`.
+            let mut inner = Vec::with_capacity(self.values.len());
+            let mut bounds = Vec::with_capacity(self.bounds.len());
+            for index in perm {
+                let lower = if *index == 0 { 0 } else { self.bounds[*index - 1] as usize };
+                let upper = self.bounds[*index] as usize;
+                Extend::extend(&mut inner, lower .. upper);
+                bounds.push(inner.len() as u64);
+            }
+            self.values.permute(&inner);
+            self.bounds = bounds;
+        }
+    }
+
+    impl<TC: ExtendFromRange + Len> ExtendFromRange for Vecs<TC> {
+        fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+            if range.is_empty() { return; }
+            let lower = if range.start == 0 { 0 } else { other.bounds[range.start - 1] };
+            let upper = other.bounds[range.end - 1];
+            // Rebase the bounds of `other` to follow the existing values of `self`.
+            let shift = self.values.len() as u64;
+            Extend::extend(&mut self.bounds, other.bounds[range].iter().map(|bound| bound - lower + shift));
+            self.values.extend_from_range(&other.values, lower as usize .. upper as usize);
+        }
+    }
+
+    impl<TC: HeapSize, BC: HeapSize> HeapSize for Vecs<TC, BC> {
+        fn heap_size(&self) -> (usize, usize) {
+            let (l0, c0) = self.bounds.heap_size();
+            let (l1, c1) = self.values.heap_size();
+            (l0 + l1, c0 + c1)
+        }
+    }
+
+    pub use spans::Spans;
+    /// An alternate layout for the bounds of `Vecs`, recording each list's start and length.
+    ///
+    /// The default bounds of `Vecs` are cumulative offsets: list `i` ends where list `i+1`
+    /// begins, which is compact but requires lists to be laid out in order. Using `Spans`,
+    /// as in `Vecs<TC, Spans>`, costs one more word per list, and in exchange lists are free to
+    /// appear anywhere in the values. Rows can be removed or reordered without moving values,
+    /// and ranges of rows are copied by rebasing their starts.
+    pub mod spans {
+
+        use crate::{Clear, Len, IndexMut, Index, IndexAs, Push, HeapSize, Slice, ExtendFromRange};
+        use super::Vecs;
+
+        /// The start and length of each list in a `Vecs`.
+        #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+        pub struct Spans<C = Vec<u64>> {
+            /// The offset of the first value of each list.
+            pub starts: C,
+            /// The number of values in each list.
+            pub lengths: C,
+        }
+
+        impl<C: IndexAs<u64>> Spans<C> {
+            /// The lower and upper bounds of list `index` in the values.
+            #[inline(always)] pub fn bounds(&self, index: usize) -> (usize, usize) {
+                let start = self.starts.index_as(index);
+                (start as usize, (start + self.lengths.index_as(index)) as usize)
+            }
+        }
+        impl Spans {
+            /// A borrowed form of the spans.
+            pub fn borrow(&self) -> Spans<&[u64]> {
+                Spans { starts: &self.starts[..], lengths: &self.lengths[..] }
+            }
+        }
+
+        impl<C: Len> Len for Spans<C> {
+            #[inline(always)] fn len(&self) -> usize { self.starts.len() }
+        }
+        impl Push<(u64, u64)> for Spans {
+            #[inline(always)] fn push(&mut self, (start, length): (u64, u64)) {
+                self.starts.push(start);
+                self.lengths.push(length);
+            }
+        }
+        impl Clear for Spans {
+            #[inline(always)] fn clear(&mut self) {
+                self.starts.clear();
+                self.lengths.clear();
+            }
+        }
+        impl<C: HeapSize> HeapSize for Spans<C> {
+            fn heap_size(&self) -> (usize, usize) {
+                let (l0, c0) = self.starts.heap_size();
+                let (l1, c1) = self.lengths.heap_size();
+                (l0 + l1, c0 + c1)
+            }
+        }
+        impl<'a, C: crate::AsBytes<'a>> crate::AsBytes<'a> for Spans<C> {
+            fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
+                self.starts.as_bytes().chain(self.lengths.as_bytes())
+            }
+        }
+        impl<'a, C: crate::FromBytes<'a>> crate::FromBytes<'a> for Spans<C> {
+            fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self {
+                Self {
+                    starts: crate::FromBytes::from_bytes(bytes),
+                    lengths: crate::FromBytes::from_bytes(bytes),
+                }
+            }
+        }
+
+        impl<TC: Copy, C: IndexAs<u64>> Index for Vecs<TC, Spans<C>> {
+            type Ref = Slice<TC>;
+            #[inline(always)]
+            fn get(&self, index: usize) -> Self::Ref {
+                let (lower, upper) = self.bounds.bounds(index);
+                Slice::new(lower as u64, upper as u64, self.values)
+            }
+        }
+        impl<'a, TC, C: IndexAs<u64>> Index for &'a Vecs<TC, Spans<C>> {
+            type Ref = Slice<&'a TC>;
+            #[inline(always)]
+            fn get(&self, index: usize) -> Self::Ref {
+                let (lower, upper) = self.bounds.bounds(index);
+                Slice::new(lower as u64, upper as u64, &self.values)
+            }
+        }
+        impl<TC, C: IndexAs<u64>> IndexMut for Vecs<TC, Spans<C>> {
+            type IndexMut<'a> = Slice<&'a mut TC> where TC: 'a, C: 'a;
+            #[inline(always)]
+            fn get_mut(&mut self, index: usize) -> Self::IndexMut<'_> {
+                let (lower, upper) = self.bounds.bounds(index);
+                Slice::new(lower as u64, upper as u64, &mut self.values)
+            }
+        }
+
+        impl<TC: Len> Vecs<TC, Spans> {
+            /// A borrowed form of the container.
+            pub fn borrow<'a, T: crate::Columnar<Container = TC>>(&'a self) -> Vecs<TC::Borrowed<'a>, Spans<&'a [u64]>> where TC: crate::Container<T> {
+                Vecs { bounds: self.bounds.borrow(), values: self.values.borrow() }
+            }
+            /// Appends a list of the items of `iter`.
+            pub fn push_iter<I>(&mut self, iter: I) where I: IntoIterator, TC: Push<I::Item> {
+                let start = self.values.len() as u64;
+                self.values.extend(iter);
+                self.bounds.push((start, self.values.len() as u64 - start));
+            }
+            /// Removes list `index`, replacing it with the last list.
+            ///
+            /// This moves no values, and the values of the removed list remain in place
+            /// until the container is cleared.
+            pub fn swap_remove(&mut self, index: usize) {
+                self.bounds.starts.swap_remove(index);
+                self.bounds.lengths.swap_remove(index);
+            }
+        }
+
+        impl<TC: Push<TC2::Ref> + Len, TC2: Index> Push<Slice<TC2>> for Vecs<TC, Spans> {
+            fn push(&mut self, item: Slice<TC2>) { self.push_iter(item.into_iter()) }
+        }
+        impl<T, TC: Push<T> + Len> Push<Vec<T>> for Vecs<TC, Spans> {
+            fn push(&mut self, item: Vec<T>) { self.push_iter(item) }
+        }
+        impl<'a, T, TC: Push<&'a T> + Len> Push<&'a Vec<T>> for Vecs<TC, Spans> {
+            fn push(&mut self, item: &'a Vec<T>) { self.push_iter(<[T]>::iter(item)) }
+        }
+        impl<'a, T, TC: Push<&'a T> + Len> Push<&'a [T]> for Vecs<TC, Spans> {
+            fn push(&mut self, item: &'a [T]) { self.push_iter(item.iter()) }
+        }
+        impl<TC: Clear> Clear for Vecs<TC, Spans> {
+            fn clear(&mut self) {
+                self.bounds.clear();
+                self.values.clear();
+            }
+        }
+
+        impl<TC: ExtendFromRange + Len> ExtendFromRange for Vecs<TC, Spans> {
+            /// Copies the values of each list in `range`, in order, and rebases their starts.
+            ///
+            /// Lists need not be contiguous in `other`, and each is copied on its own.
+            fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+                for index in range {
+                    let (lower, upper) = other.bounds.bounds(index);
+                    let start = self.values.len() as u64;
+                    self.values.extend_from_range(&other.values, lower .. upper);
+                    self.bounds.push((start, (upper - lower) as u64));
+                }
+            }
+        }
+
+        #[cfg(test)]
+        mod test {
+
+            use crate::{ExtendFromRange, Index, Len, Push};
+            use super::{Spans, Vecs};
+
+            #[test]
+            fn spans() {
+                let mut column: Vecs<Vec<u64>, Spans> = Default::default();
+                for i in 0 .. 10u64 {
+                    column.push(&(0 .. i).collect::<Vec<_>>());
+                }
+                column.swap_remove(2);
+                assert_eq!(column.len(), 9);
+                assert_eq!((&column).get(2).into_iter().copied().collect::<Vec<_>>(), (0 .. 9).collect::<Vec<_>>());
+
+                let mut other: Vecs<Vec<u64>, Spans> = Default::default();
+                other.extend_from_range(&column, 1 .. 4);
+                let borrowed = other.borrow::<u64>();
+                assert_eq!(borrowed.len(), 3);
+                assert_eq!(borrowed.get(1).into_iter().copied().collect::<Vec<_>>(), (0 .. 9).collect::<Vec<_>>());
+                assert_eq!(borrowed.get(2).len(), 3);
+                assert_eq!(other.values.len(), 1 + 9 + 3);
+            }
+        }
+    }
+
+    pub use strided::Strided;
+    /// An alternate layout for the bounds of `Vecs`, recording a single length for lists that share it.
+    ///
+    /// Columns of lists that all have the same length, like embeddings or fixed-size tuples stored
+    /// as `Vec`, need no per-list bounds: list `i` ends at `(i+1) * stride`. `Strided` detects this
+    /// as lists are pushed, recording the length of the first list as the stride and counting lists
+    /// that match it. The first list of a different length, and all lists after it, are recorded as
+    /// cumulative bounds, so that a column of uniform lists costs two words rather than one per list,
+    /// and other columns remain correct. This is the layout of Arrow's `FixedSizeList`, arrived at
+    /// from the data rather than declared.
+    pub mod strided {
+
+        use crate::{Clear, Len, Index, IndexAs, Push, HeapSize, Slice, ExtendFromRange};
+        use crate::common::index::CopyAs;
+        use super::Vecs;
+
+        /// Non-decreasing `u64` bounds, of which a leading run of equal strides is recorded once.
+        #[derive(Debug, Default, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+        pub struct Strided<BC = Vec<u64>, WC = u64> {
+            /// The bounds following the leading run, once a list of a different length appears.
+            pub bounds: BC,
+            /// The length of each list in the leading run.
+            pub stride: WC,
+            /// The number of lists in the leading run.
+            pub uniform: WC,
+        }
+
+        impl<BC: Len, WC: Copy + CopyAs<u64>> Strided<BC, WC> {
+            /// The length shared by all lists, if they share one.
+            pub fn fixed(&self) -> Option<u64> {
+                if self.bounds.is_empty() { Some(self.stride.copy_as()) } else { None }
+            }
+        }
+        impl Strided {
+            /// A borrowed form of the bounds.
+            pub fn borrow(&self) -> Strided<&[u64], &u64> {
+                Strided { bounds: &self.bounds[..], stride: &self.stride, uniform: &self.uniform }
+            }
+        }
+
+        impl<BC: Len, WC: Copy + CopyAs<u64>> Len for Strided<BC, WC> {
+            #[inline(always)] fn len(&self) -> usize { self.uniform.copy_as() as usize + self.bounds.len() }
+        }
+        impl<BC: IndexAs<u64>, WC: Copy + CopyAs<u64>> Index for Strided<BC, WC> {
+            type Ref = u64;
+            #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+                let uniform = self.uniform.copy_as() as usize;
+                if index < uniform { (index as u64 + 1) * self.stride.copy_as() } else { self.bounds.index_as(index - uniform) }
+            }
+        }
+        impl<BC: IndexAs<u64>, WC: Copy + CopyAs<u64>> Index for &Strided<BC, WC> {
+            type Ref = u64;
+            #[inline(always)] fn get(&self, index: usize) -> Self::Ref { (*self).get(index) }
+        }
+
+        impl Push<u64> for Strided {
+            fn push(&mut self, item: u64) {
+                if self.bounds.is_empty() {
+                    if self.uniform == 0 { self.stride = item; }
+                    if item == (self.uniform + 1) * self.stride {
+                        self.uniform += 1;
+                        return;
+                    }
+                }
+                self.bounds.push(item);
+            }
+        }
+        impl Push<&u64> for Strided {
+            #[inline(always)] fn push(&mut self, item: &u64) { self.push(*item) }
+        }
+        impl Clear for Strided {
+            fn clear(&mut self) {
+                self.bounds.clear();
+                self.stride = 0;
+                self.uniform = 0;
+            }
+        }
+        impl<BC: HeapSize, WC> HeapSize for Strided<BC, WC> {
+            fn heap_size(&self) -> (usize, usize) { self.bounds.heap_size() }
+        }
+        impl<'a, BC: crate::AsBytes<'a>> crate::AsBytes<'a> for Strided<BC, &'a u64> {
+            fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
+                self.bounds.as_bytes()
+                    .chain(std::iter::once((8, bytemuck::cast_slice(std::slice::from_ref(self.stride)))))
+                    .chain(std::iter::once((8, bytemuck::cast_slice(std::slice::from_ref(self.uniform)))))
+            }
+        }
+        impl<'a, BC: crate::FromBytes<'a>> crate::FromBytes<'a> for Strided<BC, &'a u64> {
+            fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self {
+                let bounds = crate::FromBytes::from_bytes(bytes);
+                let stride = &bytemuck::try_cast_slice(bytes.next().unwrap()).unwrap()[0];
+                let uniform = &bytemuck::try_cast_slice(bytes.next().unwrap()).unwrap()[0];
+                Self { bounds, stride, uniform }
+            }
+        }
 
-    impl<T: Columnar<Container = TC>, BC: crate::Container<u64>, TC: crate::Container<T>, const N: usize> crate::Container<[T; N]> for Vecs<TC, BC> {
-        type Borrowed<'a> = Vecs<TC::Borrowed<'a>, BC::Borrowed<'a>> where BC: 'a, TC: 'a, T: 'a;
-        fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
-            Vecs {
-                bounds: self.bounds.borrow(),
-                values: self.values.borrow(),
+        impl<TC: Len> Vecs<TC, Strided> {
+            /// A borrowed form of the container.
+            pub fn borrow<'a, T: crate::Columnar<Container = TC>>(&'a self) -> Vecs<TC::Borrowed<'a>, Strided<&'a [u64], &'a u64>> where TC: crate::Container<T> {
+                Vecs { bounds: self.bounds.borrow(), values: self.values.borrow() }
+            }
+            /// Appends a list of the items of `iter`.
+            pub fn push_iter<I>(&mut self, iter: I) where I: IntoIterator, TC: Push<I::Item> {
+                self.values.extend(iter);
+                self.bounds.push(self.values.len() as u64);
             }
         }
-    }
 
-    impl<'a, TC: crate::AsBytes<'a>, BC: crate::AsBytes<'a>> crate::AsBytes<'a> for Vecs<TC, BC> {
-        fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
-            self.bounds.as_bytes().chain(self.values.as_bytes())
+        impl<TC: Push<TC2::Ref> + Len, TC2: Index> Push<Slice<TC2>> for Vecs<TC, Strided> {
+            fn push(&mut self, item: Slice<TC2>) { self.push_iter(item.into_iter()) }
         }
-    }
-    impl<'a, TC: crate::FromBytes<'a>, BC: crate::FromBytes<'a>> crate::FromBytes<'a> for Vecs<TC, BC> {
-        fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self {
-            Self {
-                bounds: crate::FromBytes::from_bytes(bytes),
-                values: crate::FromBytes::from_bytes(bytes),
+        impl<T, TC: Push<T> + Len> Push<Vec<T>> for Vecs<TC, Strided> {
+            fn push(&mut self, item: Vec<T>) { self.push_iter(item) }
+        }
+        impl<'a, T, TC: Push<&'a T> + Len> Push<&'a Vec<T>> for Vecs<TC, Strided> {
+            fn push(&mut self, item: &'a Vec<T>) { self.push_iter(<[T]>::iter(item)) }
+        }
+        impl<'a, T, TC: Push<&'a T> + Len> Push<&'a [T]> for Vecs<TC, Strided> {
+            fn push(&mut self, item: &'a [T]) { self.push_iter(item.iter()) }
+        }
+        impl<TC: Clear> Clear for Vecs<TC, Strided> {
+            fn clear(&mut self) {
+                self.bounds.clear();
+                self.values.clear();
             }
         }
-    }
 
-    impl<TC: Len> Vecs<TC> {
-        pub fn push_iter<I>(&mut self, iter: I) where I: IntoIterator, TC: Push<I::Item> {
-            self.values.extend(iter);
-            self.bounds.push(self.values.len() as u64);
+        impl<TC: ExtendFromRange + Len> ExtendFromRange for Vecs<TC, Strided> {
+            fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+                if range.is_empty() { return; }
+                let lower = if range.start == 0 { 0 } else { other.bounds.get(range.start - 1) };
+                let upper = other.bounds.get(range.end - 1);
+                let shift = self.values.len() as u64;
+                for index in range {
+                    self.bounds.push(other.bounds.get(index) - lower + shift);
+                }
+                self.values.extend_from_range(&other.values, lower as usize .. upper as usize);
+            }
         }
-    }
 
-    impl<TC, BC: Len> Len for Vecs<TC, BC> {
-        #[inline(always)] fn len(&self) -> usize { self.bounds.len() }
-    }
+        #[cfg(test)]
+        mod test {
 
-    impl<TC: Copy, BC: Len+IndexAs<u64>> Index for Vecs<TC, BC> {
-        type Ref = Slice<TC>;
-        #[inline(always)]
-        fn get(&self, index: usize) -> Self::Ref {
-            let lower = if index == 0 { 0 } else { self.bounds.index_as(index - 1) };
-            let upper = self.bounds.index_as(index);
-            Slice::new(lower, upper, self.values)
-        }
-    }
-    impl<'a, TC, BC: Len+IndexAs<u64>> Index for &'a Vecs<TC, BC> {
-        type Ref = Slice<&'a TC>;
-        #[inline(always)]
-        fn get(&self, index: usize) -> Self::Ref {
-            let lower = if index == 0 { 0 } else { self.bounds.index_as(index - 1) };
-            let upper = self.bounds.index_as(index);
-            Slice::new(lower, upper, &self.values)
-        }
-    }
-    impl<TC, BC: Len+IndexAs<u64>> IndexMut for Vecs<TC, BC> {
-        type IndexMut<'a> = Slice<&'a mut TC> where TC: 'a, BC: 'a;
+            use crate::{AsBytes, ExtendFromRange, FromBytes, HeapSize, Index, Len, Push};
+            use super::{Strided, Vecs};
 
-        #[inline(always)]
-        fn get_mut(&mut self, index: usize) -> Self::IndexMut<'_> {
-            let lower = if index == 0 { 0 } else { self.bounds.index_as(index - 1) };
-            let upper = self.bounds.index_as(index);
-            Slice::new(lower, upper, &mut self.values)
+            #[test]
+            fn strided() {
+                // Lists of one length are recorded without bounds.
+                let mut column: Vecs<Vec<f32>, Strided> = Default::default();
+                for i in 0 .. 100 {
+                    column.push(&[i as f32; 4][..]);
+                }
+                assert_eq!(column.bounds.fixed(), Some(4));
+                assert_eq!(column.heap_size().0, column.values.heap_size().0);
+                assert_eq!((&column).get(10).into_iter().copied().collect::<Vec<_>>(), [10.0; 4]);
+
+                // A list of another length starts explicit bounds, and all lists remain readable.
+                column.push(&[1.0][..]);
+                column.push(&[2.0; 4][..]);
+                assert_eq!(column.bounds.fixed(), None);
+                assert_eq!(column.bounds.bounds, [401, 405]);
+                assert_eq!(column.len(), 102);
+                assert_eq!((&column).get(100).len(), 1);
+                assert_eq!((&column).get(101).len(), 4);
+
+                let mut other: Vecs<Vec<f32>, Strided> = Default::default();
+                other.extend_from_range(&column, 98 .. 102);
+                assert_eq!(other.bounds.uniform, 2);
+                assert_eq!((0 .. 4).map(|i| (&other).get(i).len()).collect::<Vec<_>>(), [4, 4, 1, 4]);
+
+                let borrowed = column.borrow::<f32>();
+                let rebuilt = Vecs::<&[f32], Strided<&[u64], &u64>>::from_bytes(&mut borrowed.as_bytes().map(|(_, bytes)| bytes));
+                assert_eq!(rebuilt, borrowed);
+            }
         }
     }
 
-    impl<TC: Push<TC2::Ref> + Len, TC2: Index> Push<Slice<TC2>> for Vecs<TC> {
-        fn push(&mut self, item: Slice<TC2>) {
-            self.values.extend(item.into_iter());
-            self.bounds.push(self.values.len() as u64);
-        }
-    }
-    impl<'a, T, TC: Push<&'a T> + Len> Push<&'a Vec<T>> for Vecs<TC> {
-        fn push(&mut self, item: &'a Vec<T>) {
-            self.push(&item[..]);
-        }
-    }
-    impl<'a, T, TC: Push<&'a T> + Len, const N: usize> Push<&'a [T; N]> for Vecs<TC> {
-        fn push(&mut self, item: &'a [T; N]) {
-            self.push(&item[..]);
+    #[cfg(test)]
+    mod test {
+
+        use crate::{Columnar, Container, Len, Permute};
+
+        #[test]
+        fn permute() {
+            let lists: Vec<Vec<u64>> = (0 .. 20).map(|i| (0 .. i % 4).collect()).collect();
+            let mut column = Columnar::as_columns(lists.iter());
+            let perm: Vec<usize> = (0 .. 20).map(|i| (i * 7) % 20).collect();
+            column.permute(&perm);
+            assert_eq!(column.len(), 20);
+            for (index, source) in perm.iter().enumerate() {
+                assert_eq!(Container::<Vec<u64>>::materialize(&column, index), lists[*source]);
+            }
         }
-    }
-    impl<'a, T, TC: Push<&'a T> + Len> Push<&'a [T]> for Vecs<TC> {
-        fn push(&mut self, item: &'a [T]) {
-            self.values.extend(item.iter());
-            self.bounds.push(self.values.len() as u64);
+
+        #[test]
+        fn push_by_value() {
+
+            use crate::{Vecs, Push, Index, Len};
+
+            // Boxed closures are not `Clone`, and can only be moved in.
+            type Handle = Box<dyn Fn(u64) -> u64>;
+            let mut column: Vecs<(Vec<u64>, Vec<Handle>)> = Default::default();
+            for i in 0 .. 10u64 {
+                let items: Vec<(u64, Handle)> = (0 .. i).map(|j| (j, Box::new(move |x| x * i + j) as Handle)).collect();
+                column.push(items);
+            }
+            assert_eq!(column.len(), 10);
+            for i in 0 .. 10u64 {
+                for (j, (k, f)) in (&column).get(i as usize).into_iter().enumerate() {
+                    assert_eq!(*k, j as u64);
+                    assert_eq!(f(2), 2 * i + j as u64);
+                }
+            }
         }
-    }
-    impl<TC: Clear> Clear for Vecs<TC> {
-        fn clear(&mut self) {
-            self.bounds.clear();
-            self.values.clear();
+
+        #[test]
+        fn extend_from_lists() {
+
+            use crate::{Push, Vecs};
+
+            let lists: Vec<Vec<u64>> = (0 .. 100).map(|i| (0 .. i).collect()).collect();
+            let mut pushed: Vecs<Vec<u64>> = Default::default();
+            for list in lists.iter() { pushed.push(list); }
+            let mut extended: Vecs<Vec<u64>> = Default::default();
+            Push::extend(&mut extended, lists.iter());
+            assert_eq!(pushed, extended);
+            assert_eq!(extended.values.capacity(), extended.values.len());
         }
-    }
 
-    impl<TC: HeapSize, BC: HeapSize> HeapSize for Vecs<TC, BC> {
-        fn heap_size(&self) -> (usize, usize) {
-            let (l0, c0) = self.bounds.heap_size();
-            let (l1, c1) = self.values.heap_size();
-            (l0 + l1, c0 + c1)
+        #[test]
+        fn unsized_rows() {
+
+            use crate::{Columnar, Container, Index, Len};
+
+            let values: Vec<u64> = (0 .. 10).collect();
+            let column = <[u64] as Columnar>::as_columns(values.chunks(3));
+            assert_eq!(column.len(), 4);
+            let borrowed = Container::<[u64]>::borrow(&column);
+            assert_eq!(borrowed.get(3).into_iter().collect::<Vec<_>>(), vec![&9]);
         }
     }
 }
 
+pub use tuple::CopyFromFields;
 #[allow(non_snake_case)]
 pub mod tuple {
 
-    use super::{Clear, Columnar, Len, IndexMut, Index, Push, HeapSize};
+    use super::{Clear, Columnar, Len, IndexMut, Index, Push, HeapSize, Permute, ExtendFromRange};
+
+    /// Appends rows presented as one sequence per field, rather than as a sequence of tuples.
+    ///
+    /// Each field container is extended from its own sequence, with no intermediate tuples.
+    /// Tuple containers already accept tuples mixing owned and borrowed fields, like `(S, &T)`,
+    /// through `Push`; this trait serves fields that arrive separately, like a slice per field.
+    pub trait CopyFromFields<F> {
+        /// Appends the rows whose fields are produced by the corresponding sequences in `fields`.
+        ///
+        /// Panics if the sequences have different lengths, after extending each field container.
+        fn copy_from_fields(&mut self, fields: F);
+    }
 
     // Implementations for tuple types.
     // These are all macro based, because the implementations are very similar.
@@ -1285,6 +3882,14 @@ pub mod tuple {
     macro_rules! tuple_impl {
         ( $($name:ident,$name2:ident)+) => (
 
+            impl<$($name: crate::IntoOwned),*> crate::IntoOwned for ($($name,)*) {
+                type Owned = ($($name::Owned,)*);
+                fn into_owned(self) -> Self::Owned {
+                    let ($($name,)*) = self;
+                    ($($name.into_owned(),)*)
+                }
+            }
+
             impl<$($name: Columnar),*> Columnar for ($($name,)*) {
                 type Ref<'a> = ($($name::Ref<'a>,)*) where $($name: 'a,)*;
                 fn copy_from<'a>(&mut self, other: Self::Ref<'a>) {
@@ -1334,6 +3939,19 @@ pub mod tuple {
                     $($name.clear();)*
                 }
             }
+            impl<$($name: Permute),*> Permute for ($($name,)*) {
+                fn permute(&mut self, perm: &[usize]) {
+                    let ($($name,)*) = self;
+                    $($name.permute(perm);)*
+                }
+            }
+            impl<$($name: ExtendFromRange),*> ExtendFromRange for ($($name,)*) {
+                fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+                    let ($($name,)*) = self;
+                    let ($($name2,)*) = other;
+                    $($name.extend_from_range($name2, range.clone());)*
+                }
+            }
             impl<$($name: HeapSize),*> HeapSize for ($($name,)*) {
                 fn heap_size(&self) -> (usize, usize) {
                     let ($($name,)*) = self;
@@ -1379,6 +3997,14 @@ pub mod tuple {
                     $($name.push($name2);)*
                 }
             }
+            impl<$($name2: IntoIterator, $name: Push<$name2::Item>),*> CopyFromFields<($($name2,)*)> for ($($name,)*) {
+                fn copy_from_fields(&mut self, fields: ($($name2,)*)) {
+                    let ($($name,)*) = self;
+                    let ($($name2,)*) = fields;
+                    let counts = [$({ let mut count = 0; $name.extend($name2.into_iter().inspect(|_| count += 1)); count },)*];
+                    assert!(counts.iter().all(|count| *count == counts[0]), "fields have different lengths: {:?}", counts);
+                }
+            }
         )
     }
 
@@ -1424,10 +4050,242 @@ pub mod tuple {
             assert_eq!(column.heap_size(), (8190, 11040));
 
         }
+
+        #[test]
+        fn mixed() {
+
+            use crate::{Columnar, Container, CopyFromFields, Index, Len, Push};
+
+            let names: Vec<String> = (0 .. 10).map(|i| i.to_string()).collect();
+            let mut column = <(u64, String) as Columnar>::Container::default();
+            column.push((&0, &names[0]));
+            column.push((1, &names[1]));
+            column.copy_from_fields((2 .. 10u64, &names[2..]));
+            assert_eq!(column.len(), 10);
+            let borrowed = Container::<(u64, String)>::borrow(&column);
+            for (index, name) in names.iter().enumerate() {
+                assert_eq!(borrowed.get(index), (&(index as u64), name.as_str()));
+            }
+        }
+
+        #[test]
+        #[should_panic]
+        fn mismatched_fields() {
+            use crate::{Columnar, CopyFromFields};
+            let mut column = <(u64, u8) as Columnar>::Container::default();
+            column.copy_from_fields((0 .. 3u64, 0 .. 2u8));
+        }
+
+        #[test]
+        fn into_owned() {
+
+            use crate::{Columnar, Container, Index, IntoOwned};
+
+            let rows: Vec<(u64, String, Vec<Option<u8>>)> = (0 .. 10).map(|i| (i, i.to_string(), vec![Some(i as u8), None])).collect();
+            let column = Columnar::as_columns(rows.iter());
+            let borrowed = Container::<(u64, String, Vec<Option<u8>>)>::borrow(&column);
+            for (index, row) in rows.iter().enumerate() {
+                assert_eq!(&borrowed.get(index).into_owned(), row);
+                assert_eq!(&Container::<(u64, String, Vec<Option<u8>>)>::materialize(&column, index), row);
+            }
+        }
+    }
+}
+
+pub use range::{Ranges, RangesInclusive};
+/// Containers for `Range<T>` and `RangeInclusive<T>`, as columns of starts and ends.
+///
+/// References are themselves ranges, of references, and so support `contains`.
+pub mod range {
+
+    use std::ops::{Range, RangeInclusive};
+    use crate::{Clear, Columnar, Len, Index, Push, HeapSize, Permute, ExtendFromRange};
+
+    /// A container for `Range<T>`, as separate columns of starts and ends.
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct Ranges<C> {
+        pub starts: C,
+        pub ends: C,
+    }
+
+    /// A container for `RangeInclusive<T>`, as separate columns of starts and ends.
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct RangesInclusive<C> {
+        pub starts: C,
+        pub ends: C,
+    }
+
+    impl<R: crate::IntoOwned> crate::IntoOwned for Range<R> {
+        type Owned = Range<R::Owned>;
+        fn into_owned(self) -> Self::Owned { self.start.into_owned() .. self.end.into_owned() }
+    }
+    impl<R: crate::IntoOwned> crate::IntoOwned for RangeInclusive<R> {
+        type Owned = RangeInclusive<R::Owned>;
+        fn into_owned(self) -> Self::Owned {
+            let (start, end) = self.into_inner();
+            start.into_owned() ..= end.into_owned()
+        }
+    }
+
+    impl<T: Columnar> Columnar for Range<T> {
+        type Ref<'a> = Range<T::Ref<'a>> where T: 'a;
+        fn copy_from<'a>(&mut self, other: Self::Ref<'a>) {
+            self.start.copy_from(other.start);
+            self.end.copy_from(other.end);
+        }
+        fn into_owned<'a>(other: Self::Ref<'a>) -> Self {
+            T::into_owned(other.start) .. T::into_owned(other.end)
+        }
+        type Container = Ranges<T::Container>;
+    }
+
+    impl<T: Columnar> Columnar for RangeInclusive<T> {
+        type Ref<'a> = RangeInclusive<T::Ref<'a>> where T: 'a;
+        fn into_owned<'a>(other: Self::Ref<'a>) -> Self {
+            let (start, end) = other.into_inner();
+            T::into_owned(start) ..= T::into_owned(end)
+        }
+        type Container = RangesInclusive<T::Container>;
+    }
+
+    impl<T: Columnar, TC: crate::Container<T>> crate::Container<Range<T>> for Ranges<TC> {
+        type Borrowed<'a> = Ranges<TC::Borrowed<'a>> where TC: 'a, T: 'a;
+        fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
+            Ranges { starts: self.starts.borrow(), ends: self.ends.borrow() }
+        }
+    }
+
+    impl<T: Columnar, TC: crate::Container<T>> crate::Container<RangeInclusive<T>> for RangesInclusive<TC> {
+        type Borrowed<'a> = RangesInclusive<TC::Borrowed<'a>> where TC: 'a, T: 'a;
+        fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
+            RangesInclusive { starts: self.starts.borrow(), ends: self.ends.borrow() }
+        }
+    }
+
+    impl<C: Index> Index for Ranges<C> {
+        type Ref = Range<C::Ref>;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+            self.starts.get(index) .. self.ends.get(index)
+        }
+    }
+    impl<'a, C> Index for &'a Ranges<C> where &'a C: Index {
+        type Ref = Range<<&'a C as Index>::Ref>;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+            (&self.starts).get(index) .. (&self.ends).get(index)
+        }
+    }
+    impl<C: Index> Index for RangesInclusive<C> {
+        type Ref = RangeInclusive<C::Ref>;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+            self.starts.get(index) ..= self.ends.get(index)
+        }
+    }
+    impl<'a, C> Index for &'a RangesInclusive<C> where &'a C: Index {
+        type Ref = RangeInclusive<<&'a C as Index>::Ref>;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+            (&self.starts).get(index) ..= (&self.ends).get(index)
+        }
+    }
+
+    impl<T, C: Push<T>> Push<Range<T>> for Ranges<C> {
+        fn push(&mut self, item: Range<T>) {
+            self.starts.push(item.start);
+            self.ends.push(item.end);
+        }
+    }
+    impl<'a, T, C: Push<&'a T>> Push<&'a Range<T>> for Ranges<C> {
+        fn push(&mut self, item: &'a Range<T>) {
+            self.starts.push(&item.start);
+            self.ends.push(&item.end);
+        }
+    }
+    impl<T, C: Push<T>> Push<RangeInclusive<T>> for RangesInclusive<C> {
+        fn push(&mut self, item: RangeInclusive<T>) {
+            let (start, end) = item.into_inner();
+            self.starts.push(start);
+            self.ends.push(end);
+        }
+    }
+    impl<'a, T, C: Push<&'a T>> Push<&'a RangeInclusive<T>> for RangesInclusive<C> {
+        fn push(&mut self, item: &'a RangeInclusive<T>) {
+            self.starts.push(item.start());
+            self.ends.push(item.end());
+        }
+    }
+
+    // The remaining implementations are identical for both containers.
+    macro_rules! implement_ranges {
+        ($($container:ident),*) => { $(
+            impl<'a, C: crate::AsBytes<'a>> crate::AsBytes<'a> for $container<C> {
+                fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
+                    self.starts.as_bytes().chain(self.ends.as_bytes())
+                }
+            }
+            impl<'a, C: crate::FromBytes<'a>> crate::FromBytes<'a> for $container<C> {
+                fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self {
+                    Self {
+                        starts: crate::FromBytes::from_bytes(bytes),
+                        ends: crate::FromBytes::from_bytes(bytes),
+                    }
+                }
+            }
+            impl<C: Len> Len for $container<C> {
+                #[inline(always)] fn len(&self) -> usize { self.starts.len() }
+            }
+            impl<C: Clear> Clear for $container<C> {
+                fn clear(&mut self) {
+                    self.starts.clear();
+                    self.ends.clear();
+                }
+            }
+            impl<C: Permute> Permute for $container<C> {
+                fn permute(&mut self, perm: &[usize]) {
+                    self.starts.permute(perm);
+                    self.ends.permute(perm);
+                }
+            }
+            impl<C: ExtendFromRange> ExtendFromRange for $container<C> {
+                fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+                    self.starts.extend_from_range(&other.starts, range.clone());
+                    self.ends.extend_from_range(&other.ends, range);
+                }
+            }
+            impl<C: HeapSize> HeapSize for $container<C> {
+                fn heap_size(&self) -> (usize, usize) {
+                    let (l0, c0) = self.starts.heap_size();
+                    let (l1, c1) = self.ends.heap_size();
+                    (l0 + l1, c0 + c1)
+                }
+            }
+        )* }
+    }
+    implement_ranges!(Ranges, RangesInclusive);
+
+    #[cfg(test)]
+    mod test {
+
+        use crate::{Columnar, Container, Index, Len};
+
+        #[test]
+        fn contains() {
+            let windows: Vec<_> = (0 .. 10u64).map(|i| (10 * i) .. (10 * i + 5)).collect();
+            let column = Columnar::as_columns(windows.iter());
+            let borrowed = Container::<std::ops::Range<u64>>::borrow(&column);
+            assert_eq!(borrowed.len(), 10);
+            assert!(borrowed.get(3).contains(&&32));
+            assert!(!borrowed.get(3).contains(&&35));
+            assert_eq!(std::ops::Range::<u64>::into_owned(borrowed.get(7)), 70 .. 75);
+
+            let names: Vec<_> = ["a" ..= "c", "d" ..= "f"].iter().map(|r| r.start().to_string() ..= r.end().to_string()).collect();
+            let column = Columnar::as_columns(names.iter());
+            let borrowed = Container::<std::ops::RangeInclusive<String>>::borrow(&column);
+            assert!(borrowed.get(1).contains(&"f"));
+            assert!(!borrowed.get(0).contains(&"d"));
+        }
     }
 }
 
-pub use sums::{rank_select::RankSelect, result::Results, option::Options};
+pub use sums::{rank_select::RankSelect, result::Results, option::{Options, DenseOptions, NullableVecs}};
 /// Containers for enumerations ("sum types") that store variants separately.
 ///
 /// The main work of these types is storing a discriminant and index efficiently,
@@ -1452,7 +4310,7 @@ pub mod sums {
         /// The design is to have `u64` running counts for each block of 1024 bits,
         /// which are roughly the size of a cache line. This is roughly 6% overhead,
         /// above the bits themselves, which seems pretty solid.
-        #[derive(Copy, Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
         pub struct RankSelect<CC = Vec<u64>, VC = Vec<u64>, WC = u64> {
             /// Counts of the number of cumulative set (true) bits, *after* each block of 1024 bits.
             pub counts: CC,
@@ -1510,33 +4368,32 @@ pub mod sums {
                 count += (intra_word & ((1 << bit) - 1)).count_ones() as usize;
                 count
             }
-            /// The index of the `rank`th set bit, should one exist.
+            /// The index of the set bit preceded by `rank` set bits, should one exist.
+            ///
+            /// The chunk containing the bit is found by binary search in `self.counts`, after
+            /// which at most sixteen words are scanned, and the bit located within its word.
             pub fn select(&self, rank: u64) -> Option<usize> {
-                let mut chunk = 0;
-                // Step one is to find the position in `counts` where we go from `rank` to `rank + 1`.
-                // The position we are looking for is within that chunk of bits.
-                // TODO: Binary search is likely better at many scales. Rust's binary search is .. not helpful with ties.
-                while chunk < self.counts.len() && self.counts.index_as(chunk) <= rank {
-                    chunk += 1;
-                }
-                let mut count = if chunk < self.counts.len() { self.counts.index_as(chunk) } else { 0 };
-                // Step two is to find the position within that chunk where the `rank`th bit is.
+                // Step one is to find the first chunk whose cumulative count exceeds `rank`.
+                let (mut lower, mut upper) = (0, self.counts.len());
+                while lower < upper {
+                    let middle = (lower + upper) / 2;
+                    if self.counts.index_as(middle) <= rank { lower = middle + 1; } else { upper = middle; }
+                }
+                let chunk = lower;
+                let mut count = if chunk > 0 { self.counts.index_as(chunk - 1) } else { 0 };
+                // Step two is to find the word within that chunk where the bit is.
                 let mut block = 16 * chunk;
-                while block < self.values.values.len() && count + (self.values.values.index_as(block).count_ones() as u64) <= rank {
-                    count += self.values.values.index_as(block).count_ones() as u64;
+                while block < self.values.values.len() {
+                    let ones = self.values.values.index_as(block).count_ones() as u64;
+                    if count + ones > rank { break; }
+                    count += ones;
                     block += 1;
                 }
-                // Step three is to search the last word for the location, or return `None` if we run out of bits.
-                let last_bits = if block == self.values.values.len() { self.values.last_bits.copy_as() as usize } else { 64 };
-                let last_word = if block == self.values.values.len() { self.values.last_word.copy_as() } else { self.values.values.index_as(block) };
-                for shift in 0 .. last_bits {
-                    if ((last_word >> shift) & 0x01 == 0x01) && count + 1 == rank {
-                        return Some(64 * block + shift);
-                    }
-                    count += (last_word >> shift) & 0x01;
-                }
-
-                None
+                // Step three is to search the word for the location, or return `None` if we run out of bits.
+                let mut word = if block == self.values.values.len() { self.values.last_word.copy_as() } else { self.values.values.index_as(block) };
+                if rank - count >= word.count_ones() as u64 { return None; }
+                for _ in 0 .. rank - count { word &= word - 1; }
+                Some(64 * block + word.trailing_zeros() as usize)
             }
         }
 
@@ -1576,15 +4433,36 @@ pub mod sums {
                 (l0 + l1, c0 + c1)
             }
         }
+
+        #[cfg(test)]
+        mod test {
+
+            use super::RankSelect;
+
+            #[test]
+            fn rank_select() {
+                let bits: Vec<bool> = (0 .. 5000).map(|i| i % 5 == 0 || (2000 .. 2100).contains(&i)).collect();
+                let mut store: RankSelect = Default::default();
+                for bit in bits.iter() { store.push(*bit); }
+                let ones: Vec<usize> = (0 .. bits.len()).filter(|i| bits[*i]).collect();
+                for (rank, index) in ones.iter().enumerate() {
+                    assert_eq!(store.rank(*index), rank);
+                    assert_eq!(store.select(rank as u64), Some(*index));
+                    assert_eq!(store.borrow().select(rank as u64), Some(*index));
+                }
+                assert_eq!(store.select(ones.len() as u64), None);
+                assert_eq!(store.rank(bits.len()), ones.len());
+            }
+        }
     }
 
     pub mod result {
 
         use crate::common::index::CopyAs;
-        use crate::{Clear, Columnar, Len, IndexMut, Index, IndexAs, Push, HeapSize};
+        use crate::{Clear, Columnar, Len, IndexMut, Index, IndexAs, Push, HeapSize, Permute, ExtendFromRange};
         use crate::RankSelect;
 
-        #[derive(Copy, Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
         pub struct Results<SC, TC, CC=Vec<u64>, VC=Vec<u64>, WC=u64> {
             /// Bits set to `true` correspond to `Ok` variants.
             pub indexes: RankSelect<CC, VC, WC>,
@@ -1592,6 +4470,16 @@ pub mod sums {
             pub errs: TC,
         }
 
+        impl<S: crate::IntoOwned, T: crate::IntoOwned> crate::IntoOwned for Result<S, T> {
+            type Owned = Result<S::Owned, T::Owned>;
+            fn into_owned(self) -> Self::Owned {
+                match self {
+                    Ok(s) => Ok(s.into_owned()),
+                    Err(t) => Err(t.into_owned()),
+                }
+            }
+        }
+
         impl<S: Columnar, T: Columnar> Columnar for Result<S, T> {
             type Ref<'a> = Result<S::Ref<'a>, T::Ref<'a>> where S: 'a, T: 'a;
             fn copy_from<'a>(&mut self, other: Self::Ref<'a>) {
@@ -1724,6 +4612,42 @@ pub mod sums {
             }
         }
 
+        impl<SC: Permute, TC: Permute> Permute for Results<SC, TC> {
+            fn permute(&mut self, perm: &[usize]) {
+                assert_eq!(perm.len(), self.len());
+                let mut indexes = RankSelect::default();
+                let ok_count = self.indexes.rank(self.len());
+                let mut oks = Vec::with_capacity(ok_count);
+                let mut errs = Vec::with_capacity(self.len() - ok_count);
+                for index in perm {
+                    let rank = self.indexes.rank(*index);
+                    if self.indexes.get(*index) {
+                        indexes.push(true);
+                        oks.push(rank);
+                    } else {
+                        indexes.push(false);
+                        errs.push(*index - rank);
+                    }
+                }
+                self.indexes = indexes;
+                self.oks.permute(&oks);
+                self.errs.permute(&errs);
+            }
+        }
+
+        impl<SC: ExtendFromRange, TC: ExtendFromRange> ExtendFromRange for Results<SC, TC> {
+            fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+                assert!(range.start <= range.end && range.end <= other.len());
+                let lower = other.indexes.rank(range.start);
+                let upper = other.indexes.rank(range.end);
+                for index in range.clone() {
+                    self.indexes.push(other.indexes.get(index));
+                }
+                self.oks.extend_from_range(&other.oks, lower .. upper);
+                self.errs.extend_from_range(&other.errs, range.start - lower .. range.end - upper);
+            }
+        }
+
         impl<SC: HeapSize, TC: HeapSize> HeapSize for Results<SC, TC> {
             fn heap_size(&self) -> (usize, usize) {
                 let (l0, c0) = self.oks.heap_size();
@@ -1769,16 +4693,34 @@ pub mod sums {
                     assert_eq!(column.get(2*i+1), Err(i as u8));
                 }
             }
+
+            #[test]
+            fn permute() {
+                use crate::{Columnar, Index, Len, Permute};
+                let items: Vec<Result<u64, String>> = (0 .. 100).map(|i| if i % 3 == 0 { Err(format!("{}", i)) } else { Ok(i) }).collect();
+                let mut column = Columnar::as_columns(items.iter());
+                let perm: Vec<usize> = (0 .. 100).map(|i| (i * 37) % 100).collect();
+                column.permute(&perm);
+                assert_eq!(column.len(), 100);
+                for (index, source) in perm.iter().enumerate() {
+                    assert_eq!((&column).get(index), items[*source].as_ref().map_err(|err| err.as_str()));
+                }
+
+                // All rows of one variant.
+                let mut column: <Result<u64, u64> as Columnar>::Container = Columnar::into_columns((0 .. 10u64).map(Ok::<u64, u64>));
+                column.permute(&(0 .. 10).rev().collect::<Vec<_>>());
+                assert!((0 .. 10).all(|index| (&column).get(index) == Ok(9 - index as u64)));
+            }
         }
     }
 
     pub mod option {
 
         use crate::common::index::CopyAs;
-        use crate::{Clear, Columnar, Len, IndexMut, Index, IndexAs, Push, HeapSize};
+        use crate::{Clear, Columnar, Len, IndexMut, Index, IndexAs, Push, HeapSize, Permute, ExtendFromRange};
         use crate::RankSelect;
 
-        #[derive(Copy, Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
         pub struct Options<TC, CC=Vec<u64>, VC=Vec<u64>, WC=u64> {
             /// Uses two bits for each item, one to indicate the variant and one (amortized)
             /// to enable efficient rank determination.
@@ -1786,6 +4728,11 @@ pub mod sums {
             pub somes: TC,
         }
 
+        impl<T: crate::IntoOwned> crate::IntoOwned for Option<T> {
+            type Owned = Option<T::Owned>;
+            fn into_owned(self) -> Self::Owned { self.map(crate::IntoOwned::into_owned) }
+        }
+
         impl<T: Columnar> Columnar for Option<T> {
             type Ref<'a> = Option<T::Ref<'a>> where T: 'a;
             fn copy_from<'a>(&mut self, other: Self::Ref<'a>) {
@@ -1896,6 +4843,33 @@ pub mod sums {
             }
         }
 
+        impl<TC: Permute> Permute for Options<TC> {
+            fn permute(&mut self, perm: &[usize]) {
+                assert_eq!(perm.len(), self.len());
+                let mut indexes = RankSelect::default();
+                let mut somes = Vec::with_capacity(self.indexes.rank(self.len()));
+                for index in perm {
+                    let some = self.indexes.get(*index);
+                    indexes.push(some);
+                    if some { somes.push(self.indexes.rank(*index)); }
+                }
+                self.indexes = indexes;
+                self.somes.permute(&somes);
+            }
+        }
+
+        impl<TC: ExtendFromRange> ExtendFromRange for Options<TC> {
+            fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+                assert!(range.start <= range.end && range.end <= other.len());
+                let lower = other.indexes.rank(range.start);
+                let upper = other.indexes.rank(range.end);
+                for index in range {
+                    self.indexes.push(other.indexes.get(index));
+                }
+                self.somes.extend_from_range(&other.somes, lower .. upper);
+            }
+        }
+
         impl<TC: HeapSize> HeapSize for Options<TC> {
             fn heap_size(&self) -> (usize, usize) {
                 let (l0, c0) = self.somes.heap_size();
@@ -1904,6 +4878,246 @@ pub mod sums {
             }
         }
 
+        impl<TC> Options<TC> {
+            /// Whether each row is present.
+            pub fn validity(&self) -> &crate::primitive::Bools { &self.indexes.values }
+            /// The present values, in order, with the absent rows dropped.
+            ///
+            /// Kernels that do not need to know which rows are absent can run on these densely.
+            pub fn flatten(&self) -> &TC { &self.somes }
+        }
+        impl<TC: Len> Options<TC> {
+            /// Rows from their present values, in order, and whether each row is present.
+            ///
+            /// Panics if the number of present rows differs from the number of values.
+            pub fn unflatten(values: TC, validity: &crate::primitive::Bools) -> Self {
+                let mut indexes = RankSelect::default();
+                for index in 0 .. validity.len() { indexes.push(validity.get(index)); }
+                assert_eq!(validity.count_ones(0 .. validity.len()), values.len(), "validity and values disagree on the number of present rows");
+                Self { indexes, somes: values }
+            }
+        }
+
+        impl<TC> crate::Vecs<Options<TC>> {
+            /// Lists of the present elements of each list, with the absent elements dropped.
+            ///
+            /// The values are those of `self`, borrowed, and only the bounds are recomputed.
+            pub fn flatten(&self) -> crate::Vecs<&TC> {
+                let bounds = self.bounds.iter().map(|bound| self.values.indexes.rank(*bound as usize) as u64).collect();
+                crate::Vecs { bounds, values: &self.values.somes }
+            }
+        }
+
+        impl<TC: Len> From<Options<crate::Vecs<TC>>> for NullableVecs<TC> {
+            fn from(options: Options<crate::Vecs<TC>>) -> Self {
+                let validity = options.indexes.values;
+                let mut bounds = Vec::with_capacity(validity.len());
+                let mut present = IntoIterator::into_iter(options.somes.bounds);
+                let mut bound = 0;
+                for index in 0 .. validity.len() {
+                    if validity.get(index) { bound = present.next().unwrap(); }
+                    bounds.push(bound);
+                }
+                Self { validity, lists: crate::Vecs { bounds, values: options.somes.values } }
+            }
+        }
+        impl<TC: Len> From<NullableVecs<TC>> for Options<crate::Vecs<TC>> {
+            fn from(nullable: NullableVecs<TC>) -> Self {
+                let mut indexes = RankSelect::default();
+                let mut bounds = Vec::new();
+                for (index, bound) in IntoIterator::into_iter(nullable.lists.bounds).enumerate() {
+                    let present = nullable.validity.get(index);
+                    indexes.push(present);
+                    if present { bounds.push(bound); }
+                }
+                Self { indexes, somes: crate::Vecs { bounds, values: nullable.lists.values } }
+            }
+        }
+
+        /// A store for `Option<T>` with a value for every row, and a bit for whether it is present.
+        ///
+        /// Unlike `Options`, which stores only the present values, the value of row `i` is always
+        /// at position `i` of `values`, and absent rows hold `T::default()`. Kernels can operate on
+        /// all of `values` and consult `validity` after, and the layout matches Arrow's for
+        /// primitive types. This is meant for small `Copy` types, where the filler costs little.
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+        pub struct DenseOptions<TC, VC=Vec<u64>, WC=u64> {
+            /// Whether each row is present.
+            pub validity: crate::primitive::Bools<VC, WC>,
+            /// The value of each row, or a default filler when it is absent.
+            pub values: TC,
+        }
+
+        impl<T: Columnar, TC: crate::Container<T>> crate::Container<Option<T>> for DenseOptions<TC> {
+            type Borrowed<'a> = DenseOptions<TC::Borrowed<'a>, &'a [u64], &'a u64> where TC: 'a, T: 'a;
+            fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
+                DenseOptions {
+                    validity: crate::Container::<bool>::borrow(&self.validity),
+                    values: self.values.borrow(),
+                }
+            }
+        }
+
+        impl<'a, TC: crate::AsBytes<'a>, VC: crate::AsBytes<'a>> crate::AsBytes<'a> for DenseOptions<TC, VC, &'a u64> {
+            fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
+                self.validity.as_bytes().chain(self.values.as_bytes())
+            }
+        }
+        impl <'a, TC: crate::FromBytes<'a>, VC: crate::FromBytes<'a>> crate::FromBytes<'a> for DenseOptions<TC, VC, &'a u64> {
+            fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self {
+                Self {
+                    validity: crate::FromBytes::from_bytes(bytes),
+                    values: crate::FromBytes::from_bytes(bytes),
+                }
+            }
+        }
+
+        impl<TC, VC: Len, WC: Copy + CopyAs<u64>> Len for DenseOptions<TC, VC, WC> {
+            #[inline(always)] fn len(&self) -> usize { self.validity.len() }
+        }
+        impl<TC: Index, VC: IndexAs<u64> + Len, WC: Copy+CopyAs<u64>> Index for DenseOptions<TC, VC, WC> {
+            type Ref = Option<TC::Ref>;
+            #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+                if self.validity.get(index) { Some(self.values.get(index)) } else { None }
+            }
+        }
+        impl<'a, TC, VC: IndexAs<u64> + Len, WC: Copy+CopyAs<u64>> Index for &'a DenseOptions<TC, VC, WC> where &'a TC: Index {
+            type Ref = Option<<&'a TC as Index>::Ref>;
+            #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+                if self.validity.get(index) { Some((&self.values).get(index)) } else { None }
+            }
+        }
+
+        impl<T: Copy + Default, TC: Push<T>> Push<Option<&T>> for DenseOptions<TC> {
+            #[inline(always)] fn push(&mut self, item: Option<&T>) {
+                self.validity.push(item.is_some());
+                self.values.push(item.copied().unwrap_or_default());
+            }
+        }
+        impl<T: Copy + Default, TC: Push<T>> Push<&Option<T>> for DenseOptions<TC> {
+            #[inline(always)] fn push(&mut self, item: &Option<T>) { self.push(item.as_ref()) }
+        }
+
+        impl<TC: Clear> Clear for DenseOptions<TC> {
+            fn clear(&mut self) {
+                self.validity.clear();
+                self.values.clear();
+            }
+        }
+        impl<TC: Permute> Permute for DenseOptions<TC> {
+            fn permute(&mut self, perm: &[usize]) {
+                self.validity.permute(perm);
+                self.values.permute(perm);
+            }
+        }
+        impl<TC: ExtendFromRange> ExtendFromRange for DenseOptions<TC> {
+            fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+                self.validity.extend_from_range(&other.validity, range.clone());
+                self.values.extend_from_range(&other.values, range);
+            }
+        }
+        impl<TC: HeapSize> HeapSize for DenseOptions<TC> {
+            fn heap_size(&self) -> (usize, usize) {
+                let (l0, c0) = self.values.heap_size();
+                let (li, ci) = self.validity.heap_size();
+                (l0 + li, c0 + ci)
+            }
+        }
+
+        /// A store for `Option<Vec<T>>` with one bound for every row, and a bit for whether it is present.
+        ///
+        /// `Options<Vecs<TC>>` stores only the present lists, and locates the list of a row by
+        /// ranking its bit among the present rows. Here absent rows are empty lists, repeating the
+        /// preceding bound, and a validity bit distinguishes them from present empty lists. Rows
+        /// are located directly, at the cost of a bound for each absent row. This is the layout
+        /// of Arrow's nullable lists, whose offsets are these bounds preceded by a zero.
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+        pub struct NullableVecs<TC, BC=Vec<u64>, VC=Vec<u64>, WC=u64> {
+            /// Whether each row is present.
+            pub validity: crate::primitive::Bools<VC, WC>,
+            /// The list of each row, or an empty list when it is absent.
+            pub lists: crate::Vecs<TC, BC>,
+        }
+
+        impl<T: Columnar<Container = TC>, TC: crate::Container<T>> crate::Container<Option<Vec<T>>> for NullableVecs<TC> {
+            type Borrowed<'a> = NullableVecs<TC::Borrowed<'a>, &'a [u64], &'a [u64], &'a u64> where TC: 'a, T: 'a;
+            fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
+                NullableVecs {
+                    validity: crate::Container::<bool>::borrow(&self.validity),
+                    lists: crate::Container::<Vec<T>>::borrow(&self.lists),
+                }
+            }
+        }
+
+        impl<'a, TC: crate::AsBytes<'a>, BC: crate::AsBytes<'a>, VC: crate::AsBytes<'a>> crate::AsBytes<'a> for NullableVecs<TC, BC, VC, &'a u64> {
+            fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
+                self.validity.as_bytes().chain(self.lists.as_bytes())
+            }
+        }
+        impl <'a, TC: crate::FromBytes<'a>, BC: crate::FromBytes<'a>, VC: crate::FromBytes<'a>> crate::FromBytes<'a> for NullableVecs<TC, BC, VC, &'a u64> {
+            fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self {
+                Self {
+                    validity: crate::FromBytes::from_bytes(bytes),
+                    lists: crate::FromBytes::from_bytes(bytes),
+                }
+            }
+        }
+
+        impl<TC, BC, VC: Len, WC: Copy + CopyAs<u64>> Len for NullableVecs<TC, BC, VC, WC> {
+            #[inline(always)] fn len(&self) -> usize { self.validity.len() }
+        }
+        impl<TC: Copy, BC: Len + IndexAs<u64>, VC: IndexAs<u64> + Len, WC: Copy+CopyAs<u64>> Index for NullableVecs<TC, BC, VC, WC> {
+            type Ref = Option<crate::Slice<TC>>;
+            #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+                if self.validity.get(index) { Some(self.lists.get(index)) } else { None }
+            }
+        }
+        impl<'a, TC, BC: Len + IndexAs<u64>, VC: IndexAs<u64> + Len, WC: Copy+CopyAs<u64>> Index for &'a NullableVecs<TC, BC, VC, WC> {
+            type Ref = Option<crate::Slice<&'a TC>>;
+            #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+                if self.validity.get(index) { Some((&self.lists).get(index)) } else { None }
+            }
+        }
+
+        impl<TC: Len, TC2> Push<Option<crate::Slice<TC2>>> for NullableVecs<TC> where crate::Vecs<TC>: Push<crate::Slice<TC2>> {
+            #[inline(always)] fn push(&mut self, item: Option<crate::Slice<TC2>>) {
+                self.validity.push(item.is_some());
+                match item {
+                    Some(list) => self.lists.push(list),
+                    None => self.lists.bounds.push(self.lists.values.len() as u64),
+                }
+            }
+        }
+        impl<'a, T, TC: Len> Push<&'a Option<Vec<T>>> for NullableVecs<TC> where crate::Vecs<TC>: Push<&'a Vec<T>> {
+            #[inline(always)] fn push(&mut self, item: &'a Option<Vec<T>>) {
+                self.validity.push(item.is_some());
+                match item {
+                    Some(list) => self.lists.push(list),
+                    None => self.lists.bounds.push(self.lists.values.len() as u64),
+                }
+            }
+        }
+
+        impl<TC: Clear> Clear for NullableVecs<TC> {
+            fn clear(&mut self) {
+                self.validity.clear();
+                self.lists.clear();
+            }
+        }
+        impl<TC: ExtendFromRange + Len> ExtendFromRange for NullableVecs<TC> {
+            fn extend_from_range(&mut self, other: &Self, range: std::ops::Range<usize>) {
+                self.validity.extend_from_range(&other.validity, range.clone());
+                self.lists.extend_from_range(&other.lists, range);
+            }
+        }
+        impl<TC: HeapSize> HeapSize for NullableVecs<TC> {
+            fn heap_size(&self) -> (usize, usize) {
+                let (l0, c0) = self.lists.heap_size();
+                let (li, ci) = self.validity.heap_size();
+                (l0 + li, c0 + ci)
+            }
+        }
+
         #[cfg(test)]
         mod test {
 
@@ -1937,21 +5151,123 @@ pub mod sums {
                 assert!((&store).iter().zip(0..100).all(|(a, b)| a == if b % 2 == 0 { Some(&b) } else { None }));
                 assert_eq!(store.heap_size(), (208, 288));
             }
+
+            #[test]
+            fn dense() {
+                use crate::{Container, Push};
+                use super::DenseOptions;
+
+                let items: Vec<Option<i32>> = (0..100).map(|x| if x % 3 == 0 { None } else { Some(x) }).collect();
+                let mut store: DenseOptions<Vec<i32>> = Default::default();
+                for item in items.iter() { store.push(item); }
+                assert_eq!(store.values.len(), 100);
+                assert_eq!(store.values[3], 0);
+                assert_eq!(store.values[4], 4);
+                let borrowed = Container::<Option<i32>>::borrow(&store);
+                assert!(borrowed.into_iter().zip(items.iter()).all(|(a, b)| a == b.as_ref()));
+            }
+
+            #[test]
+            fn flatten() {
+                use crate::{Index, Vecs};
+                let items: Vec<Option<i32>> = (0..100).map(|x| if x % 3 == 0 { None } else { Some(x) }).collect();
+                let store: Options<Vec<i32>> = Columnar::as_columns(items.iter());
+                assert_eq!(store.flatten().len(), 66);
+                assert!(!store.validity().get(3));
+                let rebuilt = Options::unflatten(store.flatten().clone(), store.validity());
+                assert_eq!(rebuilt, store);
+
+                let lists: Vec<Vec<Option<i32>>> = (0..20).map(|x| items[x .. 2 * x].to_vec()).collect();
+                let store: Vecs<Options<Vec<i32>>> = Columnar::as_columns(lists.iter());
+                let flat = store.flatten();
+                for (index, list) in lists.iter().enumerate() {
+                    let present: Vec<i32> = list.iter().flatten().copied().collect();
+                    assert_eq!(flat.get(index).into_iter().copied().collect::<Vec<_>>(), present);
+                }
+            }
+
+            #[test]
+            fn nullable_vecs() {
+                use crate::{AsBytes, Container, FromBytes, Push};
+                use super::NullableVecs;
+
+                let items: Vec<Option<Vec<u64>>> = (0..100u64).map(|x| if x % 3 == 0 { None } else { Some((0 .. x % 5).collect()) }).collect();
+                let mut store: NullableVecs<Vec<u64>> = Default::default();
+                for item in items.iter() { store.push(item); }
+                assert_eq!(store.lists.bounds.len(), 100);
+                assert_eq!(store.lists.bounds[3], store.lists.bounds[2]);
+
+                let nested: Options<crate::Vecs<Vec<u64>>> = Columnar::as_columns(items.iter());
+                assert_eq!(store.lists.values, nested.somes.values);
+
+                let borrowed = Container::<Option<Vec<u64>>>::borrow(&store);
+                let decoded = NullableVecs::<&[u64], &[u64], &[u64], &u64>::from_bytes(&mut borrowed.as_bytes().map(|(_, bytes)| bytes));
+                for (index, item) in items.iter().enumerate() {
+                    let row = decoded.get(index).map(|list| list.into_iter().copied().collect::<Vec<_>>());
+                    assert_eq!(&row, item);
+                    assert_eq!(row, (&nested).get(index).map(|list| list.into_iter().copied().collect::<Vec<_>>()));
+                }
+
+                assert_eq!(Options::from(store.clone()), nested);
+                assert_eq!(NullableVecs::from(nested.clone()), store);
+
+                let taken = Container::<Option<Vec<u64>>>::take(&store, &[4, 3, 4]);
+                assert_eq!(taken.len(), 3);
+                assert_eq!(Container::<Option<Vec<u64>>>::materialize(&taken, 2), Some(vec![0, 1, 2, 3]));
+                assert_eq!(Container::<Option<Vec<u64>>>::materialize(&taken, 1), None);
+            }
+
+            #[test]
+            fn permute() {
+                use crate::Permute;
+                let mut store: Options<Vec<i32>>  = Columnar::into_columns((0..100).map(|x| if x % 3 == 0 { None } else { Some(x) }));
+                let perm: Vec<usize> = (0..100).rev().collect();
+                store.permute(&perm);
+                assert_eq!(store.len(), 100);
+                assert!((&store).iter().zip((0..100).rev()).all(|(a, b)| a == if b % 3 == 0 { None } else { Some(&b) }));
+            }
+
+            #[test]
+            fn equality() {
+                use crate::{Permute, Push};
+                let items: Vec<_> = (0..100).map(|x| if x % 3 == 0 { None } else { Some(x) }).collect();
+                let store1: Options<Vec<i32>> = Columnar::into_columns(items.iter().copied());
+                let mut store2: Options<Vec<i32>> = Default::default();
+                for item in items.iter() { store2.push(item); }
+                assert_eq!(store1, store2);
+                store2.permute(&(0..100).rev().collect::<Vec<_>>());
+                assert_ne!(store1, store2);
+            }
+
+            #[test]
+            fn extend_from_range() {
+                use crate::{ExtendFromRange, Strings};
+                let items: Vec<_> = (0..100).map(|x| if x % 3 == 0 { None } else { Some(x.to_string()) }).collect();
+                let store: Options<Strings> = Columnar::as_columns(items.iter());
+                let mut extended: Options<Strings> = Columnar::as_columns(items[..10].iter());
+                extended.extend_from_range(&store, 25 .. 60);
+                let expected: Options<Strings> = Columnar::as_columns(items[..10].iter().chain(items[25..60].iter()));
+                assert_eq!(extended, expected);
+                let extracted = store.extract_range(25 .. 60);
+                assert_eq!(extracted, Columnar::as_columns(items[25..60].iter()));
+                assert_eq!(extracted.somes.bounds.first(), Some(&2));
+            }
         }
     }
 }
 
-pub use lookback::{Repeats, Lookbacks};
+pub use lookback::{Repeats, Lookbacks, Constants, Interned};
 /// Containers that can store either values, or offsets to prior values.
 ///
 /// This has the potential to be more efficient than a list of `T` when many values repeat in
 /// close proximity. Values must be equatable, and the degree of lookback can be configured.
 pub mod lookback {
 
-    use crate::{Options, Results, Push, Index, Len, HeapSize};
+    use crate::{Columnar, Container, AsBytes, FromBytes, Options, Results, Push, Index, Len, HeapSize, Clear, ExtendFromRange};
+    use crate::common::index::CopyAs;
 
     /// A container that encodes repeated values with a `None` variant, at the cost of extra bits for every record.
-    #[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+    #[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
     pub struct Repeats<TC, const N: u8 = 255> {
         /// Some(x) encodes a value, and None indicates the prior `x` value.
         pub inner: Options<TC>,
@@ -1989,6 +5305,21 @@ pub mod lookback {
             }
         }
     }
+    impl<'a, TC, const N: u8> Index for &'a Repeats<TC, N>
+    where
+        &'a TC: Index,
+    {
+        type Ref = <&'a TC as Index>::Ref;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+            match (&self.inner).get(index) {
+                Some(item) => item,
+                None => {
+                    let pos = self.inner.indexes.rank(index) - 1;
+                    (&self.inner.somes).get(pos)
+                },
+            }
+        }
+    }
 
     impl<TC: HeapSize, const N: u8> HeapSize for Repeats<TC, N> {
         fn heap_size(&self) -> (usize, usize) {
@@ -1996,7 +5327,101 @@ pub mod lookback {
         }
     }
 
-    #[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+    /// A container that stores a single value while all pushed values are equal.
+    ///
+    /// The first push that differs from the prior values expands the container to hold each
+    /// row, after which it behaves like `TC`. Columns that are constant within a batch, like
+    /// a tenant or partition key, are stored in the space of one value.
+    #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+    pub struct Constants<TC, WC = u64> {
+        /// Each row, or while all rows are equal, only the first row.
+        pub values: TC,
+        /// The number of rows while all rows are equal, and zero once they are not.
+        pub repeats: WC,
+    }
+
+    impl<TC: Len, WC: Copy + CopyAs<u64>> Constants<TC, WC> {
+        /// True if all rows are equal, as they are when there are none.
+        pub fn is_constant(&self) -> bool { self.repeats.copy_as() > 0 || self.values.is_empty() }
+    }
+
+    impl<C: Columnar + ?Sized, TC: Container<C>> Container<C> for Constants<TC> {
+        type Borrowed<'a> = Constants<TC::Borrowed<'a>, &'a u64> where TC: 'a, C: 'a;
+        fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
+            Constants { values: self.values.borrow(), repeats: &self.repeats }
+        }
+    }
+
+    impl<'a, TC: AsBytes<'a>> AsBytes<'a> for Constants<TC, &'a u64> {
+        fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
+            self.values.as_bytes()
+                .chain(std::iter::once((8, bytemuck::cast_slice(std::slice::from_ref(self.repeats)))))
+        }
+    }
+    impl<'a, TC: FromBytes<'a>> FromBytes<'a> for Constants<TC, &'a u64> {
+        fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self {
+            let values = FromBytes::from_bytes(bytes);
+            let repeats = &bytemuck::try_cast_slice(bytes.next().unwrap()).unwrap()[0];
+            Self { values, repeats }
+        }
+    }
+
+    impl<T: PartialEq, TC: Push<T> + Len + Default + ExtendFromRange> Push<T> for Constants<TC>
+    where
+        for<'a> &'a TC: Index,
+        for<'a> <&'a TC as Index>::Ref : PartialEq<T>,
+    {
+        fn push(&mut self, item: T) {
+            if self.values.is_empty() {
+                self.values.push(item);
+                self.repeats = 1;
+            }
+            else if self.repeats > 0 && (&self.values).get(0) == item {
+                self.repeats += 1;
+            }
+            else if self.repeats > 0 {
+                // Write out the repeated value for each prior row, then the new value.
+                let mut values = TC::default();
+                for _ in 0 .. self.repeats { values.extend_from_range(&self.values, 0 .. 1); }
+                values.push(item);
+                self.values = values;
+                self.repeats = 0;
+            }
+            else {
+                self.values.push(item);
+            }
+        }
+    }
+
+    impl<TC: Len, WC: Copy + CopyAs<u64>> Len for Constants<TC, WC> {
+        #[inline(always)] fn len(&self) -> usize {
+            let repeats = self.repeats.copy_as() as usize;
+            if repeats > 0 { repeats } else { self.values.len() }
+        }
+    }
+    impl<TC: Index, WC: Copy + CopyAs<u64>> Index for Constants<TC, WC> {
+        type Ref = TC::Ref;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+            self.values.get(if self.repeats.copy_as() > 0 { 0 } else { index })
+        }
+    }
+    impl<'a, TC, WC: Copy + CopyAs<u64>> Index for &'a Constants<TC, WC> where &'a TC: Index {
+        type Ref = <&'a TC as Index>::Ref;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+            (&self.values).get(if self.repeats.copy_as() > 0 { 0 } else { index })
+        }
+    }
+    impl<TC: Clear> Clear for Constants<TC> {
+        fn clear(&mut self) {
+            self.values.clear();
+            self.repeats = 0;
+        }
+    }
+    impl<TC: HeapSize, WC> HeapSize for Constants<TC, WC> {
+        fn heap_size(&self) -> (usize, usize) { self.values.heap_size() }
+    }
+
+    #[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
     pub struct Lookbacks<TC, VC = Vec<u8>, const N: u8 = 255> {
         /// Ok(x) encodes a value, and Err(y) indicates a value `y` back.
         pub inner: Results<TC, VC>,
@@ -2053,6 +5478,159 @@ pub mod lookback {
             self.inner.heap_size()
         }
     }
+
+    /// A container that stores each distinct row once, and each row as the `u32` id of its distinct row.
+    ///
+    /// Pushed rows are hashed, and compared against the distinct rows with the same hash.
+    /// Columns of keys that repeat many times, like composite keys in keyed streams, are
+    /// stored as their distinct keys and four bytes for each row.
+    #[derive(Copy, Clone, Debug, Default)]
+    pub struct Interned<TC, IC = Vec<u32>, LC = Lookup> {
+        /// The distinct rows, in order of their first occurrence.
+        pub rows: TC,
+        /// For each row, the position of its distinct row in `rows`.
+        pub ids: IC,
+        /// The means to find the distinct row of a pushed row, or `()` once borrowed.
+        lookup: LC,
+    }
+
+    /// The ids of distinct rows, by the hashes of their values.
+    #[derive(Clone, Debug, Default)]
+    pub struct Lookup {
+        ids: std::collections::HashMap<u64, Vec<u32>>,
+        state: std::collections::hash_map::RandomState,
+    }
+
+    impl<TC, IC, LC> Interned<TC, IC, LC> {
+        /// The distinct rows, in order of their first occurrence.
+        pub fn dictionary(&self) -> &TC { &self.rows }
+        /// For each row, the position of its distinct row in the dictionary.
+        pub fn ids(&self) -> &IC { &self.ids }
+    }
+
+    impl<C: Columnar + ?Sized, TC: Container<C>> Container<C> for Interned<TC> {
+        type Borrowed<'a> = Interned<TC::Borrowed<'a>, &'a [u32], ()> where TC: 'a, C: 'a;
+        fn borrow<'a>(&'a self) -> Self::Borrowed<'a> {
+            Interned { rows: self.rows.borrow(), ids: &self.ids[..], lookup: () }
+        }
+    }
+
+    impl<'a, TC: AsBytes<'a>> AsBytes<'a> for Interned<TC, &'a [u32], ()> {
+        fn as_bytes(&self) -> impl Iterator<Item=(u64, &'a [u8])> {
+            self.rows.as_bytes().chain(self.ids.as_bytes())
+        }
+    }
+    impl<'a, TC: FromBytes<'a>> FromBytes<'a> for Interned<TC, &'a [u32], ()> {
+        fn from_bytes(bytes: &mut impl Iterator<Item=&'a [u8]>) -> Self {
+            Self {
+                rows: FromBytes::from_bytes(bytes),
+                ids: FromBytes::from_bytes(bytes),
+                lookup: (),
+            }
+        }
+    }
+
+    impl<T: std::hash::Hash, TC: Push<T> + Len> Push<T> for Interned<TC>
+    where
+        for<'a> &'a TC: Index,
+        for<'a> <&'a TC as Index>::Ref : PartialEq<T>,
+    {
+        fn push(&mut self, item: T) {
+            use std::hash::BuildHasher;
+            let hash = self.lookup.state.hash_one(&item);
+            let candidates = self.lookup.ids.entry(hash).or_default();
+            let id = match candidates.iter().find(|id| (&self.rows).get(**id as usize) == item) {
+                Some(id) => *id,
+                None => {
+                    let id = u32::try_from(self.rows.len()).expect("more than u32::MAX distinct rows");
+                    self.rows.push(item);
+                    candidates.push(id);
+                    id
+                }
+            };
+            self.ids.push(id);
+        }
+    }
+
+    impl<TC, IC: Len, LC> Len for Interned<TC, IC, LC> {
+        #[inline(always)] fn len(&self) -> usize { self.ids.len() }
+    }
+    impl<TC: Index, IC: crate::IndexAs<u32>, LC> Index for Interned<TC, IC, LC> {
+        type Ref = TC::Ref;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref { self.rows.get(crate::IndexAs::index_as(&self.ids, index) as usize) }
+    }
+    impl<'a, TC, IC: crate::IndexAs<u32>, LC> Index for &'a Interned<TC, IC, LC> where &'a TC: Index {
+        type Ref = <&'a TC as Index>::Ref;
+        #[inline(always)] fn get(&self, index: usize) -> Self::Ref { (&self.rows).get(crate::IndexAs::index_as(&self.ids, index) as usize) }
+    }
+    impl<TC: Clear> Clear for Interned<TC> {
+        fn clear(&mut self) {
+            self.rows.clear();
+            self.ids.clear();
+            self.lookup.ids.clear();
+        }
+    }
+    impl<TC: HeapSize, IC: HeapSize, LC> HeapSize for Interned<TC, IC, LC> {
+        fn heap_size(&self) -> (usize, usize) {
+            let (l0, c0) = self.rows.heap_size();
+            let (l1, c1) = self.ids.heap_size();
+            (l0 + l1, c0 + c1)
+        }
+    }
+
+    #[cfg(test)]
+    mod test {
+
+        use crate::{AsBytes, Clear, Container, FromBytes, HeapSize, Index, Len, Push, Strings};
+        use super::{Constants, Interned};
+
+        #[test]
+        fn interned() {
+            let keys: Vec<String> = (0 .. 10).map(|i| format!("tenant{}/user{}", i % 3, i)).collect();
+            let mut column: Interned<Strings> = Default::default();
+            for i in 0 .. 1000 { column.push(&keys[(i * 7) % 10]); }
+            assert_eq!(column.len(), 1000);
+            assert_eq!(column.dictionary().len(), 10);
+            assert_eq!(column.ids()[1], 1);
+            for i in 0 .. 1000 { assert_eq!((&column).get(i), keys[(i * 7) % 10]); }
+            assert!(column.heap_size().0 < 5000);
+
+            let borrowed = Container::<String>::borrow(&column);
+            let decoded = Interned::<Strings<&[u64], &[u8]>, &[u32], ()>::from_bytes(&mut borrowed.as_bytes().map(|(_, bytes)| bytes));
+            assert_eq!(decoded.len(), 1000);
+            for i in 0 .. 1000 { assert_eq!(decoded.get(i), keys[(i * 7) % 10]); }
+
+            let taken = Container::<String>::take(&column, &[3, 3, 1]);
+            assert_eq!(taken.dictionary().len(), 2);
+            assert_eq!(taken.ids(), &[0, 0, 1]);
+        }
+
+        #[test]
+        fn constants() {
+            let tenant = "tenant-0001".to_string();
+            let mut column: Constants<Strings> = Default::default();
+            for _ in 0 .. 1000 { column.push(&tenant); }
+            assert!(column.is_constant());
+            assert_eq!(column.len(), 1000);
+            assert_eq!((&column).get(999), "tenant-0001");
+            assert!(column.heap_size().0 < 100);
+
+            column.push(&"tenant-0002".to_string());
+            assert!(!column.is_constant());
+            assert_eq!(column.len(), 1001);
+            assert_eq!((&column).get(999), "tenant-0001");
+            assert_eq!((&column).get(1000), "tenant-0002");
+
+            column.clear();
+            assert!(column.is_constant());
+            for _ in 0 .. 10 { column.push(&tenant); }
+            let borrowed = Container::<String>::borrow(&column);
+            let decoded = Constants::<Strings<&[u64], &[u8]>, &u64>::from_bytes(&mut borrowed.as_bytes().map(|(_, bytes)| bytes));
+            assert!(decoded.is_constant());
+            assert_eq!(decoded.len(), 10);
+            assert_eq!(decoded.get(9), "tenant-0001");
+        }
+    }
 }
 
 /// Containers for `Vec<(K, V)>` that form columns by `K` keys.
@@ -246,6 +246,322 @@ mod test {
     #[derive(Columnar, Debug)]
     struct Test5;
 
+    // Tests derived implementations for transparent newtypes.
+    #[derive(Columnar, Debug, PartialEq)]
+    #[columnar(transparent)]
+    struct Test6(u64);
+    #[derive(Columnar, Debug, PartialEq)]
+    #[columnar(transparent)]
+    struct Test7 { name: String }
+
+    // Tests derived implementations with custom bounds, and with pinned column order.
+    #[derive(Columnar, Debug)]
+    #[columnar(bound = "T: Columnar")]
+    struct Test8<T> { items: Vec<T>, count: u64 }
+    #[derive(Columnar, Debug)]
+    struct Test9 {
+        #[columnar(order = 2)]
+        count: u64,
+        #[columnar(order = 1)]
+        name: String,
+    }
+
+    #[test]
+    fn bounds_and_order() {
+
+        use columnar::{AsBytes, Container, Index};
+
+        let test8s = [Test8 { items: vec!["a".to_string()], count: 1 }];
+        let test8c = Columnar::as_columns(test8s.iter());
+        assert_eq!(Container::<Test8<String>>::borrow(&test8c).get(0).items.get(0), "a");
+
+        let test9s: Vec<Test9> = (0 .. 10).map(|i| Test9 { count: i, name: i.to_string() }).collect();
+        let test9c = Columnar::as_columns(test9s.iter());
+        let borrowed = Container::<Test9>::borrow(&test9c);
+        // The name column is serialized first, and compared first.
+        let names = borrowed.name.as_bytes().map(|(_, bytes)| bytes);
+        let counts = borrowed.count.as_bytes().map(|(_, bytes)| bytes);
+        assert!(borrowed.as_bytes().map(|(_, bytes)| bytes).eq(names.chain(counts)));
+        assert_eq!(columnar::sort::CompareColumns::compare_column(&borrowed, 0, 2, 9), "2".cmp("9"));
+    }
+
+    // Tests derived implementations of `Tagged`, for two versions of a struct.
+    #[derive(Columnar, Debug, PartialEq)]
+    struct Test10 {
+        #[columnar(tag = 1)]
+        id: u64,
+        #[columnar(tag = 2)]
+        name: String,
+    }
+    #[derive(Columnar, Debug, PartialEq)]
+    struct Test11 {
+        #[columnar(tag = 3)]
+        score: Option<u8>,
+        #[columnar(tag = 1)]
+        id: u64,
+    }
+
+    #[test]
+    fn tagged() {
+
+        use columnar::{Container, Len};
+        use columnar::tagged::Tagged;
+
+        let test10s: Vec<Test10> = (0 .. 10).map(|i| Test10 { id: i, name: i.to_string() }).collect();
+        let test10c = Columnar::as_columns(test10s.iter());
+        let mut store = Vec::new();
+        Test10::encode_tagged(&test10c, &mut store);
+        assert_eq!(Test10::decode_tagged(&store).materialize(3), test10s[3]);
+
+        // The newer version drops `name`, and adds `score`.
+        let test11c = Test11::decode_tagged(&store);
+        assert_eq!(test11c.len(), 10);
+        assert_eq!(test11c.materialize(3), Test11 { score: None, id: 3 });
+    }
+
+    // Tests derived implementations of `OpenEnum`, for two versions of an enum.
+    #[derive(Columnar, Debug, PartialEq)]
+    enum Test12 {
+        Start(u64),
+        Stop(String),
+    }
+    #[derive(Columnar, Debug, PartialEq)]
+    enum Test13 {
+        Start(u64),
+        Stop(String),
+        Pause(Vec<u8>, bool),
+    }
+
+    #[test]
+    fn open() {
+
+        use columnar::{Container, Index, Len};
+        use columnar::open::{self, Open, Opened};
+
+        let test13s = [Test13::Start(1), Test13::Pause(vec![1, 2], true), Test13::Stop("done".to_string()), Test13::Pause(vec![], false)];
+        let test13c = Columnar::as_columns(test13s.iter());
+        let mut store = Vec::new();
+        open::encode::<Test13>(&test13c, &mut store);
+
+        // The older version keeps the rows of `Pause` as bytes, and writes them back unchanged.
+        let opened = Opened::<Test12>::decode(&store);
+        assert_eq!(opened.len(), 4);
+        assert_eq!((&opened).get(0).map(Test12::into_owned), Open::Known(Test12::Start(1)));
+        assert_eq!((&opened).get(1).map(Test12::into_owned), Open::Unknown(2));
+        assert_eq!((&opened).get(2).map(Test12::into_owned), Open::Known(Test12::Stop("done".to_string())));
+        let mut store2 = Vec::new();
+        opened.encode(&mut store2);
+        assert_eq!(store, store2);
+
+        // The newer version reads the rows of both.
+        let opened = Opened::<Test13>::decode(&store2);
+        for (index, test13) in test13s.iter().enumerate() {
+            assert_eq!((&opened).get(index).map(Test13::into_owned), Open::Known(Test13::into_owned(Container::<Test13>::borrow(&test13c).get(index))));
+            assert_eq!(opened.tag(index) as usize, [0, 2, 1, 2][index]);
+            assert_eq!(&Test13::into_owned(Container::<Test13>::borrow(&test13c).get(index)), test13);
+        }
+        let mut store3 = Vec::new();
+        open::encode::<Test12>(&Columnar::as_columns([Test12::Stop("old".to_string())].iter()), &mut store3);
+        assert_eq!((&Opened::<Test13>::decode(&store3)).get(0).map(Test13::into_owned), Open::Known(Test13::Stop("old".to_string())));
+    }
+
+    // Tests derived implementations for fieldless enums with an integer representation.
+    #[derive(Columnar, Debug, PartialEq)]
+    #[repr(u16)]
+    enum Test14 {
+        Low = 1,
+        High = 1000,
+    }
+
+    #[test]
+    fn repr() {
+
+        use columnar::{Container, Index};
+
+        let test14c = Columnar::as_columns([Test14::High, Test14::Low, Test14::High].iter());
+        let borrowed = Container::<Test14>::borrow(&test14c);
+        assert_eq!(borrowed.variant, &[1000u16, 1, 1000]);
+        assert_eq!(borrowed.get(1), Test14::Low);
+        assert_eq!(borrowed.get(2), Test14::High);
+    }
+
+    #[test]
+    #[should_panic]
+    fn repr_invalid() {
+        use columnar::Index;
+        let test14c = Test14Container { variant: vec![1u16, 2] };
+        test14c.get(1);
+    }
+
+    // Tests derived implementations for fields of std sequence types other than `Vec`.
+    #[derive(Columnar, Debug)]
+    struct Test15 {
+        queue: std::collections::VecDeque<u64>,
+        heap: std::collections::BinaryHeap<String>,
+    }
+
+    #[test]
+    fn sequences() {
+
+        use columnar::{Container, Index};
+
+        let mut queue: std::collections::VecDeque<u64> = (0 .. 5).collect();
+        queue.rotate_left(2);
+        let heap = ["b", "c", "a"].iter().map(|s| s.to_string()).collect();
+        let test15c = Columnar::as_columns([Test15 { queue, heap }].iter());
+        let test15 = Test15::into_owned(Container::<Test15>::borrow(&test15c).get(0));
+        assert_eq!(test15.queue, [2, 3, 4, 0, 1]);
+        assert_eq!(test15.heap.into_sorted_vec(), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn inspect() {
+
+        use columnar::Index;
+        use columnar::path::{Inspect, Scalar};
+
+        let test1c = Columnar::as_columns([Test1 { foo: vec![1, 2, 3], bar: 4 }].iter());
+        let test1 = columnar::Container::<Test1<i32>>::borrow(&test1c).get(0);
+        assert_eq!(test1.scalar_at(&["foo", "2"]), Some(Scalar::Signed(3)));
+        assert_eq!(test1.scalar_at(&["bar"]), Some(Scalar::Signed(4)));
+        assert!(test1.get_path(&["baz"]).is_none());
+
+        let test3s: [Test3<i32>; 3] = [Test3::Foo(vec![1, 2, 3], 4), Test3::Bar(5), Test3::Void];
+        let test3c = Columnar::as_columns(test3s.iter());
+        let test3b = columnar::Container::<Test3<i32>>::borrow(&test3c);
+        assert_eq!(test3b.get(0).scalar_at(&["Foo", "0", "1"]), Some(Scalar::Signed(2)));
+        assert_eq!(test3b.get(0).scalar_at(&["Bar"]), None);
+        assert_eq!(test3b.get(1).scalar_at(&["Bar"]), Some(Scalar::Signed(5)));
+        assert_eq!(test3b.get(2).scalar_at(&["Void"]), Some(Scalar::Unit));
+    }
+
+    #[test]
+    fn layout() {
+
+        use columnar::Container;
+        use columnar::layout::footprint;
+
+        let test9c = Columnar::as_columns([Test9 { count: 1, name: "one".to_string() }].iter());
+        let roles = footprint(&Container::<Test9>::borrow(&test9c)).into_iter().map(|(role, _)| role).collect::<Vec<_>>();
+        assert_eq!(roles, ["name.bounds", "name.values", "count"]);
+
+        let test3c = Columnar::as_columns([Test3::Foo(vec![1, 2, 3], 4), Test3::Bar(5)].iter());
+        let roles = footprint(&Container::<Test3<i32>>::borrow(&test3c)).into_iter().map(|(role, _)| role).collect::<Vec<_>>();
+        assert_eq!(roles[0], "Foo.0.bounds");
+        assert!(roles.contains(&"Bar".to_string()));
+        assert_eq!(&roles[roles.len() - 2 ..], ["variant", "offset"]);
+    }
+
+    #[test]
+    fn transparent() {
+
+        use columnar::{Container, Index, Len};
+
+        let test6s: Vec<Test6> = (0 .. 10).map(Test6).collect();
+        let test6c = Columnar::as_columns(test6s.iter());
+        // The container wraps that of the field, and references are the field's.
+        let _: &Vec<u64> = &test6c.0;
+        let borrowed = Container::<Test6>::borrow(&test6c);
+        // Code generic over the field's references accepts the struct's.
+        fn field<'a>(item: <u64 as Columnar>::Ref<'a>) -> u64 { *item }
+        assert_eq!(field(borrowed.get(3)), 3);
+        assert_eq!(borrowed.get(3), &3);
+        assert_eq!(test6c.materialize(3), Test6(3));
+
+        let test7s: Vec<Test7> = (0 .. 10).map(|i| Test7 { name: format!("{}", i) }).collect();
+        let test7c = Columnar::as_columns(test7s.iter());
+        assert_eq!(test7c.len(), 10);
+        assert_eq!(Container::<Test7>::borrow(&test7c).get(5), "5");
+        assert_eq!(test7c.materialize(5), test7s[5]);
+    }
+
+    // Tests derived implementations for recursive structs, and for containers of them.
+    #[derive(Columnar, Debug, Default, Clone, PartialEq)]
+    struct Test16 { value: u64, children: Vec<Test16> }
+    #[derive(Columnar, Debug, PartialEq)]
+    struct Test17 { trees: Vec<Test16>, best: Option<Test16> }
+
+    #[test]
+    fn recursive() {
+
+        use columnar::{Container, ExtendFromRange, Index, Len, Permute, Push};
+
+        let leaf = |value| Test16 { value, children: vec![] };
+        let trees = vec![
+            Test16 { value: 1, children: vec![leaf(2), Test16 { value: 3, children: vec![leaf(4)] }] },
+            leaf(5),
+            Test16 { value: 6, children: vec![leaf(7)] },
+        ];
+
+        // Each tree is one row, whatever its number of nodes.
+        let mut treec = Columnar::as_columns(trees.iter());
+        assert_eq!(treec.len(), 3);
+        assert_eq!(treec.value.len(), 7);
+        for (index, tree) in trees.iter().enumerate() {
+            assert_eq!(treec.materialize(index), *tree);
+            assert_eq!(format!("{:?}", treec.borrow().get(index)), format!("{:?}", tree));
+        }
+        assert_eq!(format!("{:?}", treec.borrow().get(2)), "Test16 { value: 6, children: [Test16 { value: 7, children: [] }] }");
+        let mut copy = Test16::default();
+        copy.copy_from(treec.borrow().get(0));
+        assert_eq!(copy, trees[0]);
+
+        // References push as one row, with copies of their nodes.
+        let mut pushed = <Test16 as Columnar>::Container::default();
+        pushed.push(treec.borrow().get(0));
+        pushed.push(&trees[2]);
+        assert_eq!(pushed.len(), 2);
+        assert_eq!(pushed.materialize(0), trees[0]);
+        assert_eq!(pushed.materialize(1), trees[2]);
+
+        // Rows permute and extend as trees.
+        treec.permute(&[2, 0, 1]);
+        assert_eq!((0 .. 3).map(|index| treec.materialize(index)).collect::<Vec<_>>(), [trees[2].clone(), trees[0].clone(), trees[1].clone()]);
+        let mut extended = <Test16 as Columnar>::Container::default();
+        extended.extend_from_range(&treec, 1 .. 3);
+        assert_eq!(extended.len(), 2);
+        assert_eq!(extended.materialize(0), trees[0]);
+        assert_eq!(extended.materialize(1), trees[1]);
+
+        // Serialized containers read back with their roots.
+        let mut store = Vec::new();
+        columnar::bytes::serialization::encode(&mut store, columnar::AsBytes::as_bytes(&extended.borrow()));
+        let decoded = columnar::bytes::serialization::try_decode_container::<Test16>(&store).unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(Test16::into_owned(decoded.get(0)), trees[0]);
+
+        // Containers of recursive structs see one row for each tree.
+        let forests = vec![
+            Test17 { trees: trees.clone(), best: Some(trees[0].clone()) },
+            Test17 { trees: vec![], best: None },
+        ];
+        let forestc = Columnar::as_columns(forests.iter());
+        assert_eq!(forestc.len(), 2);
+        assert_eq!(forestc.trees.len(), 2);
+        assert_eq!(Container::<Vec<Test16>>::borrow(&forestc.trees).get(0).len(), 3);
+        assert_eq!(forestc.materialize(0), forests[0]);
+        assert_eq!(forestc.materialize(1), forests[1]);
+    }
+
+    #[test]
+    fn from_reference() {
+
+        use columnar::Index;
+
+        let test1s = [Test1 { foo: vec![1, 2, 3], bar: 4 }];
+        let test1c = Columnar::as_columns(test1s.iter());
+        let test1: Test1<i32> = columnar::Container::<Test1<i32>>::borrow(&test1c).get(0).into();
+        assert_eq!((test1.foo, test1.bar), (vec![1, 2, 3], 4));
+
+        let test3s: [Test3<i32>; 3] = [Test3::Foo(vec![1, 2, 3], 4), Test3::Bar(5), Test3::Void];
+        let test3c = Columnar::as_columns(test3s.iter());
+        let test3s: Vec<Test3<i32>> = (&test3c).into_iter().map(Test3::<i32>::from).collect();
+        assert!(matches!(&test3s[..], [Test3::Foo(foo, 4), Test3::Bar(5), Test3::Void] if foo == &[1, 2, 3]));
+
+        let test6c = Columnar::as_columns([Test6(3)].iter());
+        assert_eq!(Test6::from(columnar::Container::<Test6>::borrow(&test6c).get(0)), Test6(3));
+    }
+
     #[test]
     fn round_trip() {
 
@@ -260,6 +576,9 @@ mod test {
             assert_eq!(a.foo.len(), b.foo.len());
             assert_eq!(a.bar, *b.bar);
         }
+        let second = columnar::Container::<Test1<i32>>::borrow(&test1c).get(1);
+        assert_eq!(serde_json::to_string(&second).unwrap(), r#"{"foo":[5,6,7],"bar":8}"#);
+        assert_eq!(format!("{:?}", second), "Test1 { foo: [5, 6, 7], bar: 8 }");
 
         let test3s = vec![
             Test3::Foo(vec![1, 2, 3], 4),
@@ -268,6 +587,8 @@ mod test {
         let test3c = columnar::Columnar::as_columns(test3s.iter());
         
         println!("{:?}", test3c);
+        assert_eq!(format!("{:?}", columnar::Container::<Test3<i32>>::borrow(&test3c).get(0)), "Foo([1, 2, 3], 4)");
+        assert_eq!(format!("{:?}", columnar::Container::<Test3<i32>>::borrow(&test3c).get(1)), "Bar(4)");
 
         let iterc = (&test3c).into_iter();
 
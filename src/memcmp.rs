@@ -0,0 +1,184 @@
+//! Byte strings whose lexicographic order matches the order of the values they encode.
+//!
+//! Key-value stores like RocksDB order their keys as byte strings, and a row encoded with
+//! `Memcomparable` can be used as such a key directly, from the `Index` reference of a column
+//! without first forming an owned row. Integers are written big-endian with their sign bit
+//! flipped, floats have their bits adjusted so that negative values precede positive ones,
+//! byte strings are escaped and terminated so that prefixes precede their extensions, and
+//! tuples are the concatenation of their fields.
+//!
+//! The encoding is not self-describing, and is only comparable between values of the same type.
+
+use crate::{Index, Len, Slice};
+
+/// A value that can be encoded as a byte string preserving its order.
+///
+/// For values `a` and `b` of the same type, the encodings compare as `a.cmp(&b)` would.
+/// Floats are ordered as by `total_cmp`, so that `-0.0` precedes `0.0` and NaNs are ordered.
+pub trait Memcomparable {
+    /// Appends the encoding of `self` to `key`.
+    fn encode_key(&self, key: &mut Vec<u8>);
+}
+
+/// The encoding of `value`, as a new byte string.
+pub fn key<T: Memcomparable + ?Sized>(value: &T) -> Vec<u8> {
+    let mut key = Vec::new();
+    value.encode_key(&mut key);
+    key
+}
+
+impl<T: Memcomparable + ?Sized> Memcomparable for &T {
+    #[inline(always)] fn encode_key(&self, key: &mut Vec<u8>) { (**self).encode_key(key) }
+}
+
+macro_rules! implement_unsigned {
+    ($($index_type:ty),*) => { $(
+        impl Memcomparable for $index_type {
+            #[inline(always)] fn encode_key(&self, key: &mut Vec<u8>) { key.extend_from_slice(&self.to_be_bytes()) }
+        }
+    )* }
+}
+implement_unsigned!(u8, u16, u32, u64, u128);
+
+macro_rules! implement_signed {
+    ($($index_type:ty, $unsigned:ty),*) => { $(
+        impl Memcomparable for $index_type {
+            #[inline(always)] fn encode_key(&self, key: &mut Vec<u8>) {
+                // Flipping the sign bit places negative values before non-negative values.
+                let flipped = (*self as $unsigned) ^ (1 << (<$unsigned>::BITS - 1));
+                key.extend_from_slice(&flipped.to_be_bytes())
+            }
+        }
+    )* }
+}
+implement_signed!(i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+
+impl Memcomparable for usize {
+    #[inline(always)] fn encode_key(&self, key: &mut Vec<u8>) { (*self as u64).encode_key(key) }
+}
+impl Memcomparable for isize {
+    #[inline(always)] fn encode_key(&self, key: &mut Vec<u8>) { (*self as i64).encode_key(key) }
+}
+
+macro_rules! implement_float {
+    ($($index_type:ty, $unsigned:ty),*) => { $(
+        impl Memcomparable for $index_type {
+            #[inline(always)] fn encode_key(&self, key: &mut Vec<u8>) {
+                // Negative values have all bits flipped, reversing their order; others only the sign bit.
+                let bits = self.to_bits();
+                let sign: $unsigned = 1 << (<$unsigned>::BITS - 1);
+                let flipped = if bits & sign != 0 { !bits } else { bits ^ sign };
+                key.extend_from_slice(&flipped.to_be_bytes())
+            }
+        }
+    )* }
+}
+implement_float!(f32, u32, f64, u64);
+
+impl Memcomparable for bool {
+    #[inline(always)] fn encode_key(&self, key: &mut Vec<u8>) { key.push(*self as u8) }
+}
+impl Memcomparable for char {
+    #[inline(always)] fn encode_key(&self, key: &mut Vec<u8>) { (*self as u32).encode_key(key) }
+}
+impl Memcomparable for () {
+    #[inline(always)] fn encode_key(&self, _key: &mut Vec<u8>) { }
+}
+
+/// Bytes are written with each zero byte followed by `0xFF`, and terminated by `0x00 0x01`.
+///
+/// The terminator is less than any escaped or non-zero byte, so a byte string precedes its extensions.
+impl Memcomparable for [u8] {
+    fn encode_key(&self, key: &mut Vec<u8>) {
+        for byte in self.iter() {
+            key.push(*byte);
+            if *byte == 0 { key.push(0xFF); }
+        }
+        key.extend_from_slice(&[0x00, 0x01]);
+    }
+}
+impl Memcomparable for str {
+    #[inline(always)] fn encode_key(&self, key: &mut Vec<u8>) { self.as_bytes().encode_key(key) }
+}
+impl Memcomparable for String {
+    #[inline(always)] fn encode_key(&self, key: &mut Vec<u8>) { self.as_bytes().encode_key(key) }
+}
+
+/// `None` is written as `0x00`, and precedes `Some` written as `0x01` followed by its value.
+impl<T: Memcomparable> Memcomparable for Option<T> {
+    fn encode_key(&self, key: &mut Vec<u8>) {
+        match self {
+            None => key.push(0x00),
+            Some(value) => { key.push(0x01); value.encode_key(key); }
+        }
+    }
+}
+
+/// Each element is preceded by `0x01`, and the list is terminated by `0x00`.
+impl<S: Index> Memcomparable for Slice<S> where S::Ref: Memcomparable {
+    fn encode_key(&self, key: &mut Vec<u8>) {
+        for index in 0 .. Len::len(self) {
+            key.push(0x01);
+            self.get(index).encode_key(key);
+        }
+        key.push(0x00);
+    }
+}
+
+macro_rules! implement_tuple {
+    ($($name:ident)+) => (
+        #[allow(non_snake_case)]
+        impl<$($name: Memcomparable),*> Memcomparable for ($($name,)*) {
+            fn encode_key(&self, key: &mut Vec<u8>) {
+                let ($($name,)*) = self;
+                $($name.encode_key(key);)*
+            }
+        }
+    )
+}
+
+implement_tuple!(A);
+implement_tuple!(A B);
+implement_tuple!(A B C);
+implement_tuple!(A B C D);
+implement_tuple!(A B C D E);
+implement_tuple!(A B C D E F);
+implement_tuple!(A B C D E F G);
+implement_tuple!(A B C D E F G H);
+implement_tuple!(A B C D E F G H I);
+implement_tuple!(A B C D E F G H I J);
+
+#[cfg(test)]
+mod test {
+
+    use super::key;
+    use crate::{Columnar, Container, Index};
+
+    #[test]
+    fn order() {
+        let rows: Vec<(i32, String, Option<u8>, Vec<i64>)> = [-300, -1, 0, 1, 300].iter().flat_map(|i| {
+            ["", "a", "a\0", "ab", "b"].iter().flat_map(move |s| {
+                [None, Some(0), Some(7)].iter().flat_map(move |o| {
+                    [vec![], vec![-1], vec![-1, 0], vec![3]].into_iter().map(move |l| (*i, s.to_string(), *o, l))
+                })
+            })
+        }).collect();
+
+        let column = Columnar::as_columns(rows.iter());
+        let borrowed = Container::<(i32, String, Option<u8>, Vec<i64>)>::borrow(&column);
+        let keys: Vec<Vec<u8>> = (0 .. rows.len()).map(|index| key(&borrowed.get(index))).collect();
+        let (integer, string, option, _) = borrowed.get(7);
+        assert_eq!(key(&(integer, string, option)), key(&(rows[7].0, &rows[7].1, rows[7].2)));
+
+        let mut by_row: Vec<usize> = (0 .. rows.len()).collect();
+        by_row.sort_by(|a, b| rows[*a].cmp(&rows[*b]));
+        let mut by_key: Vec<usize> = (0 .. rows.len()).collect();
+        by_key.sort_by(|a, b| keys[*a].cmp(&keys[*b]));
+        assert_eq!(by_row, by_key);
+
+        let floats = [f64::NEG_INFINITY, -1.5, -0.0, 0.0, 1e-300, 2.0, f64::INFINITY, f64::NAN];
+        assert!(floats.windows(2).all(|pair| key(&pair[0]) < key(&pair[1])));
+        let floats = [f32::NEG_INFINITY, -1.5, -0.0, 0.0, 2.0, f32::INFINITY];
+        assert!(floats.windows(2).all(|pair| key(&pair[0]) < key(&pair[1])));
+    }
+}
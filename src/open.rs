@@ -0,0 +1,179 @@
+//! Columns of evolving enums, which preserve the rows of variants they do not know.
+//!
+//! Deriving `Columnar` for an enum with fields also implements `OpenEnum`, which tags each
+//! variant by its position in declaration order. Such an enum may evolve by appending variants.
+//! `encode` records the column of each variant separately, and `Opened<T>` decodes an encoding
+//! produced by any version of `T`: rows of the variants `T` knows are decoded into `T`'s container,
+//! and the columns of the variants it does not know are retained as opaque bytes, which
+//! `Opened::encode` writes back unchanged. A consumer built before a variant was added can then
+//! pass along the rows of that variant without loss.
+//!
+//! The layout, in `u64` words, is the number of variants, the number of byte slices in each
+//! variant's column, and then in the `bytes::serialization` layout the byte slices of each
+//! variant's column, the tag of each row as a byte, and the offset of each row among the rows
+//! of its variant.
+
+use crate::{AsBytes, Columnar, Container, FromBytes, Index, Len, Push};
+use crate::bytes::serialization;
+
+/// An enum whose variants are tagged by their positions in declaration order.
+pub trait OpenEnum: Columnar {
+    /// The number of byte slices in the column of each variant, in declaration order.
+    fn variant_slices() -> Vec<usize>;
+    /// The tag of the variant of `row`.
+    fn tag(row: &Self::Ref<'_>) -> u8;
+}
+
+/// Appends the encoding of `container`, in the layout that `Opened::decode` reads.
+pub fn encode<T: OpenEnum>(container: &T::Container, store: &mut Vec<u64>) {
+    let slices = T::variant_slices();
+    store.push(slices.len() as u64);
+    Extend::extend(store, slices.iter().map(|count| *count as u64));
+    serialization::encode(store, container.borrow().as_bytes());
+}
+
+/// A row of an open enum: either of a variant `T` knows, or of the variant with some other tag.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Open<R> {
+    /// A row of a variant that `T` knows.
+    Known(R),
+    /// A row of a variant that `T` does not know, with its tag.
+    Unknown(u8),
+}
+
+impl<R> Open<R> {
+    /// Applies `logic` to a known row.
+    pub fn map<S>(self, logic: impl FnOnce(R) -> S) -> Open<S> {
+        match self {
+            Open::Known(row) => Open::Known(logic(row)),
+            Open::Unknown(tag) => Open::Unknown(tag),
+        }
+    }
+}
+
+/// Rows of an enum `T`, including rows of variants that `T` does not know.
+pub struct Opened<T: OpenEnum> {
+    /// The number of variants of `T`.
+    variants: usize,
+    /// The rows of variants that `T` knows, in order.
+    known: T::Container,
+    /// The tag of each row.
+    tags: Vec<u8>,
+    /// For each row, its index in `known` if `T` knows its variant, and otherwise its offset
+    /// among the rows of its variant.
+    ranks: Vec<u64>,
+    /// The byte slices of the columns of variants that `T` does not know, in order of tag.
+    unknown: Vec<Vec<Vec<u8>>>,
+}
+
+impl<T: OpenEnum> Default for Opened<T> {
+    fn default() -> Self {
+        Self {
+            variants: T::variant_slices().len(),
+            known: Default::default(),
+            tags: Vec::new(),
+            ranks: Vec::new(),
+            unknown: Vec::new(),
+        }
+    }
+}
+
+impl<T: OpenEnum> Opened<T> {
+    /// The rows of variants that `T` knows, in order.
+    pub fn known(&self) -> &T::Container { &self.known }
+    /// The tag of the variant of row `index`.
+    pub fn tag(&self, index: usize) -> u8 { self.tags[index] }
+
+    /// Decodes an encoding produced by `encode` or `Opened::encode`, for any version of `T`.
+    ///
+    /// Panics if a variant that `T` knows has a different number of byte slices in the encoding,
+    /// which indicates that the variant's fields have changed.
+    pub fn decode(store: &[u64]) -> Self {
+        let count = store[0] as usize;
+        let mut slices = serialization::decode(&store[1 + count ..]);
+        let columns: Vec<Vec<&[u8]>> = store[1 .. 1 + count].iter().map(|length| slices.by_ref().take(*length as usize).collect()).collect();
+        let tags: &[u8] = slices.next().unwrap();
+        let offsets: &[u64] = bytemuck::try_cast_slice(slices.next().unwrap()).unwrap();
+
+        // Columns of known variants absent from the encoding are empty.
+        let empty = T::Container::default();
+        let empty = empty.borrow();
+        let mut defaults = empty.as_bytes().map(|(_, bytes)| bytes);
+        let known_slices = T::variant_slices();
+        let variants = known_slices.len();
+        let mut known_columns = Vec::new();
+        for (tag, length) in known_slices.iter().enumerate() {
+            let default = defaults.by_ref().take(*length).collect::<Vec<_>>();
+            match <[Vec<&[u8]>]>::get(&columns, tag) {
+                Some(column) => {
+                    assert_eq!(column.len(), *length, "variant {} has a different number of columns", tag);
+                    Extend::extend(&mut known_columns, column.iter().copied());
+                }
+                None => Extend::extend(&mut known_columns, default),
+            }
+        }
+
+        // Present the rows of known variants as a container of `T`, and copy them out.
+        let is_known = |tag: &u8| (*tag as usize) < variants;
+        let known_tags = tags.iter().copied().filter(is_known).collect::<Vec<_>>();
+        let known_offsets = tags.iter().zip(offsets).filter(|(tag, _)| is_known(tag)).map(|(_, offset)| *offset).collect::<Vec<_>>();
+        let mut bytes = IntoIterator::into_iter(known_columns).chain([&known_tags[..], bytemuck::cast_slice(&known_offsets[..])]);
+        let borrowed = <<T::Container as Container<T>>::Borrowed<'_> as FromBytes>::from_bytes(&mut bytes);
+        let mut known = T::Container::default();
+        for index in 0 .. borrowed.len() {
+            known.push(borrowed.get(index));
+        }
+
+        let mut rank = 0;
+        let ranks = tags.iter().zip(offsets).map(|(tag, offset)| {
+            if is_known(tag) { rank += 1; rank - 1 } else { *offset }
+        }).collect();
+        let unknown = columns.iter().skip(variants).map(|column| column.iter().map(|bytes| bytes.to_vec()).collect()).collect();
+
+        Self { variants, known, tags: tags.to_vec(), ranks, unknown }
+    }
+
+    /// Appends the encoding of `self`, including the columns of unknown variants as they were decoded.
+    pub fn encode(&self, store: &mut Vec<u64>) {
+        let known_slices = T::variant_slices();
+        store.push((self.variants + self.unknown.len()) as u64);
+        Extend::extend(store, known_slices.iter().map(|count| *count as u64));
+        Extend::extend(store, self.unknown.iter().map(|column| column.len() as u64));
+
+        let borrowed = self.known.borrow();
+        let mut known_bytes = borrowed.as_bytes();
+        let known_columns = known_bytes.by_ref().take(known_slices.iter().sum()).collect::<Vec<_>>();
+        let _tags = known_bytes.next();
+        let known_offsets: &[u64] = bytemuck::try_cast_slice(known_bytes.next().unwrap().1).unwrap();
+        let offsets = self.tags.iter().zip(self.ranks.iter()).map(|(tag, rank)| {
+            if (*tag as usize) < self.variants { known_offsets[*rank as usize] } else { *rank }
+        }).collect::<Vec<u64>>();
+
+        serialization::encode(store, IntoIterator::into_iter(known_columns));
+        serialization::encode(store, self.unknown.iter().flatten().map(|bytes| (8, &bytes[..])));
+        serialization::encode(store, [(1, &self.tags[..]), (8, bytemuck::cast_slice(&offsets[..]))].into_iter());
+    }
+}
+
+impl<T: OpenEnum> Len for Opened<T> {
+    #[inline(always)] fn len(&self) -> usize { self.tags.len() }
+}
+impl<'a, T: OpenEnum> Index for &'a Opened<T> {
+    type Ref = Open<T::Ref<'a>>;
+    #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+        let tag = self.tags[index];
+        if (tag as usize) < self.variants {
+            Open::Known(self.known.borrow().get(self.ranks[index] as usize))
+        } else {
+            Open::Unknown(tag)
+        }
+    }
+}
+impl<'a, T: OpenEnum> Push<&'a T> for Opened<T> {
+    fn push(&mut self, item: &'a T) {
+        self.known.push(item);
+        let rank = self.known.len() - 1;
+        self.tags.push(T::tag(&self.known.borrow().get(rank)));
+        self.ranks.push(rank as u64);
+    }
+}
@@ -0,0 +1,172 @@
+//! Access to the components of rows by path, for code that does not know the row type.
+//!
+//! A path is a sequence of names, each a struct field name, a tuple position, a list index,
+//! or an enum variant name. `Inspect` is implemented for the references that containers
+//! present, and by `#[derive(Columnar)]` for the references of derived structs and enums,
+//! so that for example `row.get_path(&["payload", "tags", "0"])` finds the first tag of a
+//! row's payload. Components are boxed as they are visited, and leaves are read as `Scalar`s.
+
+/// A leaf value reached by a path.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Scalar<'a> {
+    /// An absent optional value.
+    Null,
+    /// The unit value, as for enum variants without fields.
+    Unit,
+    /// A boolean.
+    Bool(bool),
+    /// An unsigned integer.
+    Unsigned(u64),
+    /// A signed integer.
+    Signed(i64),
+    /// A floating point number.
+    Float(f64),
+    /// A character.
+    Char(char),
+    /// A string.
+    Str(&'a str),
+    /// A byte string.
+    Bytes(&'a [u8]),
+}
+
+/// A value whose components can be reached by name.
+pub trait Inspect<'a> {
+    /// The component named `name`, if there is one.
+    fn field(&self, _name: &str) -> Option<Box<dyn Inspect<'a> + 'a>> { None }
+    /// The value of `self`, if it is a leaf.
+    fn scalar(&self) -> Option<Scalar<'a>> { None }
+
+    /// The component reached by following `path`, which must not be empty.
+    fn get_path(&self, path: &[&str]) -> Option<Box<dyn Inspect<'a> + 'a>> {
+        let (first, rest) = path.split_first()?;
+        let mut current = self.field(first)?;
+        for name in rest {
+            current = current.field(name)?;
+        }
+        Some(current)
+    }
+    /// The leaf value reached by following `path`, which may be empty to read `self`.
+    fn scalar_at(&self, path: &[&str]) -> Option<Scalar<'a>> {
+        if path.is_empty() { self.scalar() } else { self.get_path(path)?.scalar() }
+    }
+}
+
+macro_rules! implement_scalar {
+    ($variant:ident, $($index_type:ty),*) => { $(
+        impl<'a> Inspect<'a> for $index_type {
+            #[inline(always)] fn scalar(&self) -> Option<Scalar<'a>> { Some(Scalar::$variant((*self).into())) }
+        }
+        impl<'a> Inspect<'a> for &'a $index_type {
+            #[inline(always)] fn scalar(&self) -> Option<Scalar<'a>> { Some(Scalar::$variant((**self).into())) }
+        }
+    )* }
+}
+implement_scalar!(Unsigned, u8, u16, u32, u64);
+implement_scalar!(Signed, i8, i16, i32, i64);
+implement_scalar!(Float, f32, f64);
+implement_scalar!(Bool, bool);
+implement_scalar!(Char, char);
+
+impl<'a> Inspect<'a> for usize {
+    #[inline(always)] fn scalar(&self) -> Option<Scalar<'a>> { Some(Scalar::Unsigned(*self as u64)) }
+}
+impl<'a> Inspect<'a> for isize {
+    #[inline(always)] fn scalar(&self) -> Option<Scalar<'a>> { Some(Scalar::Signed(*self as i64)) }
+}
+impl<'a> Inspect<'a> for () {
+    #[inline(always)] fn scalar(&self) -> Option<Scalar<'a>> { Some(Scalar::Unit) }
+}
+impl<'a> Inspect<'a> for &'a str {
+    #[inline(always)] fn scalar(&self) -> Option<Scalar<'a>> { Some(Scalar::Str(self)) }
+}
+impl<'a> Inspect<'a> for &'a [u8] {
+    #[inline(always)] fn scalar(&self) -> Option<Scalar<'a>> { Some(Scalar::Bytes(self)) }
+}
+
+/// Absent values are the `Null` scalar, and present values have the components of their value.
+impl<'a, T: Inspect<'a>> Inspect<'a> for Option<T> {
+    fn field(&self, name: &str) -> Option<Box<dyn Inspect<'a> + 'a>> { self.as_ref()?.field(name) }
+    fn scalar(&self) -> Option<Scalar<'a>> {
+        match self {
+            Some(value) => value.scalar(),
+            None => Some(Scalar::Null),
+        }
+    }
+}
+
+/// Results have a component named `Ok` or `Err`, according to their variant.
+impl<'a, S: Inspect<'a> + Copy + 'a, T: Inspect<'a> + Copy + 'a> Inspect<'a> for Result<S, T> {
+    fn field(&self, name: &str) -> Option<Box<dyn Inspect<'a> + 'a>> {
+        match (self, name) {
+            (Ok(value), "Ok") => Some(Box::new(*value)),
+            (Err(value), "Err") => Some(Box::new(*value)),
+            _ => None,
+        }
+    }
+}
+
+/// Lists have components named by their positions.
+impl<'a, S: crate::Index + Copy + 'a> Inspect<'a> for crate::Slice<S> where S::Ref: Inspect<'a> + 'a {
+    fn field(&self, name: &str) -> Option<Box<dyn Inspect<'a> + 'a>> {
+        let index: usize = name.parse().ok()?;
+        if index < crate::Len::len(self) { Some(Box::new(crate::Index::get(self, index))) } else { None }
+    }
+}
+
+macro_rules! implement_tuple {
+    ($($name:ident $index:tt)+) => (
+        /// Tuples have components named by their positions.
+        impl<'a, $($name: Inspect<'a> + Copy + 'a),*> Inspect<'a> for ($($name,)*) {
+            fn field(&self, name: &str) -> Option<Box<dyn Inspect<'a> + 'a>> {
+                match name {
+                    $( stringify!($index) => Some(Box::new(self.$index)), )*
+                    _ => None,
+                }
+            }
+        }
+    )
+}
+
+implement_tuple!(A 0);
+implement_tuple!(A 0 B 1);
+implement_tuple!(A 0 B 1 C 2);
+implement_tuple!(A 0 B 1 C 2 D 3);
+implement_tuple!(A 0 B 1 C 2 D 3 E 4);
+implement_tuple!(A 0 B 1 C 2 D 3 E 4 F 5);
+implement_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6);
+implement_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7);
+implement_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8);
+implement_tuple!(A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8 J 9);
+
+#[cfg(test)]
+mod test {
+
+    use crate::{Columnar, Container, Index};
+    use super::{Inspect, Scalar};
+
+    type Row = (u64, Option<(String, Vec<i32>)>, Result<u8, bool>);
+
+    #[test]
+    fn paths() {
+        let rows: Vec<Row> = vec![
+            (7, Some(("seven".to_string(), vec![1, -2, 3])), Ok(5)),
+            (8, None, Err(true)),
+        ];
+        let column = Columnar::as_columns(rows.iter());
+        let borrowed = Container::<Row>::borrow(&column);
+
+        let row = borrowed.get(0);
+        assert_eq!(row.scalar_at(&["0"]), Some(Scalar::Unsigned(7)));
+        assert_eq!(row.scalar_at(&["1", "0"]), Some(Scalar::Str("seven")));
+        assert_eq!(row.scalar_at(&["1", "1", "1"]), Some(Scalar::Signed(-2)));
+        assert_eq!(row.scalar_at(&["1", "1", "3"]), None);
+        assert_eq!(row.scalar_at(&["2", "Ok"]), Some(Scalar::Unsigned(5)));
+        assert_eq!(row.scalar_at(&["2", "Err"]), None);
+        assert!(row.get_path(&["1", "1"]).unwrap().scalar().is_none());
+
+        let row = borrowed.get(1);
+        assert_eq!(row.scalar_at(&["1"]), Some(Scalar::Null));
+        assert_eq!(row.scalar_at(&["1", "0"]), None);
+        assert_eq!(row.scalar_at(&["2", "Err"]), Some(Scalar::Bool(true)));
+    }
+}
@@ -0,0 +1,114 @@
+//! Views of columns of plain-old-data primitives as bytes.
+//!
+//! The columns of integer and floating point types are slices of those types, whose bytes
+//! `bytemuck` can view without copying and without unsafe code. `PodColumn` exposes these views,
+//! so that serialization and checksumming can treat a column as `&[u8]`, and so that a column
+//! can be read back from bytes, or extended from them by a single copy. Single-field structs
+//! marked `#[columnar(transparent)]` use the column of their field, and so have the same views.
+//! If such a struct is also `#[repr(transparent)]` and `bytemuck::Pod`, `copy_slice` appends
+//! a slice of it to the column of its field with a single copy, rather than a push per value.
+//!
+//! This module is available with the `bytemuck` feature.
+
+use bytemuck::{Pod, PodCastError};
+
+/// A column of plain-old-data values, viewed as bytes.
+pub trait PodColumn {
+    /// The bytes of the values, in order, in native byte order.
+    fn as_byte_slice(&self) -> &[u8];
+    /// The values whose bytes are `bytes`, without copying.
+    ///
+    /// Fails if `bytes` is not aligned for the values, or its length is not a multiple of their size.
+    fn from_byte_slice(bytes: &[u8]) -> Result<&Self, PodCastError>;
+}
+
+/// An owned column of plain-old-data values that can be extended from bytes.
+pub trait PodColumnMut {
+    /// The type of the values.
+    type Item: Pod;
+    /// Appends the values whose bytes are `bytes`, with a single copy.
+    ///
+    /// The bytes need not be aligned, but their length must be a multiple of the size of the values.
+    fn extend_from_byte_slice(&mut self, bytes: &[u8]) -> Result<(), PodCastError>;
+}
+
+macro_rules! implement_pod {
+    ($($index_type:ty),*) => { $(
+        impl PodColumn for [$index_type] {
+            #[inline(always)] fn as_byte_slice(&self) -> &[u8] { bytemuck::cast_slice(self) }
+            #[inline(always)] fn from_byte_slice(bytes: &[u8]) -> Result<&Self, PodCastError> { bytemuck::try_cast_slice(bytes) }
+        }
+        impl PodColumnMut for Vec<$index_type> {
+            type Item = $index_type;
+            fn extend_from_byte_slice(&mut self, bytes: &[u8]) -> Result<(), PodCastError> {
+                let size = std::mem::size_of::<$index_type>();
+                if !bytes.len().is_multiple_of(size) { return Err(PodCastError::OutputSliceWouldHaveSlop); }
+                let start = self.len();
+                self.resize(start + bytes.len() / size, <$index_type>::default());
+                bytemuck::cast_slice_mut::<$index_type, u8>(&mut self[start ..]).copy_from_slice(bytes);
+                Ok(())
+            }
+        }
+    )* }
+}
+
+/// Appends `items` to `column`, with a single copy of their bytes.
+///
+/// Each item must have the representation of a value of the column, as a `#[repr(transparent)]`
+/// wrapper around it does. The container derived for a `#[columnar(transparent)]` struct wraps the
+/// column of its field as `.0`, so `copy_slice(&mut container.0, items)` appends `items` to it.
+///
+/// Fails, leaving the column unchanged, if the items and the values have different sizes.
+pub fn copy_slice<C: PodColumnMut, T: Pod>(column: &mut C, items: &[T]) -> Result<(), PodCastError> {
+    if std::mem::size_of::<T>() != std::mem::size_of::<C::Item>() { return Err(PodCastError::SizeMismatch); }
+    column.extend_from_byte_slice(bytemuck::cast_slice(items))
+}
+
+implement_pod!(u8, u16, u32, u64, u128);
+implement_pod!(i8, i16, i32, i64, i128);
+implement_pod!(f32, f64);
+
+#[cfg(test)]
+mod test {
+
+    use bytemuck::PodCastError;
+    use crate::{Columnar, Container};
+    use super::{PodColumn, PodColumnMut, copy_slice};
+
+    #[test]
+    fn views() {
+        let values: Vec<u32> = (0 .. 100).collect();
+        let column = Columnar::as_columns(values.iter());
+        let bytes = Container::<u32>::borrow(&column).as_byte_slice();
+        assert_eq!(bytes.len(), 400);
+        assert_eq!(<[u32]>::from_byte_slice(bytes), Ok(&values[..]));
+        assert_eq!(<[u32]>::from_byte_slice(&bytes[.. 6]), Err(PodCastError::OutputSliceWouldHaveSlop));
+
+        // Extending copies unaligned bytes.
+        let mut floats = vec![0.5f64];
+        let bytes = [1.5f64, 2.5].as_byte_slice().to_vec();
+        floats.extend_from_byte_slice(&[[0u8].as_slice(), &bytes].concat()[1 ..]).unwrap();
+        assert_eq!(floats, [0.5, 1.5, 2.5]);
+        assert_eq!(floats.extend_from_byte_slice(&bytes[.. 9]), Err(PodCastError::OutputSliceWouldHaveSlop));
+    }
+
+    /// A transparent newtype, as `#[columnar(transparent)]` structs often are.
+    #[derive(Copy, Clone, Debug, PartialEq)]
+    #[repr(transparent)]
+    struct Meters(f64);
+    // SAFETY: `Meters` is `#[repr(transparent)]` around `f64`, which is `Pod`.
+    unsafe impl bytemuck::Zeroable for Meters { }
+    unsafe impl bytemuck::Pod for Meters { }
+
+    #[test]
+    fn copy_slices() {
+        let mut column = vec![0.5f64];
+        let items = [Meters(1.5), Meters(2.5)];
+        copy_slice(&mut column, &items).unwrap();
+        assert_eq!(column, [0.5, 1.5, 2.5]);
+        // Items of another size are not copied.
+        assert_eq!(copy_slice(&mut column, &[1u32, 2]), Err(PodCastError::SizeMismatch));
+        assert_eq!(copy_slice(&mut vec![0u16], &items), Err(PodCastError::SizeMismatch));
+        assert_eq!(column.len(), 3);
+    }
+}
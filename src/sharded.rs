@@ -0,0 +1,92 @@
+//! Builders that accept rows from several threads at once, without synchronizing each push.
+//!
+//! A `ShardedBuilder` holds one container for each shard, and hands out an `Appender` for each,
+//! which can be moved to its own thread (for example with `std::thread::scope`). Each appender
+//! pushes to its own shard, so pushes need no lock. Finishing concatenates the shards in shard
+//! order, so the result depends only on which rows each shard received, not on thread timing.
+
+use crate::{ExtendFromRange, Len, Push};
+
+/// A builder of `C` that collects rows in one container per shard.
+#[derive(Clone, Debug)]
+pub struct ShardedBuilder<C> {
+    shards: Vec<C>,
+}
+
+impl<C: Default> ShardedBuilder<C> {
+    /// A builder with `shards` empty shards.
+    pub fn new(shards: usize) -> Self {
+        Self { shards: (0 .. shards).map(|_| C::default()).collect() }
+    }
+}
+
+impl<C> ShardedBuilder<C> {
+    /// Appenders to each shard, in shard order.
+    pub fn appenders(&mut self) -> Vec<Appender<'_, C>> {
+        self.shards.iter_mut().enumerate().map(|(shard, rows)| Appender { shard, rows }).collect()
+    }
+    /// The rows of each shard, in shard order.
+    pub fn shards(&self) -> &[C] { &self.shards }
+
+    /// Concatenates the rows of the shards, in shard order.
+    pub fn finish(self) -> C where C: Default + ExtendFromRange + Len {
+        let mut shards = self.shards.into_iter();
+        let mut result = shards.next().unwrap_or_default();
+        for shard in shards {
+            result.extend_from_range(&shard, 0 .. shard.len());
+        }
+        result
+    }
+}
+
+/// Pushes rows to one shard of a `ShardedBuilder`.
+#[derive(Debug)]
+pub struct Appender<'a, C> {
+    shard: usize,
+    rows: &'a mut C,
+}
+
+impl<C> Appender<'_, C> {
+    /// The index of the shard this appender pushes to.
+    pub fn shard(&self) -> usize { self.shard }
+}
+
+impl<T, C: Push<T>> Push<T> for Appender<'_, C> {
+    #[inline(always)] fn push(&mut self, item: T) { self.rows.push(item) }
+    #[inline(always)] fn extend(&mut self, iter: impl IntoIterator<Item=T>) { self.rows.extend(iter) }
+}
+impl<C: Len> Len for Appender<'_, C> {
+    #[inline(always)] fn len(&self) -> usize { self.rows.len() }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::{Columnar, Container, Index, Len, Push};
+    use super::ShardedBuilder;
+
+    #[test]
+    fn sharded() {
+        type Row = (u64, String);
+        let mut builder = ShardedBuilder::<<Row as Columnar>::Container>::new(4);
+        std::thread::scope(|scope| {
+            for mut appender in builder.appenders() {
+                scope.spawn(move || {
+                    for i in 0 .. 100u64 {
+                        let shard = appender.shard() as u64;
+                        appender.push(&(shard, format!("{}", i)));
+                    }
+                    assert_eq!(appender.len(), 100);
+                });
+            }
+        });
+        assert!(builder.shards().iter().all(|shard| shard.len() == 100));
+
+        let rows = builder.finish();
+        let borrowed = Container::<Row>::borrow(&rows);
+        assert_eq!(borrowed.len(), 400);
+        for index in 0 .. 400 {
+            assert_eq!(borrowed.get(index), (&(index as u64 / 100), format!("{}", index % 100).as_str()));
+        }
+    }
+}
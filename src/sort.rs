@@ -0,0 +1,937 @@
+//! Sorting rows of containers.
+//!
+//! Rows are rarely moved while sorting. Instead, we produce a permutation of row
+//! indices, which can be applied with `Permute::permute` or `Container::take`.
+
+use std::cmp::Ordering;
+
+use crate::{Columnar, Container, Index, Len, Permute, Push};
+
+/// The direction in which a sort key orders rows.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Order {
+    /// Lesser values precede greater values.
+    #[default]
+    Ascending,
+    /// Greater values precede lesser values.
+    Descending,
+}
+
+impl Order {
+    /// Orients an ascending `ordering` to this direction.
+    #[inline(always)] pub fn apply(self, ordering: Ordering) -> Ordering {
+        match self {
+            Order::Ascending => ordering,
+            Order::Descending => ordering.reverse(),
+        }
+    }
+}
+
+/// Containers whose component columns can be compared row against row.
+///
+/// This is implemented for tuples of indexable columns and for derived struct
+/// containers, where the columns are numbered in field order.
+pub trait CompareColumns {
+    /// The number of component columns.
+    fn columns(&self) -> usize;
+    /// Compares the values of rows `i` and `j` in component column `column`.
+    fn compare_column(&self, column: usize, i: usize, j: usize) -> Ordering;
+}
+
+macro_rules! compare_columns_impl {
+    ( $count:expr; $($name:ident $index:tt)+ ) => (
+        impl<$($name: Index),*> CompareColumns for ($($name,)*) where $($name::Ref: Ord),* {
+            #[inline(always)] fn columns(&self) -> usize { $count }
+            fn compare_column(&self, column: usize, i: usize, j: usize) -> Ordering {
+                match column {
+                    $( $index => self.$index.get(i).cmp(&self.$index.get(j)), )*
+                    _ => panic!("column {} out of bounds for {} columns", column, $count),
+                }
+            }
+        }
+    )
+}
+
+compare_columns_impl!(1; A 0);
+compare_columns_impl!(2; A 0 B 1);
+compare_columns_impl!(3; A 0 B 1 C 2);
+compare_columns_impl!(4; A 0 B 1 C 2 D 3);
+compare_columns_impl!(5; A 0 B 1 C 2 D 3 E 4);
+compare_columns_impl!(6; A 0 B 1 C 2 D 3 E 4 F 5);
+compare_columns_impl!(7; A 0 B 1 C 2 D 3 E 4 F 5 G 6);
+compare_columns_impl!(8; A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7);
+compare_columns_impl!(9; A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8);
+compare_columns_impl!(10; A 0 B 1 C 2 D 3 E 4 F 5 G 6 H 7 I 8 J 9);
+
+/// Compares rows `i` and `j` lexicographically by `keys`, each a column and a direction.
+pub fn compare_rows<C: CompareColumns>(columns: &C, keys: &[(usize, Order)], i: usize, j: usize) -> Ordering {
+    for (column, order) in keys {
+        match order.apply(columns.compare_column(*column, i, j)) {
+            Ordering::Equal => { },
+            not_equal => return not_equal,
+        }
+    }
+    Ordering::Equal
+}
+
+/// The permutation that sorts rows lexicographically by `keys`.
+///
+/// The sort is stable, so rows that compare equal under `keys` retain their relative order.
+pub fn permutation<C: CompareColumns + Len>(columns: &C, keys: &[(usize, Order)]) -> Vec<usize> {
+    for (column, _) in keys {
+        assert!(*column < columns.columns(), "column {} out of bounds for {} columns", column, columns.columns());
+    }
+    let mut perm: Vec<usize> = (0 .. columns.len()).collect();
+    perm.sort_by(|i, j| compare_rows(columns, keys, *i, *j));
+    perm
+}
+
+/// Sorts the rows of `container` lexicographically by `keys`, in place.
+///
+/// Each key names a component column, by position, and the direction to order it.
+/// For example, `&[(1, Order::Descending), (0, Order::Ascending)]` sorts by the second
+/// column from greatest to least, breaking ties by the first column from least to greatest.
+pub fn sort_rows_by_keys<T: Columnar>(container: &mut T::Container, keys: &[(usize, Order)])
+where
+    T::Container: Permute,
+    for<'a> <T::Container as Container<T>>::Borrowed<'a>: CompareColumns,
+{
+    let perm = permutation(&container.borrow(), keys);
+    container.permute(&perm);
+}
+
+/// How strings compare, for sorting and grouping.
+///
+/// Locale-aware collation, as by ICU, is not provided; columns needing it can be sorted by
+/// keys computed elsewhere and stored as a `Blob` column.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Collation {
+    /// Strings compare by their bytes, as `str::cmp` does.
+    #[default]
+    Binary,
+    /// Strings compare by their bytes with ASCII letters lowercased.
+    AsciiCaseInsensitive,
+    /// Strings compare by their characters with each mapped to its lowercase form.
+    ///
+    /// This uses `char::to_lowercase`, which agrees with Unicode case folding except for a few
+    /// characters, like `ß`, whose folding expands them.
+    CaseFold,
+}
+
+impl Collation {
+    /// Compares `a` and `b` under this collation.
+    pub fn compare(self, a: &str, b: &str) -> Ordering {
+        match self {
+            Collation::Binary => a.cmp(b),
+            Collation::AsciiCaseInsensitive => {
+                a.bytes().map(|byte| byte.to_ascii_lowercase()).cmp(b.bytes().map(|byte| byte.to_ascii_lowercase()))
+            },
+            Collation::CaseFold => a.chars().flat_map(char::to_lowercase).cmp(b.chars().flat_map(char::to_lowercase)),
+        }
+    }
+    /// Appends a key for `text` whose byte order is this collation's order.
+    ///
+    /// The key is the `Memcomparable` encoding of the text with letters lowercased as the
+    /// collation requires, and can be combined with other keys from `crate::memcmp`.
+    pub fn encode_key(self, text: &str, key: &mut Vec<u8>) {
+        use crate::memcmp::Memcomparable;
+        match self {
+            Collation::Binary => text.encode_key(key),
+            Collation::AsciiCaseInsensitive => text.to_ascii_lowercase().encode_key(key),
+            Collation::CaseFold => text.chars().flat_map(char::to_lowercase).collect::<String>().encode_key(key),
+        }
+    }
+    /// Hashes `text` so that texts that compare equal under this collation hash equally.
+    ///
+    /// Like `str`'s hash, this ends with a byte that cannot occur in UTF-8, so that sequences
+    /// of texts hash differently when they split the same characters differently.
+    pub fn hash<H: std::hash::Hasher>(self, text: &str, state: &mut H) {
+        use std::hash::Hash;
+        match self {
+            Collation::Binary => text.hash(state),
+            Collation::AsciiCaseInsensitive => {
+                text.bytes().for_each(|byte| byte.to_ascii_lowercase().hash(state));
+                state.write_u8(0xff);
+            },
+            Collation::CaseFold => {
+                text.chars().flat_map(char::to_lowercase).for_each(|c| c.hash(state));
+                state.write_u8(0xff);
+            },
+        }
+    }
+}
+
+/// A string column whose rows compare under a `Collation`.
+///
+/// Rows are indexed as `Collated<&str>`, which implements `Ord`, `Eq`, and `Hash` by the
+/// collation, so the column can be a component of the tuples given to `permutation`, or
+/// have its rows used as keys for grouping.
+#[derive(Copy, Clone, Debug)]
+pub struct Collated<S> {
+    /// The strings, or string column.
+    pub inner: S,
+    /// The collation by which they compare.
+    pub collation: Collation,
+}
+
+impl<S: Len> Len for Collated<S> {
+    #[inline(always)] fn len(&self) -> usize { self.inner.len() }
+}
+impl<'a, S: Index<Ref = &'a str>> Index for Collated<S> {
+    type Ref = Collated<&'a str>;
+    #[inline(always)] fn get(&self, index: usize) -> Self::Ref {
+        Collated { inner: self.inner.get(index), collation: self.collation }
+    }
+}
+
+impl PartialEq for Collated<&str> {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+impl Eq for Collated<&str> { }
+impl PartialOrd for Collated<&str> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+impl Ord for Collated<&str> {
+    fn cmp(&self, other: &Self) -> Ordering { self.collation.compare(self.inner, other.inner) }
+}
+impl std::hash::Hash for Collated<&str> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) { self.collation.hash(self.inner, state) }
+}
+
+/// Fixed-width keys that can be sorted by a least-significant-digit radix sort.
+///
+/// The key is presented as a sequence of bytes, whose lexicographic order from most
+/// to least significant byte must match the order of the keys themselves.
+pub trait RadixKey: Copy {
+    /// The number of bytes in the key.
+    const BYTES: usize;
+    /// The byte at `position`, where position zero is the least significant.
+    fn radix_byte(self, position: usize) -> u8;
+}
+
+macro_rules! radix_key_unsigned {
+    ($($index_type:ty),*) => { $(
+        impl RadixKey for $index_type {
+            const BYTES: usize = std::mem::size_of::<$index_type>();
+            #[inline(always)] fn radix_byte(self, position: usize) -> u8 { (self >> (8 * position)) as u8 }
+        }
+    )* }
+}
+radix_key_unsigned!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! radix_key_signed {
+    ($($index_type:ty, $unsigned:ty),*) => { $(
+        // Flipping the sign bit moves negative values below non-negative values.
+        impl RadixKey for $index_type {
+            const BYTES: usize = std::mem::size_of::<$index_type>();
+            #[inline(always)] fn radix_byte(self, position: usize) -> u8 {
+                ((self as $unsigned) ^ (1 << (8 * Self::BYTES - 1))).radix_byte(position)
+            }
+        }
+    )* }
+}
+radix_key_signed!(i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, isize, usize);
+
+impl<const N: usize> RadixKey for [u8; N] {
+    const BYTES: usize = N;
+    #[inline(always)] fn radix_byte(self, position: usize) -> u8 { self[N - 1 - position] }
+}
+
+/// The permutation that sorts `keys` in ascending order, computed by radix sort.
+///
+/// The sort is stable, and passes over bytes that are identical for all keys are skipped.
+/// This is generally much faster than `permutation` for large columns of integer keys.
+pub fn radix_permutation<K: RadixKey>(keys: &[K]) -> Vec<usize> {
+    let mut perm: Vec<usize> = (0 .. keys.len()).collect();
+    let mut next = vec![0; keys.len()];
+    for position in 0 .. K::BYTES {
+        let mut counts = [0usize; 256];
+        for key in keys {
+            counts[key.radix_byte(position) as usize] += 1;
+        }
+        // If all keys share this byte, the pass would not change the order.
+        if counts.contains(&keys.len()) { continue; }
+        let mut offsets = [0usize; 256];
+        for byte in 1 .. 256 {
+            offsets[byte] = offsets[byte - 1] + counts[byte - 1];
+        }
+        for index in perm.iter() {
+            let byte = keys[*index].radix_byte(position) as usize;
+            next[offsets[byte]] = *index;
+            offsets[byte] += 1;
+        }
+        std::mem::swap(&mut perm, &mut next);
+    }
+    perm
+}
+
+/// Consolidates a container of `(data, time, diff)` updates.
+///
+/// Updates are sorted by `(data, time)`, the diffs of updates with equal data and time
+/// are accumulated, and updates whose accumulated diff is zero are removed. The result
+/// has at most one update for each `(data, time)`, in sorted order.
+pub fn consolidate<D, T, R>(updates: &mut <(D, T, R) as Columnar>::Container)
+where
+    D: Columnar,
+    T: Columnar,
+    R: Columnar + Default + PartialEq + std::ops::AddAssign,
+    for<'a> D::Ref<'a>: Ord,
+    for<'a> T::Ref<'a>: Ord,
+{
+    let mut result: <(D, T, R) as Columnar>::Container = Default::default();
+    {
+        let (data, time, diff) = Container::<(D, T, R)>::borrow(updates);
+        let key = |index: usize| (data.get(index), time.get(index));
+        let mut perm: Vec<usize> = (0 .. data.len()).collect();
+        perm.sort_by(|i, j| key(*i).cmp(&key(*j)));
+
+        let mut lower = 0;
+        while lower < perm.len() {
+            let mut upper = lower + 1;
+            let mut sum = R::into_owned(diff.get(perm[lower]));
+            while upper < perm.len() && key(perm[lower]) == key(perm[upper]) {
+                sum += R::into_owned(diff.get(perm[upper]));
+                upper += 1;
+            }
+            if sum != R::default() {
+                let (d, t) = key(perm[lower]);
+                result.0.push(d);
+                result.1.push(t);
+                result.2.push(&sum);
+            }
+            lower = upper;
+        }
+    }
+    *updates = result;
+}
+
+/// The rows of the `k` least items of `column` according to `cmp`, in sorted order.
+///
+/// Items that compare equal are ordered by row, and so the result is a prefix of the stable
+/// sort of the column. Rather than sorting all rows, this maintains a heap of the `k` least
+/// rows seen so far, taking time proportional to the rows times `log k`. For the `k` greatest
+/// items, reverse the comparison.
+pub fn top_k<C, F>(column: &C, k: usize, mut cmp: F) -> Vec<usize>
+where
+    C: Index + Len,
+    F: FnMut(&C::Ref, &C::Ref) -> Ordering,
+{
+    if k == 0 { return Vec::new(); }
+    // A max-heap of rows, so that the greatest of the `k` least is at the root.
+    let mut heap: Vec<(usize, C::Ref)> = Vec::with_capacity(k.min(column.len()));
+    let mut less = |x: &(usize, C::Ref), y: &(usize, C::Ref)| cmp(&x.1, &y.1).then(x.0.cmp(&y.0)) == Ordering::Less;
+    for row in 0 .. column.len() {
+        let entry = (row, column.get(row));
+        if heap.len() < k {
+            heap.push(entry);
+            let mut position = heap.len() - 1;
+            while position > 0 {
+                let parent = (position - 1) / 2;
+                if !less(&heap[parent], &heap[position]) { break; }
+                heap.swap(position, parent);
+                position = parent;
+            }
+        } else if less(&entry, &heap[0]) {
+            heap[0] = entry;
+            let mut position = 0;
+            loop {
+                let mut greatest = position;
+                for child in [2 * position + 1, 2 * position + 2] {
+                    if child < heap.len() && less(&heap[greatest], &heap[child]) {
+                        greatest = child;
+                    }
+                }
+                if greatest == position { break; }
+                heap.swap(position, greatest);
+                position = greatest;
+            }
+        }
+    }
+    heap.sort_by(|x, y| if less(x, y) { Ordering::Less } else { Ordering::Greater });
+    heap.into_iter().map(|(row, _)| row).collect()
+}
+
+/// The `k` least items of `container` according to `cmp`, in sorted order, as a new container.
+pub fn top_k_into<T, F>(container: &T::Container, k: usize, mut cmp: F) -> T::Container
+where
+    T: Columnar,
+    F: for<'a> FnMut(&T::Ref<'a>, &T::Ref<'a>) -> Ordering,
+{
+    let borrowed = container.borrow();
+    let mut result: T::Container = Default::default();
+    for row in top_k(&borrowed, k, |x, y| cmp(x, y)) {
+        result.push(borrowed.get(row));
+    }
+    result
+}
+
+/// Merges sorted containers, yielding `(container, row, item)` in globally sorted order.
+///
+/// Each container must already be sorted according to `cmp`. Items that compare equal
+/// are produced in order of their container, and then their row, making the merge stable.
+pub fn kmerge<C, F>(containers: &[C], cmp: F) -> KMerge<'_, C, F>
+where
+    C: Index + Len,
+    F: FnMut(&C::Ref, &C::Ref) -> Ordering,
+{
+    let mut merge = KMerge { containers, heap: Vec::with_capacity(containers.len()), cmp };
+    for (index, container) in containers.iter().enumerate() {
+        if !container.is_empty() {
+            merge.heap.push((index, 0));
+            let position = merge.heap.len() - 1;
+            merge.sift_up(position);
+        }
+    }
+    merge
+}
+
+/// Merges sorted containers into a new container, stably, using `cmp` to order items.
+pub fn kmerge_into<T, F>(containers: &[T::Container], mut cmp: F) -> T::Container
+where
+    T: Columnar,
+    F: for<'a> FnMut(&T::Ref<'a>, &T::Ref<'a>) -> Ordering,
+{
+    let borrowed: Vec<_> = containers.iter().map(|container| container.borrow()).collect();
+    let mut result: T::Container = Default::default();
+    for (_, _, item) in kmerge(&borrowed[..], |x, y| cmp(x, y)) {
+        result.push(item);
+    }
+    result
+}
+
+/// An iterator merging sorted containers, produced by `kmerge`.
+///
+/// The iterator maintains a binary heap of the next row from each non-exhausted container.
+pub struct KMerge<'a, C, F> {
+    containers: &'a [C],
+    /// Pairs of container and row, heap-ordered by the row's item.
+    heap: Vec<(usize, usize)>,
+    cmp: F,
+}
+
+impl<C: Index, F: FnMut(&C::Ref, &C::Ref) -> Ordering> KMerge<'_, C, F> {
+    /// Orders two heap entries by their items, then by container and row.
+    fn less(&mut self, x: (usize, usize), y: (usize, usize)) -> bool {
+        let item_x = self.containers[x.0].get(x.1);
+        let item_y = self.containers[y.0].get(y.1);
+        (self.cmp)(&item_x, &item_y).then(x.cmp(&y)) == Ordering::Less
+    }
+    fn sift_up(&mut self, mut position: usize) {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if !self.less(self.heap[position], self.heap[parent]) { break; }
+            self.heap.swap(position, parent);
+            position = parent;
+        }
+    }
+    fn sift_down(&mut self, mut position: usize) {
+        loop {
+            let mut least = position;
+            for child in [2 * position + 1, 2 * position + 2] {
+                if child < self.heap.len() && self.less(self.heap[child], self.heap[least]) {
+                    least = child;
+                }
+            }
+            if least == position { break; }
+            self.heap.swap(position, least);
+            position = least;
+        }
+    }
+}
+
+impl<C: Index + Len, F: FnMut(&C::Ref, &C::Ref) -> Ordering> Iterator for KMerge<'_, C, F> {
+    type Item = (usize, usize, C::Ref);
+    fn next(&mut self) -> Option<Self::Item> {
+        let (container, row) = *self.heap.first()?;
+        if row + 1 < self.containers[container].len() {
+            self.heap[0] = (container, row + 1);
+        } else {
+            let last = self.heap.pop().unwrap();
+            if self.heap.is_empty() { return Some((container, row, self.containers[container].get(row))); }
+            self.heap[0] = last;
+        }
+        self.sift_down(0);
+        Some((container, row, self.containers[container].get(row)))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.heap.iter().map(|(container, row)| self.containers[*container].len() - row).sum();
+        (remaining, Some(remaining))
+    }
+}
+impl<C: Index + Len, F: FnMut(&C::Ref, &C::Ref) -> Ordering> ExactSizeIterator for KMerge<'_, C, F> { }
+impl<C: Index + Len, F: FnMut(&C::Ref, &C::Ref) -> Ordering> std::iter::FusedIterator for KMerge<'_, C, F> { }
+
+/// Sorting of more rows than fit in memory, by spilling sorted runs to files and merging them.
+///
+/// Rows are gathered into a run until the run's heap size exceeds a budget, at which point the
+/// run is sorted and written to a file as a sequence of blocks. Finishing merges the runs with
+/// the rows still in memory, holding one block of each run at a time, and produces the sorted
+/// rows either in memory or as blocks in a file, which `read_blocks` reads back.
+///
+/// Blocks hold about `1 / BLOCKS` of the budget, so the merge holds about `runs / BLOCKS` times
+/// the budget in memory; sorts of more than `BLOCKS` times the budget exceed it while merging.
+/// A block is a length in words followed by the `bytes::serialization` layout of a container,
+/// with words in little-endian order.
+pub mod external {
+
+    use std::cmp::Ordering;
+    use std::fs::File;
+    use std::io::{self, BufReader, BufWriter, Read, Write};
+    use std::marker::PhantomData;
+    use std::path::{Path, PathBuf};
+
+    use crate::{AsBytes, Clear, Columnar, Container, HeapSize, Index, Len, Push};
+    use crate::bytes::serialization;
+
+    /// The number of blocks into which a budget of rows is divided.
+    pub const BLOCKS: usize = 64;
+
+    /// Sorts rows of `T` with a bounded amount of memory, spilling sorted runs to `directory`.
+    ///
+    /// The directory holds the files of the runs until the sort finishes or is dropped, and
+    /// should not be shared with other sorts.
+    pub struct ExternalSort<T: Columnar, F> {
+        directory: PathBuf,
+        budget: usize,
+        cmp: F,
+        /// Rows not yet spilled.
+        run: T::Container,
+        /// The files of spilled runs, in the order the rows were pushed.
+        runs: Vec<PathBuf>,
+    }
+
+    impl<T, F> ExternalSort<T, F>
+    where
+        T: Columnar,
+        T::Container: HeapSize,
+        F: for<'a> FnMut(&T::Ref<'a>, &T::Ref<'a>) -> Ordering,
+    {
+        /// Starts a sort that holds about `budget_bytes` of rows in memory, ordered by `cmp`.
+        pub fn new(directory: impl AsRef<Path>, budget_bytes: usize, cmp: F) -> io::Result<Self> {
+            std::fs::create_dir_all(directory.as_ref())?;
+            Ok(Self { directory: directory.as_ref().to_path_buf(), budget: budget_bytes, cmp, run: Default::default(), runs: Vec::new() })
+        }
+        /// The number of runs spilled so far.
+        pub fn runs(&self) -> usize { self.runs.len() }
+
+        /// Adds a row to the sort, spilling the rows in memory if they exceed the budget.
+        pub fn push(&mut self, item: &T) -> io::Result<()> {
+            self.run.push(item);
+            if self.run.heap_size().0 > self.budget { self.spill()?; }
+            Ok(())
+        }
+
+        /// Merges the sorted rows into a container in memory.
+        pub fn finish(mut self) -> io::Result<T::Container> {
+            let mut result = T::Container::default();
+            self.merge(|item| { result.push(item); Ok(()) })?;
+            Ok(result)
+        }
+        /// Merges the sorted rows into blocks written to the file at `path`.
+        pub fn finish_to(mut self, path: impl AsRef<Path>) -> io::Result<()> {
+            let mut writer = BlockWriter::<T, _>::new(BufWriter::new(File::create(path)?), self.block_bytes());
+            self.merge(|item| writer.push(item))?;
+            writer.finish()?.flush()
+        }
+
+        fn block_bytes(&self) -> usize { (self.budget / BLOCKS).max(1) }
+
+        /// The order of the rows in memory, stable for equal rows.
+        fn order(&mut self) -> Vec<usize> {
+            let borrowed = self.run.borrow();
+            let mut order: Vec<usize> = (0 .. borrowed.len()).collect();
+            let cmp = &mut self.cmp;
+            order.sort_by(|i, j| cmp(&borrowed.get(*i), &borrowed.get(*j)));
+            order
+        }
+
+        /// Sorts the rows in memory, and writes them to a new run file.
+        fn spill(&mut self) -> io::Result<()> {
+            let order = self.order();
+            let path = self.directory.join(format!("run-{}.bin", self.runs.len()));
+            let mut writer = BlockWriter::<T, _>::new(BufWriter::new(File::create(&path)?), self.block_bytes());
+            self.runs.push(path);
+            let borrowed = self.run.borrow();
+            for index in order {
+                writer.push(borrowed.get(index))?;
+            }
+            writer.finish()?.flush()?;
+            self.run.clear();
+            Ok(())
+        }
+
+        /// Presents the sorted rows to `output`, merging the runs with the rows in memory.
+        fn merge(&mut self, mut output: impl FnMut(T::Ref<'_>) -> io::Result<()>) -> io::Result<()> {
+            let order = self.order();
+            let last = self.run.take(&order);
+            self.run.clear();
+
+            // Sources are ordered as their rows were pushed, so that breaking ties by source is stable.
+            let mut sources = Vec::with_capacity(self.runs.len() + 1);
+            for path in self.runs.iter() {
+                let mut blocks = read_blocks::<T>(path)?;
+                if let Some(block) = blocks.next().transpose()? {
+                    sources.push(Source { blocks: Some(blocks), block, row: 0 });
+                }
+            }
+            if !last.is_empty() {
+                sources.push(Source { blocks: None, block: last, row: 0 });
+            }
+
+            let cmp = &mut self.cmp;
+            let mut heap: Vec<usize> = Vec::with_capacity(sources.len());
+            for index in 0 .. sources.len() {
+                heap.push(index);
+                sift_up(&mut heap, &sources, cmp, index);
+            }
+            while let Some(&top) = heap.first() {
+                let source = &mut sources[top];
+                output(source.block.borrow().get(source.row))?;
+                source.row += 1;
+                if source.row == source.block.len() {
+                    match source.blocks.as_mut().map(|blocks| blocks.next().transpose()).transpose()?.flatten() {
+                        Some(block) => { source.block = block; source.row = 0; }
+                        None => { heap.swap_remove(0); }
+                    }
+                }
+                sift_down(&mut heap, &sources, cmp, 0);
+            }
+
+            for path in std::mem::take(&mut self.runs) {
+                std::fs::remove_file(path)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl<T: Columnar, F> Drop for ExternalSort<T, F> {
+        fn drop(&mut self) {
+            for path in self.runs.iter() {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+    }
+
+    /// Sorts `rows` by `cmp`, holding about `budget_bytes` of rows in memory and spilling the
+    /// rest to `directory`, and returns the sorted rows in memory.
+    pub fn external_sort<'a, T, F>(rows: impl IntoIterator<Item=&'a T>, cmp: F, budget_bytes: usize, directory: impl AsRef<Path>) -> io::Result<T::Container>
+    where
+        T: Columnar + 'a,
+        T::Container: HeapSize,
+        F: for<'b> FnMut(&T::Ref<'b>, &T::Ref<'b>) -> Ordering,
+    {
+        let mut sort = ExternalSort::<T, F>::new(directory, budget_bytes, cmp)?;
+        for row in rows {
+            sort.push(row)?;
+        }
+        sort.finish()
+    }
+
+    /// A sorted sequence of rows being merged: the rows of its current block, and the blocks that follow.
+    struct Source<T: Columnar> {
+        blocks: Option<Blocks<T>>,
+        block: T::Container,
+        row: usize,
+    }
+
+    /// Orders two sources by their current rows, and then by their positions.
+    fn less<T, F>(sources: &[Source<T>], cmp: &mut F, x: usize, y: usize) -> bool
+    where
+        T: Columnar,
+        F: for<'a> FnMut(&T::Ref<'a>, &T::Ref<'a>) -> Ordering,
+    {
+        let (block_x, block_y) = (sources[x].block.borrow(), sources[y].block.borrow());
+        cmp(&block_x.get(sources[x].row), &block_y.get(sources[y].row)).then(x.cmp(&y)) == Ordering::Less
+    }
+    fn sift_up<T, F>(heap: &mut [usize], sources: &[Source<T>], cmp: &mut F, mut position: usize)
+    where
+        T: Columnar,
+        F: for<'a> FnMut(&T::Ref<'a>, &T::Ref<'a>) -> Ordering,
+    {
+        while position > 0 {
+            let parent = (position - 1) / 2;
+            if !less(sources, cmp, heap[position], heap[parent]) { break; }
+            heap.swap(position, parent);
+            position = parent;
+        }
+    }
+    fn sift_down<T, F>(heap: &mut [usize], sources: &[Source<T>], cmp: &mut F, mut position: usize)
+    where
+        T: Columnar,
+        F: for<'a> FnMut(&T::Ref<'a>, &T::Ref<'a>) -> Ordering,
+    {
+        loop {
+            let mut least = position;
+            for child in [2 * position + 1, 2 * position + 2] {
+                if child < heap.len() && less(sources, cmp, heap[child], heap[least]) {
+                    least = child;
+                }
+            }
+            if least == position { break; }
+            heap.swap(position, least);
+            position = least;
+        }
+    }
+
+    /// Writes rows to `writer` in blocks of about `block_bytes` each.
+    struct BlockWriter<T: Columnar, W> {
+        writer: W,
+        block: T::Container,
+        block_bytes: usize,
+    }
+
+    impl<T: Columnar, W: Write> BlockWriter<T, W> where T::Container: HeapSize {
+        fn new(writer: W, block_bytes: usize) -> Self {
+            Self { writer, block: Default::default(), block_bytes }
+        }
+        fn push(&mut self, item: T::Ref<'_>) -> io::Result<()> {
+            self.block.push(item);
+            if self.block.heap_size().0 >= self.block_bytes { self.flush()?; }
+            Ok(())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            if !self.block.is_empty() {
+                let mut words = Vec::new();
+                serialization::encode(&mut words, self.block.borrow().as_bytes());
+                let bytes = std::iter::once(words.len() as u64).chain(words).flat_map(u64::to_le_bytes).collect::<Vec<_>>();
+                self.writer.write_all(&bytes)?;
+                self.block.clear();
+            }
+            Ok(())
+        }
+        fn finish(mut self) -> io::Result<W> {
+            self.flush()?;
+            Ok(self.writer)
+        }
+    }
+
+    /// Reads the blocks of sorted rows written by `ExternalSort::finish_to`.
+    pub fn read_blocks<T: Columnar>(path: impl AsRef<Path>) -> io::Result<Blocks<T>> {
+        let file = File::open(path)?;
+        let remaining = file.metadata()?.len();
+        Ok(Blocks { reader: BufReader::new(file), remaining, phantom: PhantomData })
+    }
+
+    /// An iterator over the blocks of a file, each as a container.
+    ///
+    /// A block that is not in the layout the blocks are written in, or whose rows cannot be read,
+    /// yields an error of kind `io::ErrorKind::InvalidData`, as does a block whose length reaches
+    /// past the end of the file, or a file that ends partway through the length of a block.
+    pub struct Blocks<T> {
+        reader: BufReader<File>,
+        /// The number of bytes of the file not yet read.
+        remaining: u64,
+        phantom: PhantomData<T>,
+    }
+
+    impl<T: Columnar> Iterator for Blocks<T> {
+        type Item = io::Result<T::Container>;
+        fn next(&mut self) -> Option<Self::Item> {
+            let invalid = |message: &str| Some(Err(io::Error::new(io::ErrorKind::InvalidData, message)));
+            if self.remaining == 0 { return None; }
+            let mut length = [0u8; 8];
+            match self.reader.read_exact(&mut length) {
+                Ok(()) => { },
+                Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return invalid("block length is truncated"),
+                Err(error) => return Some(Err(error)),
+            }
+            self.remaining = self.remaining.saturating_sub(8);
+            let length = match u64::from_le_bytes(length).checked_mul(8) {
+                Some(length) if length <= self.remaining => length,
+                _ => return invalid("block length reaches past the end of the file"),
+            };
+            let mut bytes = vec![0u8; length as usize];
+            if let Err(error) = self.reader.read_exact(&mut bytes) { return Some(Err(error)); }
+            self.remaining -= length;
+            let words = bytes.chunks_exact(8).map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap())).collect::<Vec<_>>();
+            match serialization::try_decode_owned::<T>(&words) {
+                Some(block) if !block.is_empty() => Some(Ok(block)),
+                _ => invalid("block is not the encoding of a container"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::{Columnar, Container, Index, Len};
+    use super::Order;
+
+    #[test]
+    fn sort_by_keys() {
+        let rows: Vec<(u64, String)> = (0 .. 100).map(|i| (i % 7, format!("{:02}", i % 10))).collect();
+        let mut column = Columnar::as_columns(rows.iter());
+        super::sort_rows_by_keys::<(u64, String)>(&mut column, &[(1, Order::Descending), (0, Order::Ascending)]);
+
+        let mut expected = rows.clone();
+        expected.sort_by(|x, y| y.1.cmp(&x.1).then(x.0.cmp(&y.0)));
+        let borrowed = Container::<(u64, String)>::borrow(&column);
+        for (index, (a, b)) in expected.iter().enumerate() {
+            assert_eq!(borrowed.get(index), (a, b.as_str()));
+        }
+    }
+
+    #[test]
+    fn collation() {
+        use super::{Collated, Collation};
+        let words = ["apple", "Banana", "APPLE", "Äpfel", "äpfel", "banana", "Zebra", "ångström"];
+        let column: crate::Strings = Columnar::into_columns(words.iter().map(|word| word.to_string()));
+        let ids: Vec<u64> = (0 .. words.len() as u64).collect();
+        let borrowed = Container::<String>::borrow(&column);
+
+        let collated = Collated { inner: borrowed, collation: Collation::AsciiCaseInsensitive };
+        let perm = super::permutation(&(collated, &ids[..]), &[(0, Order::Ascending), (1, Order::Descending)]);
+        assert_eq!(perm, [2, 0, 5, 1, 6, 3, 4, 7]);
+
+        let collated = Collated { inner: borrowed, collation: Collation::CaseFold };
+        let perm = super::permutation(&(collated,), &[(0, Order::Ascending)]);
+        assert_eq!(perm.iter().map(|index| words[*index]).collect::<Vec<_>>(), ["apple", "APPLE", "Banana", "banana", "Zebra", "Äpfel", "äpfel", "ångström"]);
+        assert_eq!(collated.get(3), collated.get(4));
+        let groups: std::collections::HashSet<_> = (0 .. words.len()).map(|index| collated.get(index)).collect();
+        assert_eq!(groups.len(), 5);
+
+        // Texts that split the same characters differently hash differently.
+        let hash = |texts: &[&str], collation: Collation| {
+            use std::hash::{Hash, Hasher};
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            for text in texts { Collated { inner: *text, collation }.hash(&mut hasher); }
+            hasher.finish()
+        };
+        for collation in [Collation::Binary, Collation::AsciiCaseInsensitive, Collation::CaseFold] {
+            assert_ne!(hash(&["ab", ""], collation), hash(&["a", "b"], collation));
+        }
+        assert_eq!(hash(&["AB", "c"], Collation::CaseFold), hash(&["ab", "C"], Collation::CaseFold));
+
+        for collation in [Collation::Binary, Collation::AsciiCaseInsensitive, Collation::CaseFold] {
+            let key = |text: &str| { let mut key = Vec::new(); collation.encode_key(text, &mut key); key };
+            for a in words.iter() {
+                for b in words.iter() {
+                    assert_eq!(key(a).cmp(&key(b)), collation.compare(a, b));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn radix() {
+        let keys: Vec<i64> = (0 .. 1000i64).map(|i| (i * 7919) % 1009 - 500).collect();
+        let perm = super::radix_permutation(&keys);
+        let mut expected: Vec<usize> = (0 .. keys.len()).collect();
+        expected.sort_by_key(|i| keys[*i]);
+        assert_eq!(perm, expected);
+    }
+
+    #[test]
+    fn consolidate() {
+        let updates: Vec<(String, u64, i64)> = (0 .. 100).map(|i| (format!("{}", i % 7), i % 3, if i % 2 == 0 { 1 } else { -1 })).collect();
+        let mut column = Columnar::as_columns(updates.iter());
+        super::consolidate::<String, u64, i64>(&mut column);
+
+        let mut expected = std::collections::BTreeMap::new();
+        for (d, t, r) in updates.iter() {
+            *expected.entry((d.clone(), *t)).or_insert(0) += r;
+        }
+        expected.retain(|_, r| *r != 0);
+        let borrowed = Container::<(String, u64, i64)>::borrow(&column);
+        assert_eq!(borrowed.len(), expected.len());
+        for (index, ((d, t), r)) in expected.iter().enumerate() {
+            assert_eq!(borrowed.get(index), (d.as_str(), t, r));
+        }
+    }
+
+    #[test]
+    fn top_k() {
+        let scores: Vec<(u64, String)> = (0 .. 1000).map(|i| ((i * 7919) % 101, format!("player{}", i))).collect();
+        let column = Columnar::as_columns(scores.iter());
+        let borrowed = Container::<(u64, String)>::borrow(&column);
+        let mut expected: Vec<usize> = (0 .. scores.len()).collect();
+        expected.sort_by(|x, y| scores[*y].0.cmp(&scores[*x].0));
+        for k in [0, 1, 10, 999, 1000, 2000] {
+            let rows = super::top_k(&borrowed.0, k, |x, y| y.cmp(x));
+            assert_eq!(rows, expected[.. k.min(1000)]);
+        }
+
+        let least = super::top_k_into::<(u64, String), _>(&column, 3, |x, y| x.cmp(y));
+        let borrowed = Container::<(u64, String)>::borrow(&least);
+        assert_eq!(borrowed.get(0), (&0, "player0"));
+        assert_eq!(borrowed.get(1), (&0, "player101"));
+        assert_eq!(borrowed.get(2), (&0, "player202"));
+    }
+
+    #[test]
+    fn kmerge() {
+        let inputs: Vec<Vec<u64>> = (1 .. 5).map(|step| (0 .. 20).map(|i| i * step).collect()).collect();
+        let containers: Vec<_> = inputs.iter().map(|input| Columnar::as_columns(input.iter())).collect();
+        let merged = super::kmerge_into::<u64, _>(&containers, |x, y| x.cmp(y));
+        let mut expected: Vec<u64> = inputs.concat();
+        expected.sort();
+        assert_eq!(merged, expected);
+
+        let borrowed: Vec<_> = containers.iter().map(Container::<u64>::borrow).collect();
+        let mut iter = super::kmerge(&borrowed, |x, y| x.cmp(y));
+        assert_eq!(iter.len(), 80);
+        iter.nth(9);
+        assert_eq!(iter.len(), 70);
+        assert_eq!(iter.by_ref().count(), 70);
+        assert_eq!(iter.next(), None);
+        assert_eq!(borrowed[0].iter().skip(5).len(), 15);
+    }
+
+    #[test]
+    fn external() {
+        use super::external::{self, ExternalSort};
+
+        let directory = std::env::temp_dir().join(format!("columnar-sort-{}", std::process::id()));
+        let rows: Vec<(u64, String)> = (0 .. 2000u64).map(|i| ((i * 7919) % 97, format!("{}", i))).collect();
+        let mut expected = rows.clone();
+        expected.sort_by_key(|row| row.0);
+
+        // Sorting by the first field alone is stable, so rows keep their order within each key.
+        let mut sort = ExternalSort::<(u64, String), _>::new(&directory, 4096, |x: &(&u64, &str), y: &(&u64, &str)| x.0.cmp(y.0)).unwrap();
+        for row in rows.iter() { sort.push(row).unwrap(); }
+        assert!(sort.runs() > 1);
+        let sorted = sort.finish().unwrap();
+        assert_eq!(sorted.len(), expected.len());
+        for (index, row) in expected.iter().enumerate() {
+            assert_eq!(Container::<(u64, String)>::borrow(&sorted).get(index), (&row.0, row.1.as_str()));
+        }
+
+        let mut sort = ExternalSort::<(u64, String), _>::new(&directory, 4096, |x: &(&u64, &str), y: &(&u64, &str)| x.0.cmp(y.0)).unwrap();
+        for row in rows.iter() { sort.push(row).unwrap(); }
+        let path = directory.join("sorted.bin");
+        sort.finish_to(&path).unwrap();
+        let blocks = external::read_blocks::<(u64, String)>(&path).unwrap().collect::<Result<Vec<_>, _>>().unwrap();
+        assert!(blocks.len() > 1);
+        let merged = blocks.iter().flat_map(|block| (0 .. block.len()).map(|index| block.materialize(index))).collect::<Vec<_>>();
+        assert_eq!(merged, expected);
+
+        // Only the output remains in the directory.
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 1);
+
+        // Corrupt blocks are reported as invalid data, rather than read or allocated.
+        let bytes = std::fs::read(&path).unwrap();
+        let mut corrupt = bytes.clone();
+        corrupt[.. 8].copy_from_slice(&u64::MAX.to_le_bytes());
+        let mut huge = bytes.clone();
+        huge[.. 8].copy_from_slice(&(1u64 << 40).to_le_bytes());
+        let words = [3u64, 1, 2, 3].iter().flat_map(|word| word.to_le_bytes()).collect::<Vec<_>>();
+        // A block in the layout whose string bounds reach past the string bytes.
+        let mut block = Vec::new();
+        let slices = [(8, bytemuck::cast_slice(&[1u64][..])), (8, bytemuck::cast_slice(&[100u64][..])), (1, &b"a"[..])];
+        crate::bytes::serialization::encode(&mut block, slices.into_iter());
+        let bounds = std::iter::once(block.len() as u64).chain(block).flat_map(u64::to_le_bytes).collect::<Vec<_>>();
+        for corrupt in [corrupt, huge, words, bounds] {
+            std::fs::write(&path, corrupt).unwrap();
+            let error = external::read_blocks::<(u64, String)>(&path).unwrap().next().unwrap().unwrap_err();
+            assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        }
+        // A file that ends partway through the length of a block.
+        let mut truncated = bytes.clone();
+        truncated.extend_from_slice(&[0, 0, 0]);
+        std::fs::write(&path, truncated).unwrap();
+        let error = external::read_blocks::<(u64, String)>(&path).unwrap().find_map(Result::err).unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,348 @@
+//! An append-only store of batches, kept as files in a directory.
+//!
+//! Each batch is encoded to its own segment file, and a manifest lists the segments in order
+//! with their numbers of rows. The manifest is rewritten in full and renamed into place on each
+//! change, so that a reader sees either the prior or the next set of segments, and segment files
+//! not listed in the manifest are ignored. Compaction replaces a range of adjacent segments with
+//! one segment, produced from their concatenated rows by a caller-supplied hook.
+//!
+//! Batches do not record their column types, and reading segments requires a batch with the
+//! schema they were written with, as for `Batch::decode`.
+//!
+//! Segment files and the manifest are synced to disk before they are renamed into place, so a
+//! crash cannot leave the manifest listing a partially written segment. Batches that should be
+//! durable before they are gathered into a segment can first be appended to a `WriteAheadLog`.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::batch::Batch;
+
+/// A segment of the store, holding one encoded batch.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Segment {
+    /// The identifier of the segment, which names its file.
+    pub id: u64,
+    /// The number of rows in the segment.
+    pub rows: u64,
+}
+
+/// A directory of segment files and the manifest that orders them.
+pub struct SegmentStore {
+    directory: PathBuf,
+    segments: Vec<Segment>,
+    next_id: u64,
+}
+
+impl SegmentStore {
+    /// The name of the manifest file within the directory.
+    pub const MANIFEST: &'static str = "MANIFEST";
+
+    /// Opens the store in `directory`, creating the directory if it does not exist.
+    pub fn open(directory: impl AsRef<Path>) -> io::Result<Self> {
+        let directory = directory.as_ref().to_path_buf();
+        std::fs::create_dir_all(&directory)?;
+        let mut segments = Vec::new();
+        match std::fs::read_to_string(directory.join(Self::MANIFEST)) {
+            Ok(manifest) => {
+                for line in manifest.lines().filter(|line| !line.is_empty()) {
+                    let mut fields = line.split(' ').map(|field| field.parse::<u64>());
+                    match (fields.next(), fields.next(), fields.next()) {
+                        (Some(Ok(id)), Some(Ok(rows)), None) => segments.push(Segment { id, rows }),
+                        _ => return Err(io::Error::new(io::ErrorKind::InvalidData, format!("malformed manifest line: {:?}", line))),
+                    }
+                }
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => { }
+            Err(error) => return Err(error),
+        }
+        let next_id = segments.iter().map(|segment| segment.id + 1).max().unwrap_or(0);
+        Ok(Self { directory, segments, next_id })
+    }
+
+    /// The segments of the store, in order.
+    pub fn segments(&self) -> &[Segment] { &self.segments }
+    /// The total number of rows across all segments.
+    pub fn rows(&self) -> u64 { self.segments.iter().map(|segment| segment.rows).sum() }
+
+    /// Appends `batch` as a new segment, and returns the segment's identifier.
+    pub fn append_batch(&mut self, batch: &Batch) -> io::Result<u64> {
+        let segment = self.write_segment(batch)?;
+        self.segments.push(segment);
+        self.write_manifest()?;
+        Ok(segment.id)
+    }
+
+    /// Reads the batches of the segments at positions `range`, with the schema of `schema`.
+    pub fn iter_batches<'a>(&'a self, range: Range<usize>, schema: &'a Batch) -> impl Iterator<Item = io::Result<Batch>> + 'a {
+        self.segments[range].iter().map(move |segment| self.read_segment(segment, schema))
+    }
+
+    /// Replaces the segments at positions `range` with a single segment.
+    ///
+    /// The rows of the segments are read into one batch with the schema of `schema`, which
+    /// `hook` may then filter, consolidate, or otherwise rewrite before it is written. The
+    /// manifest is updated before the replaced segment files are removed. An empty `range`
+    /// replaces no segments, and leaves the store unchanged.
+    pub fn compact(&mut self, range: Range<usize>, schema: &Batch, hook: impl FnOnce(Batch) -> Batch) -> io::Result<()> {
+        if range.is_empty() { return Ok(()); }
+        let mut merged = schema.empty();
+        for segment in self.segments[range.clone()].iter() {
+            Self::decode_into(&mut merged, &self.read_words(segment)?, segment)?;
+        }
+        let segment = self.write_segment(&hook(merged))?;
+        let replaced = self.segments.splice(range, std::iter::once(segment)).collect::<Vec<_>>();
+        self.write_manifest()?;
+        for segment in replaced {
+            std::fs::remove_file(self.path(segment.id))?;
+        }
+        Ok(())
+    }
+
+    /// The path of the file of the segment with identifier `id`.
+    fn path(&self, id: u64) -> PathBuf { self.directory.join(format!("segment-{:020}", id)) }
+
+    fn write_segment(&mut self, batch: &Batch) -> io::Result<Segment> {
+        let mut words = Vec::with_capacity(batch.length_in_words());
+        batch.encode(&mut words);
+        let segment = Segment { id: self.next_id, rows: batch.rows() as u64 };
+        self.next_id += 1;
+        let mut file = File::create(self.path(segment.id))?;
+        file.write_all(&to_bytes(&words))?;
+        file.sync_all()?;
+        Ok(segment)
+    }
+    fn read_words(&self, segment: &Segment) -> io::Result<Vec<u64>> {
+        let bytes = std::fs::read(self.path(segment.id))?;
+        if !bytes.len().is_multiple_of(8) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, format!("segment {} is not a whole number of words", segment.id)));
+        }
+        Ok(from_bytes(&bytes))
+    }
+    fn read_segment(&self, segment: &Segment, schema: &Batch) -> io::Result<Batch> {
+        let mut batch = schema.empty();
+        Self::decode_into(&mut batch, &self.read_words(segment)?, segment)?;
+        Ok(batch)
+    }
+    /// Appends the rows of `segment`, encoded in `words`, to `batch`.
+    fn decode_into(batch: &mut Batch, words: &[u64], segment: &Segment) -> io::Result<()> {
+        batch.try_decode(words).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("segment {} is not the encoding of a batch with the schema", segment.id)))
+    }
+    /// Writes the manifest to a temporary file, syncs it, and renames it into place.
+    fn write_manifest(&self) -> io::Result<()> {
+        let manifest = self.segments.iter().map(|segment| format!("{} {}\n", segment.id, segment.rows)).collect::<String>();
+        let temporary = self.directory.join(format!("{}.tmp", Self::MANIFEST));
+        let mut file = File::create(&temporary)?;
+        file.write_all(manifest.as_bytes())?;
+        file.sync_all()?;
+        std::fs::rename(temporary, self.directory.join(Self::MANIFEST))?;
+        sync_directory(&self.directory)
+    }
+}
+
+/// Syncs the entries of `directory`, so that renames within it are durable.
+fn sync_directory(directory: &Path) -> io::Result<()> {
+    // Directories cannot be opened as files on all platforms; elsewhere renames are durable on their own.
+    if cfg!(unix) { File::open(directory)?.sync_all()?; }
+    Ok(())
+}
+
+fn to_bytes(words: &[u64]) -> Vec<u8> { words.iter().flat_map(|word| word.to_le_bytes()).collect() }
+fn from_bytes(bytes: &[u8]) -> Vec<u64> {
+    bytes.chunks_exact(8).map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap())).collect()
+}
+
+/// A 64-bit FNV-1a hash of `words`, to detect frames that were not completely written.
+fn checksum(words: &[u64]) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+    for byte in words.iter().flat_map(|word| word.to_le_bytes()) {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// A log of encoded batches, each synced to disk before `append` returns.
+///
+/// Each frame is its length in words, a checksum of its words, and the words themselves.
+/// A crash during an append can leave a torn frame at the end of the log, which `open`
+/// detects by its length or checksum and truncates, so that only complete frames are recovered.
+/// Once logged batches have been written elsewhere, for example to a `SegmentStore`, the log
+/// can be emptied with `reset`.
+pub struct WriteAheadLog {
+    file: File,
+}
+
+impl WriteAheadLog {
+    /// Opens the log at `path`, creating it if it does not exist, and recovers its frames.
+    ///
+    /// Any incomplete or corrupted suffix of the log is truncated before it is returned.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<(Self, Vec<Vec<u64>>)> {
+        let mut file = File::options().read(true).write(true).create(true).truncate(false).open(path)?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        let words = from_bytes(&bytes);
+
+        let mut frames = Vec::new();
+        let mut valid = 0;
+        while let [length, sum, rest @ ..] = &words[valid..] {
+            let length = *length as usize;
+            if rest.len() < length || checksum(&rest[.. length]) != *sum { break; }
+            frames.push(rest[.. length].to_vec());
+            valid += 2 + length;
+        }
+        if 8 * valid < bytes.len() {
+            file.set_len(8 * valid as u64)?;
+            file.sync_all()?;
+        }
+        file.seek(io::SeekFrom::End(0))?;
+        Ok((Self { file }, frames))
+    }
+
+    /// Appends `words` as a frame, and syncs it to disk.
+    ///
+    /// If the frame cannot be written or synced, the log is truncated to the frames before it,
+    /// so that a torn frame does not hide the frames appended after it from `open`.
+    pub fn append(&mut self, words: &[u64]) -> io::Result<()> {
+        let mut frame = Vec::with_capacity(2 + words.len());
+        frame.push(words.len() as u64);
+        frame.push(checksum(words));
+        frame.extend_from_slice(words);
+        let offset = self.file.stream_position()?;
+        let result = self.file.write_all(&to_bytes(&frame)).and_then(|()| self.file.sync_data());
+        if result.is_err() {
+            self.file.set_len(offset)?;
+            self.file.seek(io::SeekFrom::Start(offset))?;
+        }
+        result
+    }
+    /// Appends the encoding of `batch` as a frame, and syncs it to disk.
+    ///
+    /// The recovered frame can be read with `Batch::decode` into a batch with the same schema.
+    pub fn append_batch(&mut self, batch: &Batch) -> io::Result<()> {
+        let mut words = Vec::with_capacity(batch.length_in_words());
+        batch.encode(&mut words);
+        self.append(&words)
+    }
+    /// Removes all frames from the log.
+    pub fn reset(&mut self) -> io::Result<()> {
+        self.file.set_len(0)?;
+        self.file.seek(io::SeekFrom::Start(0))?;
+        self.file.sync_all()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::{SegmentStore, WriteAheadLog};
+    use crate::batch::Batch;
+    use crate::{Columnar, Container};
+
+    fn batch(range: std::ops::Range<u64>) -> Batch {
+        Batch::new()
+            .with_column::<u64>("id", Columnar::into_columns(range.clone()))
+            .with_column::<String>("name", Columnar::into_columns(range.map(|i| format!("name{}", i))))
+    }
+
+    #[test]
+    fn append_and_compact() {
+        let directory = std::env::temp_dir().join(format!("columnar-store-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&directory);
+        let schema = batch(0 .. 0);
+
+        let mut store = SegmentStore::open(&directory).unwrap();
+        for start in [0, 10, 20, 30] {
+            store.append_batch(&batch(start .. start + 10)).unwrap();
+        }
+        assert_eq!(store.rows(), 40);
+
+        // Reopening reads the manifest.
+        let mut store = SegmentStore::open(&directory).unwrap();
+        assert_eq!(store.segments().len(), 4);
+        let batches = store.iter_batches(1 .. 3, &schema).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches[1].typed::<String>("name").unwrap().materialize(0), "name20");
+
+        // Compact the middle segments, keeping only even identifiers.
+        store.compact(1 .. 3, &schema, |merged| {
+            let ids = merged.typed::<u64>("id").unwrap();
+            let names = merged.typed::<String>("name").unwrap();
+            let evens = (0 .. merged.rows()).filter(|index| ids[*index] % 2 == 0);
+            Batch::new()
+                .with_column::<u64>("id", Columnar::into_columns(evens.clone().map(|index| ids[index])))
+                .with_column::<String>("name", Columnar::into_columns(evens.map(|index| names.materialize(index))))
+        }).unwrap();
+        assert_eq!(store.segments().len(), 3);
+        assert_eq!(SegmentStore::open(&directory).unwrap().segments(), store.segments());
+        let batches = store.iter_batches(0 .. 3, &schema).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(batches.iter().map(|batch| batch.rows()).collect::<Vec<_>>(), [10, 10, 10]);
+        assert_eq!(batches[1].typed::<String>("name").unwrap().materialize(9), "name28");
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 4);
+
+        // Compacting no segments writes no segment.
+        store.compact(2 .. 2, &schema, |_| panic!("nothing to compact")).unwrap();
+        assert_eq!(store.segments().len(), 3);
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 4);
+
+        // Segments that are not the encoding of a batch with the schema are reported as invalid data.
+        let path = directory.join(format!("segment-{:020}", store.segments()[0].id));
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes.truncate(bytes.len() - 8);
+        std::fs::write(&path, bytes).unwrap();
+        let error = store.iter_batches(0 .. 1, &schema).next().unwrap().err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(store.compact(0 .. 2, &schema, |merged| merged).unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(store.segments().len(), 3);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn torn_writes() {
+        use std::io::Write;
+
+        let path = std::env::temp_dir().join(format!("columnar-wal-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let schema = batch(0 .. 0);
+
+        let (mut log, frames) = WriteAheadLog::open(&path).unwrap();
+        assert!(frames.is_empty());
+        for start in [0, 10, 20] {
+            log.append_batch(&batch(start .. start + 10)).unwrap();
+        }
+        drop(log);
+        let complete = std::fs::metadata(&path).unwrap().len();
+
+        // A frame header promising more words than follow, and a partial word.
+        let mut file = std::fs::File::options().append(true).open(&path).unwrap();
+        file.write_all(&[100u64.to_le_bytes(), 0u64.to_le_bytes()].concat()).unwrap();
+        file.write_all(&[1, 2, 3]).unwrap();
+        drop(file);
+
+        let (mut log, frames) = WriteAheadLog::open(&path).unwrap();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), complete);
+        let mut recovered = schema.empty();
+        for frame in frames.iter() { recovered.decode(frame); }
+        assert_eq!(recovered.rows(), 30);
+        assert_eq!(recovered.typed::<String>("name").unwrap().materialize(25), "name25");
+
+        // A frame whose words do not match its checksum is discarded, with those after it.
+        log.append(&[1, 2, 3]).unwrap();
+        log.append(&[4, 5, 6]).unwrap();
+        drop(log);
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[complete as usize + 16] ^= 1;
+        std::fs::write(&path, bytes).unwrap();
+        let (mut log, frames) = WriteAheadLog::open(&path).unwrap();
+        assert_eq!(frames.len(), 3);
+
+        log.reset().unwrap();
+        log.append(&[7]).unwrap();
+        drop(log);
+        assert_eq!(WriteAheadLog::open(&path).unwrap().1, vec![vec![7]]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
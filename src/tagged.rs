@@ -0,0 +1,79 @@
+//! Serialization of struct containers by field tag, for decoding across versions of a struct.
+//!
+//! Deriving `Columnar` for a struct whose fields are each marked `#[columnar(tag = N)]` also
+//! implements `Tagged`, which records each field's columns along with its tag. Decoding matches
+//! fields by tag rather than by position: fields may be reordered, fields whose tags are absent
+//! from the encoding are filled with default values, and encoded fields whose tags the struct no
+//! longer has are skipped. A tag should not be reused for a field of a different type.
+//!
+//! The layout, in `u64` words, is the number of rows, the number of fields, then for each field
+//! its tag and the number of byte slices of its columns, and then all of the byte slices in the
+//! `bytes::serialization` layout.
+
+use crate::{Columnar, Container, FromBytes, Index, Len, Push};
+
+/// A type whose containers can be encoded and decoded by field tag.
+pub trait Tagged: Columnar {
+    /// Appends the encoding of `container` to `store`.
+    fn encode_tagged(container: &Self::Container, store: &mut Vec<u64>);
+    /// Decodes a container encoded by `encode_tagged`, perhaps by another version of `Self`.
+    fn decode_tagged(store: &[u64]) -> Self::Container;
+}
+
+/// A field's tag, and the byte slices of its columns with their alignments.
+pub type Field<'a> = (u64, Vec<(u64, &'a [u8])>);
+
+/// Appends `rows` rows of tagged fields.
+pub fn encode(store: &mut Vec<u64>, rows: usize, fields: Vec<Field<'_>>) {
+    store.push(rows as u64);
+    store.push(fields.len() as u64);
+    for (tag, slices) in fields.iter() {
+        store.push(*tag);
+        store.push(slices.len() as u64);
+    }
+    crate::bytes::serialization::encode(store, fields.into_iter().flat_map(|(_, slices)| slices));
+}
+
+/// Reads the fields of an encoding, by tag.
+pub struct Decoder<'a> {
+    rows: usize,
+    fields: Vec<(u64, Vec<&'a [u8]>)>,
+}
+
+impl<'a> Decoder<'a> {
+    /// Reads the fields of the encoding at the start of `store`.
+    pub fn new(store: &'a [u64]) -> Self {
+        let rows = store[0] as usize;
+        let count = store[1] as usize;
+        let mut slices = crate::bytes::serialization::decode(&store[2 + 2 * count ..]);
+        let fields = (0 .. count).map(|index| {
+            let tag = store[2 + 2 * index];
+            let length = store[3 + 2 * index] as usize;
+            (tag, slices.by_ref().take(length).collect())
+        }).collect();
+        Self { rows, fields }
+    }
+    /// The number of rows in the encoding.
+    pub fn rows(&self) -> usize { self.rows }
+    /// The field with tag `tag` as a container of `T`, if it is present.
+    pub fn field<T: Columnar>(&self, tag: u64) -> Option<T::Container> {
+        let (_, slices) = self.fields.iter().find(|(t, _)| *t == tag)?;
+        let borrowed = <T::Container as Container<T>>::Borrowed::from_bytes(&mut slices.iter().copied());
+        assert_eq!(borrowed.len(), self.rows, "field with tag {} has the wrong number of rows", tag);
+        let mut container = T::Container::default();
+        for index in 0 .. borrowed.len() {
+            container.push(borrowed.get(index));
+        }
+        Some(container)
+    }
+}
+
+/// A container of `rows` default values of `T`, standing in for a field absent from an encoding.
+pub fn defaults<T: Columnar + Default>(rows: usize) -> T::Container {
+    let value = T::default();
+    let mut container = T::Container::default();
+    for _ in 0 .. rows {
+        container.push(&value);
+    }
+    container
+}
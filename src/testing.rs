@@ -0,0 +1,201 @@
+//! Random generation of rows and containers, for property testing.
+//!
+//! Each generated container is accompanied by the `Vec<T>` of rows it was built from,
+//! which serves as an oracle for the container's behavior. Generation is deterministic
+//! given a seed, so that failing cases can be reproduced.
+//!
+//! The generator is self-contained rather than built on `proptest` or `arbitrary`, so that the
+//! feature adds no dependencies. Users of those crates can drive `Generate` from their own
+//! strategies or `Unstructured` input, by seeding `Rng` with a value drawn from them.
+//!
+//! This module is available with the `testing` feature.
+
+use crate::{Columnar, Push};
+
+/// A small, seedable, pseudo-random number generator (SplitMix64).
+///
+/// This is not suitable for anything other than generating test inputs.
+#[derive(Copy, Clone, Debug)]
+pub struct Rng { state: u64 }
+
+impl Rng {
+    /// A generator whose output is determined by `seed`.
+    pub fn new(seed: u64) -> Self { Self { state: seed } }
+    /// The next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+    /// A value drawn uniformly from `0 .. bound`, or zero if `bound` is zero.
+    pub fn below(&mut self, bound: usize) -> usize {
+        if bound == 0 { 0 } else { (self.next_u64() % (bound as u64)) as usize }
+    }
+    /// True with probability one half.
+    pub fn flip(&mut self) -> bool { self.next_u64() & 1 == 1 }
+}
+
+/// Types that can be randomly generated.
+///
+/// The `size` parameter bounds the lengths of generated strings and lists, and is passed
+/// along to nested values, so that small sizes produce small values.
+pub trait Generate: Sized {
+    /// A random value whose variable-length parts have lengths at most `size`.
+    fn generate(rng: &mut Rng, size: usize) -> Self;
+}
+
+macro_rules! generate_integer {
+    ($($index_type:ty),*) => { $(
+        impl Generate for $index_type {
+            // Favor small values and extremes, which are likelier to find bugs.
+            fn generate(rng: &mut Rng, _size: usize) -> Self {
+                match rng.below(4) {
+                    0 => <$index_type>::MIN,
+                    1 => <$index_type>::MAX,
+                    2 => (rng.below(16) as $index_type),
+                    _ => (rng.next_u64() as $index_type),
+                }
+            }
+        }
+    )* }
+}
+generate_integer!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl Generate for u128 {
+    fn generate(rng: &mut Rng, _size: usize) -> Self { ((rng.next_u64() as u128) << 64) | rng.next_u64() as u128 }
+}
+impl Generate for i128 {
+    fn generate(rng: &mut Rng, size: usize) -> Self { u128::generate(rng, size) as i128 }
+}
+impl Generate for f32 {
+    fn generate(rng: &mut Rng, _size: usize) -> Self { (rng.next_u64() as i64 as f32) / (1u64 << 40) as f32 }
+}
+impl Generate for f64 {
+    fn generate(rng: &mut Rng, _size: usize) -> Self { (rng.next_u64() as i64 as f64) / (1u64 << 40) as f64 }
+}
+impl Generate for bool {
+    fn generate(rng: &mut Rng, _size: usize) -> Self { rng.flip() }
+}
+impl Generate for char {
+    // Mixes ASCII with scalar values of every encoded length.
+    fn generate(rng: &mut Rng, _size: usize) -> Self {
+        match rng.below(4) {
+            0 => char::from_u32(rng.below(0xD800) as u32).unwrap(),
+            1 => char::from_u32(0xE000 + rng.below(0x110000 - 0xE000) as u32).unwrap(),
+            _ => (b'a' + rng.below(26) as u8) as char,
+        }
+    }
+}
+impl Generate for () {
+    fn generate(_rng: &mut Rng, _size: usize) -> Self { }
+}
+impl Generate for std::time::Duration {
+    fn generate(rng: &mut Rng, _size: usize) -> Self {
+        std::time::Duration::new(rng.next_u64() >> 2, rng.below(1_000_000_000) as u32)
+    }
+}
+impl Generate for String {
+    // Mixes ASCII with multi-byte characters, to exercise byte offsets.
+    fn generate(rng: &mut Rng, size: usize) -> Self {
+        let length = rng.below(size + 1);
+        (0 .. length).map(|_| match rng.below(8) {
+            0 => 'é',
+            1 => '☃',
+            _ => (b'a' + rng.below(26) as u8) as char,
+        }).collect()
+    }
+}
+impl<T: Generate> Generate for Vec<T> {
+    fn generate(rng: &mut Rng, size: usize) -> Self {
+        let length = rng.below(size + 1);
+        (0 .. length).map(|_| T::generate(rng, size / 2)).collect()
+    }
+}
+impl<T: Generate, const N: usize> Generate for [T; N] {
+    fn generate(rng: &mut Rng, size: usize) -> Self { std::array::from_fn(|_| T::generate(rng, size)) }
+}
+impl<T: Generate> Generate for Option<T> {
+    fn generate(rng: &mut Rng, size: usize) -> Self {
+        if rng.flip() { Some(T::generate(rng, size)) } else { None }
+    }
+}
+impl<S: Generate, T: Generate> Generate for Result<S, T> {
+    fn generate(rng: &mut Rng, size: usize) -> Self {
+        if rng.flip() { Ok(S::generate(rng, size)) } else { Err(T::generate(rng, size)) }
+    }
+}
+
+macro_rules! generate_tuple {
+    ($($name:ident)+) => (
+        impl<$($name: Generate),*> Generate for ($($name,)*) {
+            fn generate(rng: &mut Rng, size: usize) -> Self { ($($name::generate(rng, size),)*) }
+        }
+    )
+}
+generate_tuple!(A);
+generate_tuple!(A B);
+generate_tuple!(A B C);
+generate_tuple!(A B C D);
+generate_tuple!(A B C D E);
+generate_tuple!(A B C D E F);
+generate_tuple!(A B C D E F G);
+generate_tuple!(A B C D E F G H);
+
+/// Generates `rows` random rows, and returns them both as a container and as a `Vec<T>`.
+pub fn container<T: Columnar + Generate>(rng: &mut Rng, rows: usize, size: usize) -> (T::Container, Vec<T>) {
+    let oracle: Vec<T> = (0 .. rows).map(|_| T::generate(rng, size)).collect();
+    let mut container: T::Container = Default::default();
+    for row in oracle.iter() {
+        container.push(row);
+    }
+    (container, oracle)
+}
+
+/// Calls `property` with `cases` random containers and their oracles, of increasing size.
+///
+/// Each case is generated from its own seed, derived from `seed` and the case number,
+/// which is reported if `property` panics so that the case can be regenerated with `container`.
+/// The panic is raised again with the case, its seed, and the number and size of its rows.
+pub fn check<T, P>(seed: u64, cases: usize, mut property: P)
+where
+    T: Columnar + Generate,
+    P: FnMut(&T::Container, &[T]),
+{
+    for case in 0 .. cases {
+        let case_seed = Rng::new(seed ^ case as u64).next_u64();
+        let mut rng = Rng::new(case_seed);
+        let (rows, size) = (case, 1 + case / 4);
+        let (container, oracle) = container::<T>(&mut rng, rows, size);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| property(&container, &oracle)));
+        if let Err(payload) = result {
+            let msg = payload.downcast_ref::<&str>().copied().or(payload.downcast_ref::<String>().map(String::as_str)).unwrap_or("non-string panic payload");
+            panic!("property failed for case {case} (seed {case_seed}, {rows} rows of size {size}): {msg}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use crate::{Columnar, Container, Index, Len};
+
+    #[test]
+    fn oracles_agree() {
+        type Row = (u64, Option<String>, Vec<Result<i32, Vec<bool>>>);
+        super::check::<Row, _>(0, 100, |container, oracle| {
+            let borrowed = Container::<Row>::borrow(container);
+            assert_eq!(borrowed.len(), oracle.len());
+            for (index, row) in oracle.iter().enumerate() {
+                assert_eq!(&Row::into_owned(borrowed.get(index)), row);
+            }
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "property failed for case 3 (seed 2092789425003139053, 3 rows of size 1): too long")]
+    fn failures_report_case() {
+        super::check::<u64, _>(0, 10, |_container, oracle| assert!(oracle.len() < 3, "too long"));
+    }
+}
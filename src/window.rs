@@ -0,0 +1,207 @@
+//! Rolling-window aggregation over columns of primitive values.
+//!
+//! Each kernel produces one result for each input row, aggregating the values of the rows in
+//! the window ending at that row. Windows extend backwards either by a number of rows, or by a
+//! span of an ordered timestamp column. Each kernel makes a single pass: sums are maintained
+//! by adding entering values and subtracting departing values, and minima and maxima by a
+//! queue of the rows that could yet be the extreme value of some window.
+
+use std::collections::VecDeque;
+
+/// The rows that precede and include each row in its window.
+#[derive(Copy, Clone, Debug)]
+pub enum Window<'a> {
+    /// The row and at most this many minus one rows before it.
+    Rows(usize),
+    /// The rows whose timestamps are greater than the row's timestamp minus `width`.
+    ///
+    /// The timestamps must be non-decreasing, and have one entry for each row of values.
+    Span {
+        /// The timestamp of each row.
+        timestamps: &'a [u64],
+        /// The extent of each window, in units of the timestamps.
+        width: u64,
+    },
+}
+
+impl Window<'_> {
+    /// The first row in the window of `row`, given the first row in the window of `row - 1`.
+    #[inline(always)] fn start(&self, row: usize, mut start: usize) -> usize {
+        match self {
+            Window::Rows(count) => (row + 1).saturating_sub(*count).max(start),
+            Window::Span { timestamps, width } => {
+                while timestamps[row] - timestamps[start] >= *width { start += 1; }
+                start
+            }
+        }
+    }
+    /// Checks that the window applies to `rows` rows.
+    fn validate(&self, rows: usize) {
+        match self {
+            Window::Rows(count) => assert!(*count > 0, "windows must contain at least one row"),
+            Window::Span { timestamps, width } => {
+                assert!(*width > 0, "windows must have a positive width");
+                assert_eq!(timestamps.len(), rows, "timestamps and values have different numbers of rows");
+                assert!(timestamps.windows(2).all(|pair| pair[0] <= pair[1]), "timestamps must be non-decreasing");
+            }
+        }
+    }
+}
+
+/// Primitive values that can be summed as `f64`.
+pub trait Summable: Copy {
+    /// The value as an `f64`, rounded to the nearest representable value if need be.
+    fn to_f64(self) -> f64;
+}
+
+macro_rules! implement_summable {
+    ($($index_type:ty),*) => { $(
+        impl Summable for $index_type {
+            #[inline(always)] fn to_f64(self) -> f64 { self as f64 }
+        }
+    )* }
+}
+implement_summable!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+
+/// The sum of the values in the window of each row.
+///
+/// Sums are accumulated as `f64`, which is exact for integer sums up to 2^53. A window holding
+/// `NaN`, or infinities of both signs, sums to `NaN`, and otherwise a window holding an infinity
+/// sums to it; neither affects the sums of windows that do not hold them.
+pub fn rolling_sum<T: Summable>(values: &[T], window: Window) -> Vec<f64> {
+    rolling_sum_by(values, window, |sum, _rows| sum)
+}
+
+/// The mean of the values in the window of each row.
+///
+/// Non-finite values affect only the means of the windows holding them, as for `rolling_sum`.
+pub fn rolling_mean<T: Summable>(values: &[T], window: Window) -> Vec<f64> {
+    rolling_sum_by(values, window, |sum, rows| sum / rows as f64)
+}
+
+/// The sum of the values in the window of each row, and its number of rows, passed to `finish`.
+///
+/// Finite values are summed, and non-finite values are counted by kind, so that a non-finite
+/// value stops affecting the sum once it leaves the window.
+fn rolling_sum_by<T: Summable>(values: &[T], window: Window, finish: impl Fn(f64, usize) -> f64) -> Vec<f64> {
+    window.validate(values.len());
+    let mut result = Vec::with_capacity(values.len());
+    let mut start = 0;
+    let mut sum = 0.0;
+    // The numbers of `NaN`, positive infinite, and negative infinite values in the window.
+    let mut counts = [0usize; 3];
+    let kind = |value: f64| if value.is_nan() { 0 } else if value > 0.0 { 1 } else { 2 };
+    for row in 0 .. values.len() {
+        let value = values[row].to_f64();
+        if value.is_finite() { sum += value; } else { counts[kind(value)] += 1; }
+        let next = window.start(row, start);
+        for value in values[start .. next].iter().map(|value| value.to_f64()) {
+            if value.is_finite() { sum -= value; } else { counts[kind(value)] -= 1; }
+        }
+        start = next;
+        // Finite values may sum past `f64::MAX`, which would not be undone by their departure.
+        if !sum.is_finite() {
+            sum = values[start ..= row].iter().map(|value| value.to_f64()).filter(|value| value.is_finite()).sum();
+        }
+        let total = match counts {
+            [0, 0, 0] => sum,
+            [0, _, 0] => f64::INFINITY,
+            [0, 0, _] => f64::NEG_INFINITY,
+            _ => f64::NAN,
+        };
+        result.push(finish(total, row + 1 - start));
+    }
+    result
+}
+
+/// The least value in the window of each row.
+///
+/// Of equal values, the latest is reported; values that are incomparable (e.g. `NaN`) may be
+/// reported or passed over depending on their position.
+pub fn rolling_min<T: Copy + PartialOrd>(values: &[T], window: Window) -> Vec<T> {
+    rolling_extreme(values, window, |x, y| x <= y)
+}
+
+/// The greatest value in the window of each row.
+///
+/// Of equal values, the latest is reported; values that are incomparable (e.g. `NaN`) may be
+/// reported or passed over depending on their position.
+pub fn rolling_max<T: Copy + PartialOrd>(values: &[T], window: Window) -> Vec<T> {
+    rolling_extreme(values, window, |x, y| x >= y)
+}
+
+/// The extreme value in the window of each row, where `dominates(x, y)` if a later `x` means
+/// an earlier `y` can no longer be extreme.
+fn rolling_extreme<T: Copy>(values: &[T], window: Window, dominates: impl Fn(&T, &T) -> bool) -> Vec<T> {
+    window.validate(values.len());
+    let mut result = Vec::with_capacity(values.len());
+    // Rows in increasing order, whose values no later row in the queue dominates.
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    let mut start = 0;
+    for row in 0 .. values.len() {
+        while queue.back().is_some_and(|back| dominates(&values[row], &values[*back])) { queue.pop_back(); }
+        queue.push_back(row);
+        start = window.start(row, start);
+        while queue.front().is_some_and(|front| *front < start) { queue.pop_front(); }
+        result.push(values[queue[0]]);
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+
+    use super::Window;
+
+    #[test]
+    fn rolling() {
+        let values: Vec<i32> = (0 .. 200).map(|i| (i * 7919) % 101 - 50).collect();
+        let timestamps: Vec<u64> = (0 .. 200).map(|i| i * 3 / 2 + (i % 11 == 0) as u64 * 5).scan(0, |max, t| { *max = t.max(*max); Some(*max) }).collect();
+        let windows = [Window::Rows(1), Window::Rows(7), Window::Rows(1000), Window::Span { timestamps: &timestamps, width: 10 }];
+        for window in windows {
+            let starts: Vec<usize> = (0 .. values.len()).map(|row| match window {
+                Window::Rows(count) => (row + 1).saturating_sub(count),
+                Window::Span { timestamps, width } => (0 ..= row).find(|start| timestamps[row] - timestamps[*start] < width).unwrap(),
+            }).collect();
+            let sums = super::rolling_sum(&values, window);
+            let means = super::rolling_mean(&values, window);
+            let mins = super::rolling_min(&values, window);
+            let maxs = super::rolling_max(&values, window);
+            for (row, start) in starts.iter().enumerate() {
+                let rows = &values[*start ..= row];
+                let sum = rows.iter().sum::<i32>();
+                assert_eq!(sums[row], sum as f64);
+                assert_eq!(means[row], sum as f64 / rows.len() as f64);
+                assert_eq!(mins[row], *rows.iter().min().unwrap());
+                assert_eq!(maxs[row], *rows.iter().max().unwrap());
+            }
+        }
+    }
+    #[test]
+    fn non_finite() {
+        let values = [1.0, f64::NAN, 2.0, 3.0, f64::INFINITY, 4.0, f64::NEG_INFINITY, 5.0, 6.0];
+        let sums = super::rolling_sum(&values, Window::Rows(2));
+        assert!(sums[1].is_nan() && sums[2].is_nan());
+        assert_eq!(sums[3], 5.0);
+        assert_eq!(sums[4 .. 6], [f64::INFINITY, f64::INFINITY]);
+        assert_eq!(sums[6 ..], [f64::NEG_INFINITY, f64::NEG_INFINITY, 11.0]);
+        assert!(super::rolling_sum(&values, Window::Rows(3))[6].is_nan());
+        let means = super::rolling_mean(&values, Window::Rows(2));
+        assert_eq!(means[8], 5.5);
+
+        // Finite values whose sum overflows, and then leave the window.
+        let values = [f64::MAX, f64::MAX, -f64::MAX, 1.0];
+        assert_eq!(super::rolling_sum(&values, Window::Rows(2)), [f64::MAX, f64::INFINITY, 0.0, -f64::MAX]);
+    }
+
+    #[test]
+    fn integers() {
+        let counters: Vec<u64> = (0 .. 10).map(|i| 1 << 40 | i).collect();
+        let sums = super::rolling_sum(&counters, Window::Rows(3));
+        assert_eq!(sums[9], (3 * (1u64 << 40) + 7 + 8 + 9) as f64);
+        let offsets: Vec<usize> = (0 .. 10).collect();
+        assert_eq!(super::rolling_mean(&offsets, Window::Rows(4))[9], 7.5);
+        let deltas: Vec<i64> = vec![-5, 5, i64::MIN, 0];
+        assert_eq!(super::rolling_sum(&deltas, Window::Rows(1))[2], i64::MIN as f64);
+    }
+}